@@ -2,6 +2,7 @@ use anyhow::{Result, bail};
 use greentic_config::ConfigResolver;
 use greentic_config_types::{GreenticConfig, ServiceTransportConfig};
 use greentic_types::EnvId;
+use gsm_core::{PlannerPolicies, ProviderCapsOverrides, RenderModeConfig};
 use std::fs;
 use std::path::PathBuf;
 
@@ -16,6 +17,15 @@ pub struct EgressConfig {
     pub runner_http_url: Option<String>,
     pub runner_http_api_key: Option<String>,
     pub install_store_path: Option<PathBuf>,
+    /// Per-tenant choice between the render planner and legacy rendering.
+    pub render_modes: RenderModeConfig,
+    /// Planner rules per tenant and provider.
+    pub planner_policies: PlannerPolicies,
+    /// Capabilities that replace what packs declare for a provider.
+    pub provider_caps: ProviderCapsOverrides,
+    /// Directory shared with the gateway for Telegram postback tokens; `None` keeps them in
+    /// memory, where ingress cannot resolve them.
+    pub postback_token_dir: Option<PathBuf>,
 }
 
 impl EgressConfig {
@@ -53,6 +63,10 @@ impl EgressConfig {
             runner_http_url: runner_http_url_from_config(config),
             runner_http_api_key: None,
             install_store_path: install_store_path(config),
+            render_modes: render_modes_from_config(config)?,
+            planner_policies: planner_policies_from_config(config)?,
+            provider_caps: provider_caps_from_config(config)?,
+            postback_token_dir: Some(config.paths.state_dir.join("postback-tokens")),
         })
     }
 }
@@ -114,6 +128,42 @@ fn install_store_path(config: &GreenticConfig) -> Option<PathBuf> {
     path.exists().then_some(path)
 }
 
+fn render_modes_from_config(config: &GreenticConfig) -> Result<RenderModeConfig> {
+    let path = config
+        .paths
+        .greentic_root
+        .join(".greentic/render_modes.json");
+    if path.exists() {
+        RenderModeConfig::load(&path)
+    } else {
+        Ok(RenderModeConfig::default())
+    }
+}
+
+fn planner_policies_from_config(config: &GreenticConfig) -> Result<PlannerPolicies> {
+    let path = config
+        .paths
+        .greentic_root
+        .join(".greentic/planner_policies.json");
+    if path.exists() {
+        PlannerPolicies::load(&path)
+    } else {
+        Ok(PlannerPolicies::default())
+    }
+}
+
+fn provider_caps_from_config(config: &GreenticConfig) -> Result<ProviderCapsOverrides> {
+    let path = config
+        .paths
+        .greentic_root
+        .join(".greentic/provider_caps.json");
+    if path.exists() {
+        ProviderCapsOverrides::load(&path)
+    } else {
+        Ok(ProviderCapsOverrides::default())
+    }
+}
+
 fn install_store_path_from_file(path: PathBuf) -> Option<PathBuf> {
     let raw = fs::read_to_string(&path).ok()?;
     let trimmed = raw.trim();
@@ -4,22 +4,77 @@ use async_nats::jetstream::{
     stream::RetentionPolicy,
 };
 use futures::StreamExt;
+use gsm_core::messaging_card::{DirPostbackTokenStore, MessageCardEngine};
 use gsm_core::{
     AdapterDescriptor, AdapterRegistry, DefaultAdapterPacksConfig, HttpRunnerClient,
-    InMemoryProviderInstallStore, LoggingRunnerClient, OutMessage, ProviderInstallError,
-    ProviderInstallState, ProviderInstallStore, RunnerClient, apply_install_refs,
+    InMemoryProviderInstallStore, IntentDelivery, LoggingRunnerClient, OutMessage,
+    ProviderCapsRegistry, ProviderExtensionsRegistry, ProviderInstallError, ProviderInstallState,
+    ProviderInstallStore, RunnerClient, apply_install_refs, apply_intent, attach_render_plan,
     default_adapter_pack_paths, extract_provider_route, load_install_store_from_path,
-    shared_client,
+    load_provider_extensions_from_pack_files, shared_client,
 };
 use metrics::counter;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 use tracing::{error, info, warn};
 
 use crate::adapter_registry::AdapterLookup;
 use crate::config::EgressConfig;
 use gsm_bus::{BusClient, NatsBusClient, to_value};
 
+static CARD_ENGINE: OnceLock<MessageCardEngine> = OnceLock::new();
+
+/// Engine shared by every message. `run` builds it with the renderers packs declare; this
+/// falls back to the built-in renderers when it was not set.
+fn card_engine(config: &EgressConfig) -> &'static MessageCardEngine {
+    CARD_ENGINE.get_or_init(|| {
+        build_card_engine(
+            config,
+            &ProviderExtensionsRegistry::default(),
+            Path::new(&config.packs_root),
+        )
+    })
+}
+
+/// Telegram postback tokens go to the directory the gateway resolves them from; pack renderer
+/// components are resolved under `packs_root`.
+fn build_card_engine(
+    config: &EgressConfig,
+    extensions: &ProviderExtensionsRegistry,
+    packs_root: &Path,
+) -> MessageCardEngine {
+    let engine = MessageCardEngine::bootstrap();
+    let engine = match &config.postback_token_dir {
+        Some(dir) => engine.with_postback_token_store(Arc::new(DirPostbackTokenStore::new(dir))),
+        None => engine,
+    };
+    with_pack_renderers(engine, extensions, packs_root)
+}
+
+#[cfg(feature = "pack-renderers")]
+fn with_pack_renderers(
+    engine: MessageCardEngine,
+    extensions: &ProviderExtensionsRegistry,
+    packs_root: &Path,
+) -> MessageCardEngine {
+    engine.with_pack_renderers(extensions, packs_root)
+}
+
+#[cfg(not(feature = "pack-renderers"))]
+fn with_pack_renderers(
+    engine: MessageCardEngine,
+    extensions: &ProviderExtensionsRegistry,
+    _packs_root: &Path,
+) -> MessageCardEngine {
+    if !extensions.renderers.is_empty() {
+        warn!(
+            platforms = ?extensions.renderers.keys().collect::<Vec<_>>(),
+            "packs declare renderers but egress was built without the pack-renderers feature"
+        );
+    }
+    engine
+}
+
 pub async fn run() -> Result<()> {
     let config = EgressConfig::load()?;
     gsm_core::set_current_env(config.env.clone());
@@ -38,6 +93,26 @@ pub async fn run() -> Result<()> {
             AdapterRegistry::default()
         });
     let adapters = AdapterLookup::new(&registry);
+    let extensions = load_provider_extensions_from_pack_files(packs_root.as_path(), &pack_paths)
+        .unwrap_or_else(|err| {
+            warn!(error = %err, "failed to load provider extensions; using adapter features only");
+            Default::default()
+        });
+    let caps = ProviderCapsRegistry::from_packs(&registry, &extensions, &config.provider_caps);
+    if CARD_ENGINE
+        .set(build_card_engine(
+            &config,
+            &extensions,
+            packs_root.as_path(),
+        ))
+        .is_err()
+    {
+        warn!("card engine already initialised; pack renderers not loaded");
+    }
+    info!(
+        providers = caps.records().len(),
+        "provider capabilities loaded"
+    );
     let bus = NatsBusClient::new(client.clone());
     let runner_client: Arc<dyn RunnerClient> = match &config.runner_http_url {
         Some(url) => shared_client(HttpRunnerClient::new(
@@ -107,6 +182,7 @@ pub async fn run() -> Result<()> {
                     &*runner_client,
                     &config,
                     install_store.as_ref(),
+                    &caps,
                 )
                 .await
                 {
@@ -133,6 +209,7 @@ async fn process_message(
     runner: &dyn RunnerClient,
     config: &EgressConfig,
     install_store: &dyn ProviderInstallStore,
+    caps: &ProviderCapsRegistry,
 ) -> Result<(), Error> {
     let out: OutMessage = serde_json::from_slice(&msg.payload)?;
     info!(
@@ -157,7 +234,7 @@ async fn process_message(
         platform = %out.platform.as_str(),
         "resolved egress adapter"
     );
-    process_message_internal(&out, &adapter, bus, runner, config, &install_state).await
+    process_message_internal(&out, &adapter, bus, runner, config, &install_state, caps).await
 }
 
 /// Internal helper used by tests to avoid NATS.
@@ -168,9 +245,60 @@ pub async fn process_message_internal(
     runner: &dyn RunnerClient,
     config: &EgressConfig,
     install_state: &ProviderInstallState,
+    caps: &ProviderCapsRegistry,
 ) -> Result<(), Error> {
     let mut routed = out.clone();
     apply_install_refs(&mut routed.meta, &install_state.record);
+    let mode = config.render_modes.mode_for(&out.tenant);
+    match attach_render_plan(
+        &mut routed,
+        mode,
+        &adapter.name,
+        card_engine(config),
+        caps,
+        caps.get_caps(out.platform.as_str()),
+        config
+            .planner_policies
+            .policy_for(&out.tenant, &adapter.name),
+    ) {
+        Ok(Some(outcome)) => {
+            let _ = counter!(
+                "messaging_egress_render_planned_total",
+                "tenant" => out.tenant.clone(),
+                "platform" => out.platform.as_str().to_string(),
+                "tier" => outcome.tier().as_str()
+            );
+        }
+        Ok(None) => {}
+        Err(err) => warn!(
+            tenant = %out.tenant,
+            platform = %out.platform.as_str(),
+            error = %err,
+            "render planning failed; sending card without a plan"
+        ),
+    }
+    let intent_caps = caps
+        .get_caps_v2(&adapter.name)
+        .or_else(|| caps.get_caps_v2(out.platform.as_str()))
+        .cloned()
+        .unwrap_or_default();
+    if let Some(plan) = apply_intent(&mut routed, &intent_caps) {
+        let _ = counter!(
+            "messaging_egress_intent_total",
+            "tenant" => out.tenant.clone(),
+            "platform" => out.platform.as_str().to_string(),
+            "delivery" => plan.delivery.as_str()
+        );
+        if plan.delivery == IntentDelivery::Skip {
+            info!(
+                tenant = %out.tenant,
+                platform = %out.platform.as_str(),
+                adapter = %adapter.name,
+                "outbound intent not supported by provider; nothing to send"
+            );
+            return Ok(());
+        }
+    }
     if let Err(err) = runner.invoke_adapter(&routed, adapter).await {
         let _ = counter!(
             "messaging_egress_runner_failure_total",
@@ -341,8 +469,7 @@ mod tests {
             thread_id: None,
             kind: gsm_core::OutKind::Text,
             text: Some("hi".into()),
-            message_card: None,
-            adaptive_card: None,
+            card: None,
             meta,
         };
 
@@ -368,8 +495,7 @@ mod tests {
             thread_id: None,
             kind: gsm_core::OutKind::Text,
             text: Some("hi".into()),
-            message_card: None,
-            adaptive_card: None,
+            card: None,
             meta,
         };
 
@@ -395,8 +521,7 @@ mod tests {
             thread_id: None,
             kind: gsm_core::OutKind::Text,
             text: Some("hi".into()),
-            message_card: None,
-            adaptive_card: None,
+            card: None,
             meta,
         };
 
@@ -422,8 +547,7 @@ mod tests {
             thread_id: None,
             kind: gsm_core::OutKind::Text,
             text: Some("hi".into()),
-            message_card: None,
-            adaptive_card: None,
+            card: None,
             meta,
         };
 
@@ -68,8 +68,7 @@ async fn http_runner_client_posts_invocation() {
         thread_id: None,
         kind: OutKind::Text,
         text: Some("hi".into()),
-        message_card: None,
-        adaptive_card: None,
+        card: None,
         meta: Default::default(),
     };
 
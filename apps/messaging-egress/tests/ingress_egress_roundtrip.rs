@@ -4,7 +4,7 @@ use std::sync::Arc;
 use gsm_bus::InMemoryBusClient;
 use gsm_core::{
     AdapterDescriptor, ChannelMessage, DefaultAdapterPacksConfig, InMemoryProviderInstallStore,
-    LoggingRunnerClient, MessagingAdapterKind, OutKind, OutMessage, Platform,
+    LoggingRunnerClient, MessagingAdapterKind, OutKind, OutMessage, Platform, ProviderCapsRegistry,
     ProviderExtensionsRegistry, ProviderInstallState, ProviderInstallStore, make_tenant_ctx,
 };
 use gsm_egress::adapter_registry::AdapterLookup;
@@ -28,6 +28,8 @@ fn test_gateway_config() -> GatewayConfig {
         default_packs: DefaultAdapterPacksConfig::default(),
         extra_pack_paths: Vec::new(),
         install_store_path: None,
+        batch: Default::default(),
+        postback_token_dir: None,
     }
 }
 
@@ -111,6 +113,9 @@ async fn ingress_to_egress_round_trip_over_in_memory_bus() {
         install_store,
         workers: std::collections::BTreeMap::new(),
         worker_default: None,
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
     });
 
     let payload = NormalizedRequest {
@@ -127,7 +132,7 @@ async fn ingress_to_egress_round_trip_over_in_memory_bus() {
         Some("team".into()),
         "slack".into(),
         state,
-        payload,
+        serde_json::to_vec(&payload).unwrap().into(),
         Default::default(),
     )
     .await
@@ -166,8 +171,7 @@ async fn ingress_to_egress_round_trip_over_in_memory_bus() {
         thread_id: None,
         kind: OutKind::Text,
         text: Some(text.to_string()),
-        message_card: None,
-        adaptive_card: None,
+        card: None,
         meta,
     };
 
@@ -181,6 +185,10 @@ async fn ingress_to_egress_round_trip_over_in_memory_bus() {
         runner_http_url: None,
         runner_http_api_key: None,
         install_store_path: None,
+        render_modes: Default::default(),
+        planner_policies: Default::default(),
+        provider_caps: Default::default(),
+        postback_token_dir: None,
     };
 
     let runner = LoggingRunnerClient;
@@ -193,9 +201,17 @@ async fn ingress_to_egress_round_trip_over_in_memory_bus() {
         .config
         .insert("config".into(), serde_json::json!({"ok": true}));
 
-    process_message_internal(&out, &adapter, bus.as_ref(), &runner, &cfg, &install_state)
-        .await
-        .unwrap();
+    process_message_internal(
+        &out,
+        &adapter,
+        bus.as_ref(),
+        &runner,
+        &cfg,
+        &install_state,
+        &ProviderCapsRegistry::new(),
+    )
+    .await
+    .unwrap();
 
     let published = bus.take_published().await;
     assert_eq!(published.len(), 1);
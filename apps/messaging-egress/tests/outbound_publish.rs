@@ -47,12 +47,37 @@ async fn publishes_outbound_payload_via_bus() {
         thread_id: None,
         kind: OutKind::Text,
         text: Some("hi".into()),
-        message_card: None,
-        adaptive_card: None,
+        card: None,
         meta,
     };
 
-    let cfg = gsm_egress::config::EgressConfig {
+    let cfg = egress_config();
+
+    let runner = LoggingRunnerClient;
+    let install_state = install_state("install-a");
+
+    gsm_egress::process_message_internal(
+        &out,
+        &adapter,
+        &bus,
+        &runner,
+        &cfg,
+        &install_state,
+        &gsm_core::ProviderCapsRegistry::new(),
+    )
+    .await
+    .unwrap();
+
+    let published = bus.take_published().await;
+    assert_eq!(published.len(), 1);
+    let (subject, payload) = &published[0];
+    assert!(subject.contains("greentic.messaging.egress.dev.acme.team.slack"));
+    assert_eq!(payload["text"], "hi");
+    assert_eq!(payload["adapter"], "slack-main");
+}
+
+fn egress_config() -> gsm_egress::config::EgressConfig {
+    gsm_egress::config::EgressConfig {
         env: "dev".try_into().unwrap(),
         nats_url: "nats://localhost".into(),
         subject_filter: "greentic.messaging.egress.dev.>".into(),
@@ -62,21 +87,104 @@ async fn publishes_outbound_payload_via_bus() {
         runner_http_url: None,
         runner_http_api_key: None,
         install_store_path: None,
+        render_modes: Default::default(),
+        planner_policies: Default::default(),
+        provider_caps: Default::default(),
+        postback_token_dir: None,
+    }
+}
+
+#[tokio::test]
+async fn unsupported_typing_intent_is_not_sent() {
+    let mut registry = gsm_core::AdapterRegistry::default();
+    registry.register(adapter("slack-main")).unwrap();
+    let lookup = AdapterLookup::new(&registry);
+    let adapter = lookup
+        .default_for_platform(Platform::Slack.as_str())
+        .unwrap();
+    let bus = InMemoryBusClient::default();
+    let out = OutMessage {
+        ctx: make_tenant_ctx("acme".into(), Some("team".into()), None),
+        tenant: "acme".into(),
+        platform: Platform::Slack,
+        chat_id: "C123".into(),
+        thread_id: None,
+        kind: OutKind::Text,
+        text: None,
+        card: None,
+        meta: BTreeMap::from([(
+            gsm_core::INTENT_META_KEY.to_string(),
+            serde_json::json!({"type": "typing"}),
+        )]),
     };
 
-    let runner = LoggingRunnerClient;
-    let install_state = install_state("install-a");
+    gsm_egress::process_message_internal(
+        &out,
+        &adapter,
+        &bus,
+        &LoggingRunnerClient,
+        &egress_config(),
+        &install_state("install-a"),
+        &gsm_core::ProviderCapsRegistry::new(),
+    )
+    .await
+    .unwrap();
+
+    assert!(bus.take_published().await.is_empty());
+}
 
-    gsm_egress::process_message_internal(&out, &adapter, &bus, &runner, &cfg, &install_state)
-        .await
+#[tokio::test]
+async fn ephemeral_reply_degraded_to_direct_message_goes_to_the_user() {
+    let mut registry = gsm_core::AdapterRegistry::default();
+    registry.register(adapter("slack-main")).unwrap();
+    let lookup = AdapterLookup::new(&registry);
+    let adapter = lookup
+        .default_for_platform(Platform::Slack.as_str())
         .unwrap();
+    let mut caps = gsm_core::ProviderCapsRegistry::new();
+    caps.register_provider_v2(
+        "slack-main",
+        "1.0.0",
+        gsm_core::CapsSource::Override,
+        gsm_core::ProviderCapabilitiesV2 {
+            supports_direct_messages: true,
+            ..Default::default()
+        },
+        None,
+    );
+    let bus = InMemoryBusClient::default();
+    let out = OutMessage {
+        ctx: make_tenant_ctx("acme".into(), Some("team".into()), None),
+        tenant: "acme".into(),
+        platform: Platform::Slack,
+        chat_id: "C123".into(),
+        thread_id: None,
+        kind: OutKind::Text,
+        text: None,
+        card: None,
+        meta: BTreeMap::from([(
+            gsm_core::INTENT_META_KEY.to_string(),
+            serde_json::json!({"type": "ephemeral_reply", "user_id": "U42", "text": "psst"}),
+        )]),
+    };
+
+    gsm_egress::process_message_internal(
+        &out,
+        &adapter,
+        &bus,
+        &LoggingRunnerClient,
+        &egress_config(),
+        &install_state("install-a"),
+        &caps,
+    )
+    .await
+    .unwrap();
 
     let published = bus.take_published().await;
     assert_eq!(published.len(), 1);
-    let (subject, payload) = &published[0];
-    assert!(subject.contains("greentic.messaging.egress.dev.acme.team.slack"));
-    assert_eq!(payload["text"], "hi");
-    assert_eq!(payload["adapter"], "slack-main");
+    let (_, payload) = &published[0];
+    assert_eq!(payload["chat_id"], "U42");
+    assert_eq!(payload["text"], "psst");
 }
 
 fn install_state(install_id: &str) -> ProviderInstallState {
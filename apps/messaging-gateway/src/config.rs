@@ -1,6 +1,6 @@
 use std::fs;
 use std::net::{IpAddr, SocketAddr};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::{Context, Result, bail};
@@ -8,6 +8,7 @@ use greentic_config::ConfigResolver;
 use greentic_config_types::{GreenticConfig, ServiceTransportConfig};
 use greentic_types::EnvId;
 use gsm_core::DefaultAdapterPacksConfig;
+use serde::Deserialize;
 
 #[derive(Debug, Clone)]
 pub struct GatewayConfig {
@@ -22,6 +23,46 @@ pub struct GatewayConfig {
     pub default_packs: DefaultAdapterPacksConfig,
     pub extra_pack_paths: Vec<PathBuf>,
     pub install_store_path: Option<PathBuf>,
+    pub batch: BatchIngestConfig,
+    /// Directory egress writes Telegram postback tokens to; `pb:` callback data is resolved
+    /// from it on ingress.
+    pub postback_token_dir: Option<PathBuf>,
+}
+
+/// Limits for `POST /api/{tenant}/{team}/{channel}/batch`, read from
+/// `.greentic/gateway_batch.json` when present.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct BatchIngestConfig {
+    /// Largest number of items accepted in one request.
+    pub max_items: usize,
+    /// Number of items published at the same time.
+    pub concurrency: usize,
+}
+
+impl Default for BatchIngestConfig {
+    fn default() -> Self {
+        Self {
+            max_items: 500,
+            concurrency: 8,
+        }
+    }
+}
+
+impl BatchIngestConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read batch config from {}", path.display()))?;
+        let config: Self = serde_json::from_str(&raw)
+            .with_context(|| format!("invalid batch config {}", path.display()))?;
+        if config.max_items == 0 || config.concurrency == 0 {
+            bail!(
+                "batch config {}: max_items and concurrency must be positive",
+                path.display()
+            );
+        }
+        Ok(config)
+    }
 }
 
 impl GatewayConfig {
@@ -56,6 +97,8 @@ impl GatewayConfig {
             default_packs: DefaultAdapterPacksConfig::default(),
             extra_pack_paths: Vec::new(),
             install_store_path: install_store_path(config),
+            batch: batch_ingest_from_config(config)?,
+            postback_token_dir: Some(config.paths.state_dir.join("postback-tokens")),
         })
     }
 }
@@ -93,6 +136,18 @@ fn nats_settings(config: &GreenticConfig) -> Result<Option<(String, Option<Strin
     }
 }
 
+fn batch_ingest_from_config(config: &GreenticConfig) -> Result<BatchIngestConfig> {
+    let path = config
+        .paths
+        .greentic_root
+        .join(".greentic/gateway_batch.json");
+    if path.exists() {
+        BatchIngestConfig::load(&path)
+    } else {
+        Ok(BatchIngestConfig::default())
+    }
+}
+
 fn install_store_path(config: &GreenticConfig) -> Option<PathBuf> {
     let root = &config.paths.greentic_root;
     if let Some(path) = install_store_path_from_file(root.join(".greentic/install_store.path")) {
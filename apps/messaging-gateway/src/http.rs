@@ -1,11 +1,19 @@
-use std::{collections::BTreeMap, str::FromStr, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    str::FromStr,
+    sync::{Arc, LazyLock},
+};
 
 use axum::{
-    Router, debug_handler,
+    Router,
+    body::Bytes,
+    debug_handler,
     extract::{Extension, Json, Path},
     http::{HeaderMap, StatusCode},
     routing::post,
 };
+use futures::stream::{self, StreamExt};
 use metrics::counter;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -13,13 +21,21 @@ use time::OffsetDateTime;
 use tracing::{Instrument, warn};
 
 use crate::config::GatewayConfig;
+use crate::idempotency::{RAW_BODY_METADATA_KEY, native_event_id};
+use crate::webhook::{DEFAULT_MAX_SKEW_SECS, ReplayCache, WebhookScheme, WebhookVerifier};
 use gsm_bus::{BusClient, BusError, to_value};
 use gsm_core::{
-    AdapterDescriptor, AdapterRegistry, ChannelMessage, Platform, ProviderExtensionsRegistry,
-    ProviderInstallError, ProviderInstallStore, WorkerClient, WorkerRoutingConfig,
-    apply_install_refs, forward_to_worker, infer_platform_from_adapter_name, make_tenant_ctx,
+    AdapterDescriptor, AdapterRegistry, ChannelMessage, DirPostbackTokenStore,
+    InMemoryPostbackTokenStore, MessageCardEngine, Platform, PostbackTokenStore,
+    ProviderExtensionsRegistry, ProviderInstallError, ProviderInstallStore, WorkerClient,
+    WorkerRoutingConfig, apply_install_refs, forward_to_worker, infer_platform_from_adapter_name,
+    make_tenant_ctx,
+    messaging_card::{TelemetryEvent, TelemetryHook},
+    resolve_callback_data,
 };
+use gsm_idempotency::{IdKey, IdempotencyGuard};
 use gsm_telemetry::set_current_tenant_ctx;
+use security::BotFrameworkValidator;
 
 #[derive(Clone)]
 pub struct GatewayState {
@@ -30,6 +46,10 @@ pub struct GatewayState {
     pub install_store: Arc<dyn ProviderInstallStore>,
     pub workers: BTreeMap<String, Arc<dyn WorkerClient>>,
     pub worker_default: Option<WorkerRoutingConfig>,
+    pub replay_cache: Arc<ReplayCache>,
+    pub bot_framework: Option<Arc<BotFrameworkValidator>>,
+    /// Deduplicates ingress by `{tenant, platform, msg_id}`; `None` publishes every request.
+    pub idempotency: Option<IdempotencyGuard>,
 }
 
 impl GatewayState {
@@ -63,6 +83,22 @@ pub struct NormalizedRequest {
 pub struct ApiResponse {
     status: String,
     subject: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    duplicate: bool,
+}
+
+impl ApiResponse {
+    /// Whether the message was already ingested and this request was not published again.
+    pub fn is_duplicate(&self) -> bool {
+        self.duplicate
+    }
+}
+
+/// A request that passed validation: published, or recognised as a duplicate.
+struct Ingested {
+    subject: String,
+    msg_id: String,
+    duplicate: bool,
 }
 
 #[derive(Serialize, Debug)]
@@ -78,6 +114,70 @@ impl ApiError {
     }
 }
 
+fn api_error((status, message): (StatusCode, String)) -> (StatusCode, Json<ApiError>) {
+    (
+        status,
+        Json(ApiError {
+            error: message,
+            code: None,
+        }),
+    )
+}
+
+#[derive(Serialize, Debug)]
+pub struct BatchResponse {
+    pub accepted: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItemResult>,
+}
+
+/// Outcome of one batch item, reported at the item's position in the request.
+#[derive(Serialize, Debug)]
+pub struct BatchItemResult {
+    pub index: usize,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub msg_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub duplicate: bool,
+}
+
+impl BatchItemResult {
+    fn accepted(index: usize, ingested: Ingested) -> Self {
+        Self {
+            index,
+            status: "accepted".into(),
+            subject: Some(ingested.subject),
+            msg_id: Some(ingested.msg_id),
+            error: None,
+            code: None,
+            duplicate: ingested.duplicate,
+        }
+    }
+
+    fn failed(index: usize, error: ApiError) -> Self {
+        Self {
+            index,
+            status: "failed".into(),
+            subject: None,
+            msg_id: None,
+            error: Some(error.error),
+            code: error.code,
+            duplicate: false,
+        }
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        self.status == "accepted"
+    }
+}
+
 fn outbound_to_out_message(
     outbound: gsm_core::OutboundEnvelope,
     platform: Platform,
@@ -120,8 +220,7 @@ fn outbound_to_out_message(
         } else {
             text
         },
-        message_card: None,
-        adaptive_card: None,
+        card: None,
         meta,
     }
 }
@@ -133,6 +232,7 @@ pub async fn build_router_with_bus(
     bus: Arc<dyn BusClient>,
     install_store: Arc<dyn ProviderInstallStore>,
     workers: BTreeMap<String, Arc<dyn WorkerClient>>,
+    idempotency: Option<IdempotencyGuard>,
 ) -> anyhow::Result<Router> {
     let state = Arc::new(GatewayState {
         bus,
@@ -142,6 +242,9 @@ pub async fn build_router_with_bus(
         provider_extensions,
         install_store,
         workers,
+        replay_cache: Arc::default(),
+        bot_framework: Some(Arc::new(BotFrameworkValidator::microsoft())),
+        idempotency,
     });
 
     if state.adapters.is_empty() {
@@ -151,6 +254,7 @@ pub async fn build_router_with_bus(
     let router = Router::new()
         .route("/api/{tenant}/{channel}", post(ingest_without_team))
         .route("/api/{tenant}/{team}/{channel}", post(ingest_with_team))
+        .route("/api/{tenant}/{team}/{channel}/batch", post(ingest_batch))
         .layer(Extension(state));
 
     Ok(router)
@@ -161,9 +265,9 @@ async fn ingest_without_team(
     Path((tenant, channel)): Path<(String, String)>,
     Extension(state): Extension<Arc<GatewayState>>,
     headers: HeaderMap,
-    Json(payload): Json<NormalizedRequest>,
+    body: Bytes,
 ) -> Result<Json<ApiResponse>, (StatusCode, Json<ApiError>)> {
-    handle_ingress(tenant, None, channel, state, payload, headers).await
+    handle_ingress(tenant, None, channel, state, body, headers).await
 }
 
 #[debug_handler]
@@ -171,17 +275,19 @@ async fn ingest_with_team(
     Path((tenant, team, channel)): Path<(String, String, String)>,
     Extension(state): Extension<Arc<GatewayState>>,
     headers: HeaderMap,
-    Json(payload): Json<NormalizedRequest>,
+    body: Bytes,
 ) -> Result<Json<ApiResponse>, (StatusCode, Json<ApiError>)> {
-    handle_ingress(tenant, Some(team), channel, state, payload, headers).await
+    handle_ingress(tenant, Some(team), channel, state, body, headers).await
 }
 
+/// Ingests one `NormalizedRequest`. The body is taken as raw bytes so webhook signatures are
+/// checked over exactly what the provider signed.
 pub async fn handle_ingress(
     tenant: String,
     team_path: Option<String>,
     channel: String,
     state: Arc<GatewayState>,
-    payload: NormalizedRequest,
+    body: Bytes,
     headers: HeaderMap,
 ) -> Result<Json<ApiResponse>, (StatusCode, Json<ApiError>)> {
     let span = tracing::info_span!(
@@ -192,53 +298,244 @@ pub async fn handle_ingress(
     );
     async move {
         let (platform, adapter) =
-            resolve_ingress_target(&channel, &state.adapters).map_err(|(code, message)| {
-                (
-                    code,
-                    Json(ApiError {
-                        error: message,
-                        code: None,
-                    }),
-                )
-            })?;
-
-        let tenant_ctx =
-            make_tenant_ctx(tenant.clone(), team_path.clone(), payload.user_id.clone());
-        let install_state =
-            resolve_install_for_ingress(state.as_ref(), &tenant_ctx, &payload, &channel, &headers)
-                .map_err(map_install_error)?;
-
-        publish(
+            resolve_ingress_target(&channel, &state.adapters).map_err(api_error)?;
+        let (activity, payload) = serde_json::from_slice::<Value>(&body)
+            .map_err(|err| format!("invalid JSON body: {err}"))
+            .and_then(parse_request)
+            .map_err(|message| api_error((StatusCode::BAD_REQUEST, message)))?;
+        let ingested = ingest_resolved(
             &tenant,
             team_path.as_deref(),
+            &channel,
             &platform,
             adapter.as_ref(),
             state.as_ref(),
             payload,
-            &install_state,
+            &activity,
+            SignedBody::Request(&body),
             &headers,
         )
-        .await
-        .map(|subject| {
-            Json(ApiResponse {
-                status: "accepted".into(),
-                subject,
-            })
-        })
-        .map_err(|(code, message)| {
-            (
-                code,
+        .await?;
+        Ok(Json(ApiResponse {
+            status: "accepted".into(),
+            subject: ingested.subject,
+            duplicate: ingested.duplicate,
+        }))
+    }
+    .instrument(span)
+    .await
+}
+
+#[debug_handler]
+async fn ingest_batch(
+    Path((tenant, team, channel)): Path<(String, String, String)>,
+    Extension(state): Extension<Arc<GatewayState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<BatchResponse>, (StatusCode, Json<ApiError>)> {
+    handle_batch_ingress(tenant, Some(team), channel, state, body, headers).await
+}
+
+/// Ingests a JSON array, or an NDJSON stream when the content type says so, of
+/// `NormalizedRequest`s. Each item is resolved and published like a single ingress request,
+/// at most `config.batch.concurrency` at a time, and gets its own result; only a malformed
+/// body, an unknown channel or an oversized batch fail the whole request. The webhook signature
+/// covers the whole body, so it is checked once per install rather than once per item.
+pub async fn handle_batch_ingress(
+    tenant: String,
+    team_path: Option<String>,
+    channel: String,
+    state: Arc<GatewayState>,
+    body: Bytes,
+    headers: HeaderMap,
+) -> Result<Json<BatchResponse>, (StatusCode, Json<ApiError>)> {
+    let span = tracing::info_span!(
+        "ingress_batch",
+        tenant = %tenant,
+        team = team_path.as_deref().unwrap_or(""),
+        channel = %channel
+    );
+    async move {
+        let (platform, adapter) =
+            resolve_ingress_target(&channel, &state.adapters).map_err(api_error)?;
+        let items = parse_batch(&body, &headers).map_err(api_error)?;
+        let limits = &state.config.batch;
+        if items.len() > limits.max_items {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
                 Json(ApiError {
-                    error: message,
-                    code: None,
+                    error: format!(
+                        "batch has {} items; at most {} are accepted",
+                        items.len(),
+                        limits.max_items
+                    ),
+                    code: Some("batch_too_large".into()),
                 }),
-            )
-        })
+            ));
+        }
+
+        let verified = BatchSignatures::default();
+        let results: Vec<BatchItemResult> = stream::iter(items.into_iter().enumerate())
+            .map(|(index, item)| {
+                let (tenant, team_path, channel) = (&tenant, team_path.as_deref(), &channel);
+                let (platform, adapter, state, headers) =
+                    (&platform, adapter.as_ref(), state.as_ref(), &headers);
+                let signed = SignedBody::Batch {
+                    body: &body,
+                    verified: &verified,
+                };
+                async move {
+                    let outcome = match item {
+                        Ok((activity, payload)) => {
+                            ingest_resolved(
+                                tenant, team_path, channel, platform, adapter, state, payload,
+                                &activity, signed, headers,
+                            )
+                            .await
+                        }
+                        Err(message) => Err(api_error((StatusCode::BAD_REQUEST, message))),
+                    };
+                    match outcome {
+                        Ok(ingested) => BatchItemResult::accepted(index, ingested),
+                        Err((_, Json(error))) => BatchItemResult::failed(index, error),
+                    }
+                }
+            })
+            .buffered(limits.concurrency.max(1))
+            .collect()
+            .await;
+
+        let accepted = results.iter().filter(|r| r.is_accepted()).count();
+        let failed = results.len() - accepted;
+        let _ = counter!(
+            "messaging_ingress_batch_total",
+            "tenant" => tenant.clone(),
+            "platform" => platform.as_str().to_string()
+        );
+        if failed > 0 {
+            warn!(accepted, failed, "batch ingress completed with failures");
+        }
+        Ok(Json(BatchResponse {
+            accepted,
+            failed,
+            results,
+        }))
     }
     .instrument(span)
     .await
 }
 
+/// Splits a batch body into items; an item that is not a valid `NormalizedRequest` becomes an
+/// error message for that item.
+fn parse_batch(
+    body: &[u8],
+    headers: &HeaderMap,
+) -> Result<Vec<Result<(Value, NormalizedRequest), String>>, (StatusCode, String)> {
+    let ndjson = headers
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.contains("ndjson") || ct.contains("jsonl"));
+    let parse_item =
+        |value: Value| parse_request(value).map_err(|err| format!("invalid item: {err}"));
+    if ndjson {
+        let body = std::str::from_utf8(body).map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                "batch body is not UTF-8".to_string(),
+            )
+        })?;
+        return Ok(body
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str::<Value>(line)
+                    .map_err(|err| format!("invalid JSON line: {err}"))
+                    .and_then(parse_item)
+            })
+            .collect());
+    }
+    let items: Vec<Value> = serde_json::from_slice(body).map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("batch body must be a JSON array: {err}"),
+        )
+    })?;
+    Ok(items.into_iter().map(parse_item).collect())
+}
+
+/// Parses one item, keeping the object as sent: a Teams activity's `serviceUrl` is read from it,
+/// and it is forwarded in `metadata.raw` so provider event ids can be found.
+fn parse_request(value: Value) -> Result<(Value, NormalizedRequest), String> {
+    let mut request = NormalizedRequest::deserialize(&value).map_err(|err| err.to_string())?;
+    request
+        .metadata
+        .entry(RAW_BODY_METADATA_KEY.to_string())
+        .or_insert_with(|| value.clone());
+    Ok((value, request))
+}
+
+/// The bytes a request's webhook signature covers.
+#[derive(Clone, Copy)]
+enum SignedBody<'a> {
+    /// The request carried a single item.
+    Request(&'a [u8]),
+    /// The item is part of a batch; `verified` holds the outcome per install.
+    Batch {
+        body: &'a [u8],
+        verified: &'a BatchSignatures,
+    },
+}
+
+/// Signature checks of one batch request, keyed by `{provider_id}/{install_id}`.
+#[derive(Default)]
+struct BatchSignatures(std::sync::Mutex<BTreeMap<String, Result<bool, ProviderInstallError>>>);
+
+/// Resolves the install for one request on an already resolved channel and publishes it.
+#[allow(clippy::too_many_arguments)]
+async fn ingest_resolved(
+    tenant: &str,
+    team_path: Option<&str>,
+    channel: &str,
+    platform: &Platform,
+    adapter: Option<&AdapterDescriptor>,
+    state: &GatewayState,
+    payload: NormalizedRequest,
+    activity: &Value,
+    signed: SignedBody<'_>,
+    headers: &HeaderMap,
+) -> Result<Ingested, (StatusCode, Json<ApiError>)> {
+    let tenant_ctx = make_tenant_ctx(
+        tenant.to_string(),
+        team_path.map(str::to_string),
+        payload.user_id.clone(),
+    );
+    let (install_state, replayed) = resolve_install_for_ingress(
+        state,
+        &tenant_ctx,
+        &payload,
+        channel,
+        activity,
+        signed,
+        headers,
+    )
+    .await
+    .map_err(map_install_error)?;
+    publish(
+        tenant,
+        team_path,
+        platform,
+        adapter,
+        state,
+        payload,
+        &install_state,
+        replayed,
+        headers,
+    )
+    .await
+}
+
+/// Publishes a verified request. `replayed` marks a signature already seen: a provider retry of
+/// a message the idempotency guard knows is answered as a duplicate, anything else is refused.
 #[allow(clippy::too_many_arguments)]
 async fn publish(
     tenant: &str,
@@ -248,12 +545,13 @@ async fn publish(
     state: &GatewayState,
     payload: NormalizedRequest,
     install_state: &gsm_core::ProviderInstallState,
+    replayed: bool,
     headers: &HeaderMap,
-) -> Result<String, (StatusCode, String)> {
+) -> Result<Ingested, (StatusCode, Json<ApiError>)> {
     let chat_id = payload
         .chat_id
         .clone()
-        .ok_or_else(|| (StatusCode::BAD_REQUEST, "chat_id is required".into()))?;
+        .ok_or_else(|| api_error((StatusCode::BAD_REQUEST, "chat_id is required".into())))?;
 
     let team = sanitize_team(team_path, &state.config.default_team);
     let user_id = payload.user_id.or_else(|| {
@@ -270,11 +568,17 @@ async fn publish(
         .format(&time::format_description::well_known::Rfc3339)
         .unwrap_or_else(|_| now.unix_timestamp().to_string());
 
-    let msg_id = payload
+    let provided_id = payload
         .msg_id
-        .unwrap_or_else(|| format!("gw:{}", now.unix_timestamp_nanos()));
+        .or_else(|| native_event_id(platform, &payload.metadata));
+    let deduplicate = provided_id.is_some();
+    let msg_id = provided_id.unwrap_or_else(|| format!("gw:{}", now.unix_timestamp_nanos()));
 
     let mut context = payload.metadata;
+    if *platform == Platform::Telegram {
+        resolve_telegram_postback(&mut context, state.config.postback_token_dir.as_deref());
+    }
+    record_card_interaction(platform.as_str(), &mut context);
     if let Some(adapter) = adapter {
         context.insert("adapter".into(), Value::String(adapter.name.clone()));
     }
@@ -305,18 +609,52 @@ async fn publish(
 
     let subject = state.subject(tenant, &team, envelope.channel_id.as_str());
 
-    let value =
-        to_value(&envelope).map_err(|err| (StatusCode::SERVICE_UNAVAILABLE, err.to_string()))?;
-    state
-        .bus
-        .publish_value(&subject, value.clone())
-        .await
-        .map_err(|err| match err {
-            BusError::Publish(e) => {
-                tracing::error!(%subject, error = %e, "failed to publish ingress envelope");
-                (StatusCode::SERVICE_UNAVAILABLE, e.to_string())
+    let value = to_value(&envelope)
+        .map_err(|err| api_error((StatusCode::SERVICE_UNAVAILABLE, err.to_string())))?;
+    let idem_key = IdKey {
+        tenant: tenant.to_string(),
+        platform: platform.as_str().to_string(),
+        msg_id: msg_id.clone(),
+    };
+    let guard = state.idempotency.as_ref().filter(|_| deduplicate);
+    if let Some(guard) = guard {
+        match guard.should_process(&idem_key).await {
+            Ok(true) => {}
+            Ok(false) => {
+                let _ = counter!(
+                    "messaging_ingress_duplicate_total",
+                    "tenant" => tenant.to_string(),
+                    "platform" => platform.as_str().to_string()
+                );
+                return Ok(Ingested {
+                    subject,
+                    msg_id,
+                    duplicate: true,
+                });
             }
-        })?;
+            Err(err) => {
+                warn!(error = %err, %msg_id, "idempotency check failed; publishing anyway");
+            }
+        }
+    }
+    if replayed {
+        if let Some(guard) = guard
+            && let Err(err) = guard.release(&idem_key).await
+        {
+            warn!(error = %err, %msg_id, "failed to release idempotency key");
+        }
+        return Err(map_install_error(ProviderInstallError::ReplayedRequest));
+    }
+    if let Err(BusError::Publish(e)) = state.bus.publish_value(&subject, value.clone()).await {
+        tracing::error!(%subject, error = %e, "failed to publish ingress envelope");
+        // Let the provider's retry through instead of treating it as a duplicate.
+        if let Some(guard) = guard
+            && let Err(err) = guard.release(&idem_key).await
+        {
+            warn!(error = %err, %msg_id, "failed to release idempotency key");
+        }
+        return Err(api_error((StatusCode::SERVICE_UNAVAILABLE, e.to_string())));
+    }
 
     let selected_cfg = state
         .config
@@ -354,8 +692,9 @@ async fn publish(
                             platform.clone(),
                             envelope.payload["thread_id"].as_str().map(str::to_string),
                         );
-                        let out_value = to_value(&out_msg)
-                            .map_err(|err| (StatusCode::SERVICE_UNAVAILABLE, err.to_string()))?;
+                        let out_value = to_value(&out_msg).map_err(|err| {
+                            api_error((StatusCode::SERVICE_UNAVAILABLE, err.to_string()))
+                        })?;
                         let team = envelope
                             .tenant
                             .team
@@ -409,16 +748,88 @@ async fn publish(
     )
     .in_scope(|| tracing::trace!("ingress request dispatched"));
 
-    Ok(subject)
+    Ok(Ingested {
+        subject,
+        msg_id,
+        duplicate: false,
+    })
 }
 
-fn resolve_install_for_ingress(
+/// Telegram button taps arrive as `metadata.callback_data`. Inline data is parsed and `pb:`
+/// tokens are looked up in the directory egress issued them to, so flows get the payload the
+/// card was rendered with as `metadata.postback`.
+fn resolve_telegram_postback(metadata: &mut BTreeMap<String, Value>, token_dir: Option<&Path>) {
+    let Some(callback_data) = metadata.get("callback_data").and_then(Value::as_str) else {
+        return;
+    };
+    let store: Box<dyn PostbackTokenStore> = match token_dir {
+        Some(dir) => Box::new(DirPostbackTokenStore::new(dir)),
+        None => Box::new(InMemoryPostbackTokenStore::new()),
+    };
+    match resolve_callback_data(store.as_ref(), callback_data) {
+        Some(postback) => {
+            metadata.insert("postback".into(), postback);
+        }
+        None => warn!(%callback_data, "telegram postback token is unknown or expired"),
+    }
+}
+
+/// Records interactions for cards rendered with a `card_id` on postbacks (`metadata.postback`)
+/// and strips the correlation stamp, so flows see the payload the card was authored with.
+fn record_card_interaction(platform: &str, metadata: &mut BTreeMap<String, Value>) {
+    if let Some(postback) = metadata.get_mut("postback") {
+        CARD_INTERACTIONS.record_interaction(platform, postback);
+    }
+}
+
+static CARD_INTERACTIONS: LazyLock<MessageCardEngine> = LazyLock::new(|| {
+    MessageCardEngine::bootstrap().with_telemetry(Arc::new(CardInteractionMetrics))
+});
+
+/// Counts card interactions seen on ingress.
+struct CardInteractionMetrics;
+
+impl TelemetryHook for CardInteractionMetrics {
+    fn emit(&self, event: TelemetryEvent) {
+        let (kind, card_id, platform, age_ms) = match event {
+            TelemetryEvent::ActionInvoked {
+                card_id,
+                platform,
+                latency_ms,
+                ..
+            } => ("action", card_id, platform, latency_ms),
+            TelemetryEvent::InputSubmitted {
+                card_id,
+                platform,
+                latency_ms,
+                ..
+            } => ("submit", card_id, platform, latency_ms),
+            TelemetryEvent::CardExpired {
+                card_id,
+                platform,
+                age_ms,
+            } => ("expired", card_id, platform, age_ms),
+            _ => return,
+        };
+        tracing::debug!(%card_id, %platform, kind, age_ms, "card interaction");
+        counter!(
+            "messaging_card_interactions_total",
+            "platform" => platform,
+            "kind" => kind
+        )
+        .increment(1);
+    }
+}
+
+async fn resolve_install_for_ingress(
     state: &GatewayState,
     tenant: &gsm_core::TenantCtx,
     payload: &NormalizedRequest,
     channel: &str,
+    activity: &Value,
+    signed: SignedBody<'_>,
     headers: &HeaderMap,
-) -> Result<gsm_core::ProviderInstallState, ProviderInstallError> {
+) -> Result<(gsm_core::ProviderInstallState, bool), ProviderInstallError> {
     let provider_id = payload
         .provider_id
         .as_ref()
@@ -446,9 +857,17 @@ fn resolve_install_for_ingress(
     })?;
 
     enforce_install_secrets(&install_state)?;
-    verify_webhook_signature(state, &provider_id, &install_state, payload, headers)?;
+    let replayed = verify_webhook_signature(
+        state,
+        &provider_id,
+        &install_state,
+        activity,
+        signed,
+        headers,
+    )
+    .await?;
 
-    Ok(install_state)
+    Ok((install_state, replayed))
 }
 
 fn enforce_install_secrets(
@@ -467,29 +886,67 @@ fn enforce_install_secrets(
     Ok(())
 }
 
-fn verify_webhook_signature(
+/// Checks the request's signature and returns whether it was already accepted before. Replays
+/// are not rejected here: the idempotency guard decides first whether they are provider retries.
+async fn verify_webhook_signature(
     state: &GatewayState,
     provider_id: &str,
     install_state: &gsm_core::ProviderInstallState,
-    payload: &NormalizedRequest,
+    activity: &Value,
+    signed: SignedBody<'_>,
     headers: &HeaderMap,
-) -> Result<(), ProviderInstallError> {
+) -> Result<bool, ProviderInstallError> {
     let Some(decl) = state.provider_extensions.ingress.get(provider_id) else {
-        return Ok(());
+        return Ok(false);
     };
     if !decl.capabilities.supports_webhook_validation {
-        return Ok(());
+        return Ok(false);
     }
 
-    let header_name = install_state
-        .record
-        .webhook_state
+    let webhook_state = &install_state.record.webhook_state;
+    let scheme = match webhook_state.get("scheme") {
+        Some(value) => serde_json::from_value::<WebhookScheme>(value.clone()).map_err(|_| {
+            ProviderInstallError::MissingConfig {
+                key: "webhook_state.scheme".into(),
+            }
+        })?,
+        None => WebhookScheme::default(),
+    };
+    if scheme == WebhookScheme::BotFramework {
+        verify_bot_framework_token(state, install_state, activity, headers).await?;
+        return Ok(false);
+    }
+    match signed {
+        SignedBody::Request(body) => {
+            verify_body_signature(state, provider_id, install_state, scheme, body, headers)
+        }
+        SignedBody::Batch { body, verified } => {
+            let key = format!("{provider_id}/{}", install_state.record.install_id);
+            let mut verified = verified.0.lock().unwrap_or_else(|err| err.into_inner());
+            verified
+                .entry(key)
+                .or_insert_with(|| {
+                    verify_body_signature(state, provider_id, install_state, scheme, body, headers)
+                })
+                .clone()
+        }
+    }
+}
+
+fn verify_body_signature(
+    state: &GatewayState,
+    provider_id: &str,
+    install_state: &gsm_core::ProviderInstallState,
+    scheme: WebhookScheme,
+    body: &[u8],
+    headers: &HeaderMap,
+) -> Result<bool, ProviderInstallError> {
+    let webhook_state = &install_state.record.webhook_state;
+    let header = webhook_state
         .get("signature_header")
         .and_then(|v| v.as_str())
-        .unwrap_or("x-signature");
-    let secret_key = install_state
-        .record
-        .webhook_state
+        .unwrap_or(scheme.default_header());
+    let secret_key = webhook_state
         .get("secret_key")
         .and_then(|v| v.as_str())
         .unwrap_or("webhook_secret");
@@ -498,40 +955,67 @@ fn verify_webhook_signature(
             key: secret_key.to_string(),
         }
     })?;
-    let provided = headers
-        .get(header_name)
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
-    if provided.is_empty() {
-        return Err(ProviderInstallError::InvalidSignature {
-            header: header_name.to_string(),
-        });
-    }
-    let body = serde_json::to_vec(payload).unwrap_or_else(|_| b"{}".to_vec());
-    if !verify_hmac_sha256(secret.as_str(), &body, provided) {
-        return Err(ProviderInstallError::InvalidSignature {
-            header: header_name.to_string(),
-        });
-    }
-    Ok(())
+    let verifier = WebhookVerifier {
+        scheme,
+        header: header.to_ascii_lowercase(),
+        secret: secret.clone(),
+        max_skew_secs: webhook_state
+            .get("max_skew_secs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_MAX_SKEW_SECS),
+    };
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let delivery = verifier.verify(headers, body, now)?;
+    let key = format!(
+        "{provider_id}/{}/{}",
+        install_state.record.install_id, delivery.nonce
+    );
+    Ok(!state
+        .replay_cache
+        .insert(key, now, delivery.replay_ttl_secs))
 }
 
-fn verify_hmac_sha256(secret: &str, body: &[u8], sig_hdr: &str) -> bool {
-    use base64::{Engine as _, engine::general_purpose::STANDARD as B64};
-    use hmac::{Hmac, Mac};
-    use sha2::Sha256;
-
-    type HmacSha256 = Hmac<Sha256>;
-    let mut mac = match HmacSha256::new_from_slice(secret.as_bytes()) {
-        Ok(mac) => mac,
-        Err(_) => return false,
+/// Bot Framework tokens are reused for many activities until they expire, so they are checked
+/// against the JWKS only and not recorded in the replay cache. The token must have been issued
+/// for the `serviceUrl` of the activity itself; one without it is rejected.
+async fn verify_bot_framework_token(
+    state: &GatewayState,
+    install_state: &gsm_core::ProviderInstallState,
+    activity: &Value,
+    headers: &HeaderMap,
+) -> Result<(), ProviderInstallError> {
+    let header = WebhookScheme::BotFramework.default_header();
+    let invalid = || ProviderInstallError::InvalidSignature {
+        header: header.to_string(),
     };
-    mac.update(body);
-    let provided = match B64.decode(sig_hdr) {
-        Ok(sig) => sig,
-        Err(_) => return false,
+    let app_id = install_state
+        .record
+        .webhook_state
+        .get("app_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ProviderInstallError::MissingConfig {
+            key: "webhook_state.app_id".into(),
+        })?;
+    let Some(validator) = state.bot_framework.as_ref() else {
+        warn!("bot_framework webhook scheme configured but no validator is available");
+        return Err(invalid());
     };
-    mac.verify_slice(&provided).is_ok()
+    let authorization = headers
+        .get(header)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(invalid)?;
+    let service_url = activity
+        .get("serviceUrl")
+        .and_then(|v| v.as_str())
+        .ok_or_else(invalid)?;
+    validator
+        .validate(authorization, app_id, service_url)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            warn!(error = %err, "rejected bot framework token");
+            invalid()
+        })
 }
 
 fn map_install_error(err: ProviderInstallError) -> (StatusCode, Json<ApiError>) {
@@ -540,6 +1024,10 @@ fn map_install_error(err: ProviderInstallError) -> (StatusCode, Json<ApiError>)
         ProviderInstallError::InvalidSignature { .. } => {
             (StatusCode::UNAUTHORIZED, "invalid_signature")
         }
+        ProviderInstallError::StaleSignature { .. } => {
+            (StatusCode::UNAUTHORIZED, "stale_signature")
+        }
+        ProviderInstallError::ReplayedRequest => (StatusCode::CONFLICT, "replayed_request"),
         ProviderInstallError::MissingSecret { .. } => (StatusCode::BAD_REQUEST, "missing_secret"),
         ProviderInstallError::MissingConfig { .. } => (StatusCode::BAD_REQUEST, "missing_config"),
         ProviderInstallError::MissingRoute => (StatusCode::BAD_REQUEST, "missing_route"),
@@ -0,0 +1,96 @@
+//! Ingress deduplication: provider webhook retries carry the same event id, so the gateway keys
+//! an [`IdempotencyGuard`] on `{tenant, platform, msg_id}` and publishes each message once.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_nats::{Client, jetstream};
+use gsm_core::Platform;
+use gsm_idempotency::{
+    IdempotencyConfig, IdempotencyGuard, InMemoryIdemStore, NatsKvIdemStore, SharedIdemStore,
+};
+use serde_json::Value;
+use tracing::warn;
+
+/// Metadata key holding the provider's original request body. The gateway fills it with the
+/// request body unless a normalizer already forwarded one.
+pub const RAW_BODY_METADATA_KEY: &str = "raw";
+
+/// Builds the guard on the JetStream KV store, falling back to memory when JetStream is
+/// unavailable.
+pub async fn init_guard(nats: &Client) -> IdempotencyGuard {
+    let cfg = IdempotencyConfig::default();
+    let js = jetstream::new(nats.clone());
+    let store: SharedIdemStore = match NatsKvIdemStore::new(&js, &cfg.namespace).await {
+        Ok(store) => Arc::new(store),
+        Err(err) => {
+            warn!(error = %err, "idempotency store unavailable, using in-memory fallback");
+            Arc::new(InMemoryIdemStore::new())
+        }
+    };
+    IdempotencyGuard::new(store, cfg.ttl_hours)
+}
+
+/// Extracts the provider's own event id from the original body in `metadata.raw`: Slack
+/// `event_id` (or the message's `client_msg_id`), Telegram `update_id`, WhatsApp/Meta message
+/// ids, the Teams activity `id` and the Webex `data.id`.
+pub fn native_event_id(platform: &Platform, metadata: &BTreeMap<String, Value>) -> Option<String> {
+    let raw = metadata.get(RAW_BODY_METADATA_KEY)?;
+    let pointers: &[&str] = match platform {
+        Platform::Slack => &["/event_id", "/event/client_msg_id"],
+        Platform::Telegram => &["/update_id"],
+        Platform::WhatsApp => &[
+            "/entry/0/changes/0/value/messages/0/id",
+            "/entry/0/messaging/0/message/mid",
+        ],
+        Platform::Teams => &["/id"],
+        Platform::Webex => &["/data/id"],
+        Platform::WebChat => &[],
+    };
+    pointers
+        .iter()
+        .find_map(|pointer| match raw.pointer(pointer)? {
+            Value::String(id) if !id.is_empty() => Some(id.clone()),
+            Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(raw: Value) -> BTreeMap<String, Value> {
+        BTreeMap::from([(RAW_BODY_METADATA_KEY.to_string(), raw)])
+    }
+
+    #[test]
+    fn extracts_provider_event_ids() {
+        let slack = metadata(serde_json::json!({"event_id": "Ev123", "event": {}}));
+        assert_eq!(
+            native_event_id(&Platform::Slack, &slack).as_deref(),
+            Some("Ev123")
+        );
+
+        let telegram = metadata(serde_json::json!({"update_id": 42}));
+        assert_eq!(
+            native_event_id(&Platform::Telegram, &telegram).as_deref(),
+            Some("42")
+        );
+
+        let whatsapp = metadata(serde_json::json!({
+            "entry": [{"changes": [{"value": {"messages": [{"id": "wamid.1"}]}}]}]
+        }));
+        assert_eq!(
+            native_event_id(&Platform::WhatsApp, &whatsapp).as_deref(),
+            Some("wamid.1")
+        );
+    }
+
+    #[test]
+    fn ignores_bodies_without_ids() {
+        assert_eq!(native_event_id(&Platform::Slack, &BTreeMap::new()), None);
+        let webchat = metadata(serde_json::json!({"id": "abc"}));
+        assert_eq!(native_event_id(&Platform::WebChat, &webchat), None);
+    }
+}
@@ -1,6 +1,8 @@
 pub mod config;
 pub mod http;
+pub mod idempotency;
 mod main_logic;
+pub mod webhook;
 
 use crate::config::GatewayConfig;
 use anyhow::Result;
@@ -92,6 +94,7 @@ pub async fn build_router_with_bus<B: BusClient + 'static>(
     bus: Arc<B>,
     install_store: Arc<dyn gsm_core::ProviderInstallStore>,
     workers: std::collections::BTreeMap<String, Arc<dyn WorkerClient>>,
+    idempotency: Option<gsm_idempotency::IdempotencyGuard>,
 ) -> Result<axum::Router> {
     http::build_router_with_bus(
         config,
@@ -100,6 +103,7 @@ pub async fn build_router_with_bus<B: BusClient + 'static>(
         bus,
         install_store,
         workers,
+        idempotency,
     )
     .await
 }
@@ -80,6 +80,7 @@ pub async fn run(config: GatewayConfig) -> Result<()> {
         std::sync::Arc::new(bus),
         install_store,
         worker_clients,
+        Some(crate::idempotency::init_guard(&nats).await),
     )
     .await?;
     let listener = TcpListener::bind(config.addr).await?;
@@ -0,0 +1,453 @@
+//! Provider-specific webhook signature checks with timestamp windows and replay protection.
+//!
+//! The scheme is chosen per install through `webhook_state.scheme` and defaults to the generic
+//! base64 HMAC-SHA256 check the gateway has always done. Signatures are checked over the raw
+//! request body. A signed timestamp (Slack's header, or the event time inside a Meta, Webex or
+//! Telegram body) outside `webhook_state.max_skew_secs` is rejected, and every accepted
+//! signature is remembered in a [`ReplayCache`]: for twice the window when the request was
+//! dated, and for [`UNDATED_REPLAY_TTL_SECS`] otherwise.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use axum::http::HeaderMap;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use gsm_core::ProviderInstallError;
+
+/// Clock skew accepted when an install does not set `webhook_state.max_skew_secs`.
+pub const DEFAULT_MAX_SKEW_SECS: u64 = 300;
+
+/// How long nonces of requests without a signed timestamp are remembered. Matches the default
+/// ingress idempotency TTL, so such a request cannot be replayed once its id has been forgotten.
+pub const UNDATED_REPLAY_TTL_SECS: u64 = 36 * 3600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookScheme {
+    /// Base64 HMAC-SHA256 of the body in a configurable header (`x-signature` by default).
+    #[default]
+    Hmac,
+    /// `X-Slack-Signature: v0=<hex>` over `v0:{timestamp}:{body}`, timestamp in
+    /// `X-Slack-Request-Timestamp`.
+    Slack,
+    /// `X-Hub-Signature-256: sha256=<hex>` over the body (WhatsApp, Messenger, Instagram).
+    Meta,
+    /// `X-Telegram-Bot-Api-Secret-Token` equal to the configured secret.
+    Telegram,
+    /// `X-Spark-Signature: <hex>` HMAC-SHA1 over the body.
+    Webex,
+    /// `Authorization: Bearer <jwt>` issued by the Bot Framework connector (Teams). Checked
+    /// asynchronously with `security::BotFrameworkValidator` rather than [`WebhookVerifier`].
+    BotFramework,
+}
+
+impl WebhookScheme {
+    /// Header carrying the signature unless the install overrides it.
+    pub fn default_header(self) -> &'static str {
+        match self {
+            WebhookScheme::Hmac => "x-signature",
+            WebhookScheme::Slack => "x-slack-signature",
+            WebhookScheme::Meta => "x-hub-signature-256",
+            WebhookScheme::Telegram => "x-telegram-bot-api-secret-token",
+            WebhookScheme::Webex => "x-spark-signature",
+            WebhookScheme::BotFramework => "authorization",
+        }
+    }
+}
+
+/// An accepted webhook delivery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delivery {
+    /// Identifies the delivery in the [`ReplayCache`].
+    pub nonce: String,
+    /// How long the nonce must be remembered.
+    pub replay_ttl_secs: u64,
+}
+
+/// Verification settings for one install.
+#[derive(Debug, Clone)]
+pub struct WebhookVerifier {
+    pub scheme: WebhookScheme,
+    pub header: String,
+    pub secret: String,
+    pub max_skew_secs: u64,
+}
+
+impl WebhookVerifier {
+    /// Checks `headers` and the raw `body` at unix time `now` and returns the delivery to be
+    /// recorded in a [`ReplayCache`].
+    pub fn verify(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+        now: i64,
+    ) -> Result<Delivery, ProviderInstallError> {
+        let nonce = self.verify_signature(headers, body, now)?;
+        let dated = match self.scheme {
+            WebhookScheme::Slack => true,
+            WebhookScheme::Meta | WebhookScheme::Webex | WebhookScheme::Telegram => {
+                match body_timestamp(self.scheme, body) {
+                    Some(timestamp) => {
+                        self.check_skew(timestamp, now)?;
+                        true
+                    }
+                    None => false,
+                }
+            }
+            WebhookScheme::Hmac | WebhookScheme::BotFramework => false,
+        };
+        let replay_ttl_secs = if dated {
+            self.max_skew_secs.saturating_mul(2).max(1)
+        } else {
+            UNDATED_REPLAY_TTL_SECS
+        };
+        Ok(Delivery {
+            nonce,
+            replay_ttl_secs,
+        })
+    }
+
+    fn verify_signature(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+        now: i64,
+    ) -> Result<String, ProviderInstallError> {
+        let provided = header_value(headers, &self.header)
+            .ok_or_else(|| self.invalid())?
+            .to_string();
+        match self.scheme {
+            WebhookScheme::Hmac => {
+                use base64::{Engine as _, engine::general_purpose::STANDARD as B64};
+                let signature = B64.decode(&provided).map_err(|_| self.invalid())?;
+                verify_mac::<Hmac<Sha256>>(&self.secret, &[body], &signature)
+                    .ok_or_else(|| self.invalid())?;
+                Ok(provided)
+            }
+            WebhookScheme::Slack => {
+                let timestamp = header_value(headers, "x-slack-request-timestamp")
+                    .ok_or_else(|| self.invalid())?;
+                self.check_timestamp(timestamp, now)?;
+                let signature = provided
+                    .strip_prefix("v0=")
+                    .and_then(|hex_sig| hex::decode(hex_sig).ok())
+                    .ok_or_else(|| self.invalid())?;
+                let prefix = format!("v0:{timestamp}:");
+                verify_mac::<Hmac<Sha256>>(&self.secret, &[prefix.as_bytes(), body], &signature)
+                    .ok_or_else(|| self.invalid())?;
+                Ok(provided)
+            }
+            WebhookScheme::Meta => {
+                let signature = provided
+                    .strip_prefix("sha256=")
+                    .and_then(|hex_sig| hex::decode(hex_sig).ok())
+                    .ok_or_else(|| self.invalid())?;
+                verify_mac::<Hmac<Sha256>>(&self.secret, &[body], &signature)
+                    .ok_or_else(|| self.invalid())?;
+                Ok(provided)
+            }
+            WebhookScheme::Telegram => {
+                if !bool::from(provided.as_bytes().ct_eq(self.secret.as_bytes())) {
+                    return Err(self.invalid());
+                }
+                // The token is the same on every delivery, so the body identifies it.
+                Ok(hex::encode(Sha256::digest(body)))
+            }
+            WebhookScheme::Webex => {
+                let signature = hex::decode(&provided).map_err(|_| self.invalid())?;
+                verify_mac::<Hmac<Sha1>>(&self.secret, &[body], &signature)
+                    .ok_or_else(|| self.invalid())?;
+                Ok(provided)
+            }
+            WebhookScheme::BotFramework => Err(self.invalid()),
+        }
+    }
+
+    fn check_timestamp(&self, raw: &str, now: i64) -> Result<(), ProviderInstallError> {
+        let timestamp: i64 = raw.trim().parse().map_err(|_| self.invalid())?;
+        self.check_skew(timestamp, now)
+    }
+
+    /// A timestamp may be up to the skew window in the past or in the future, so accepted
+    /// nonces of dated requests are remembered for twice the window.
+    fn check_skew(&self, timestamp: i64, now: i64) -> Result<(), ProviderInstallError> {
+        if timestamp.abs_diff(now) > self.max_skew_secs {
+            return Err(ProviderInstallError::StaleSignature {
+                header: self.header.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn invalid(&self) -> ProviderInstallError {
+        ProviderInstallError::InvalidSignature {
+            header: self.header.clone(),
+        }
+    }
+}
+
+/// The event time a provider signs into its body, in unix seconds: the newest Meta
+/// `entry[].time` (or message/status `timestamp` for WhatsApp), the Webex `data.created` and the
+/// date of a Telegram message update. Button taps and other bodies without one return `None`.
+fn body_timestamp(scheme: WebhookScheme, body: &[u8]) -> Option<i64> {
+    let body: Value = serde_json::from_slice(body).ok()?;
+    match scheme {
+        WebhookScheme::Meta => {
+            let mut newest = None;
+            for entry in body.get("entry")?.as_array()? {
+                if let Some(time) = entry.get("time").and_then(Value::as_i64) {
+                    // Messenger and Instagram send milliseconds.
+                    let time = if time > 100_000_000_000 {
+                        time / 1000
+                    } else {
+                        time
+                    };
+                    newest = newest.max(Some(time));
+                }
+                let changes = entry.get("changes").and_then(Value::as_array);
+                for change in changes.into_iter().flatten() {
+                    for pointer in ["/value/messages", "/value/statuses"] {
+                        let items = change.pointer(pointer).and_then(Value::as_array);
+                        for item in items.into_iter().flatten() {
+                            let timestamp = item.get("timestamp").and_then(Value::as_str);
+                            newest = newest.max(timestamp.and_then(|t| t.parse().ok()));
+                        }
+                    }
+                }
+            }
+            newest
+        }
+        WebhookScheme::Webex => {
+            let created = body.pointer("/data/created")?.as_str()?;
+            OffsetDateTime::parse(created, &Rfc3339)
+                .ok()
+                .map(OffsetDateTime::unix_timestamp)
+        }
+        WebhookScheme::Telegram => [
+            "message",
+            "edited_message",
+            "channel_post",
+            "edited_channel_post",
+        ]
+        .into_iter()
+        .find_map(|key| {
+            let message = body.get(key)?;
+            message
+                .get("edit_date")
+                .or_else(|| message.get("date"))?
+                .as_i64()
+        }),
+        _ => None,
+    }
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+}
+
+fn verify_mac<M: Mac + hmac::digest::KeyInit>(
+    secret: &str,
+    parts: &[&[u8]],
+    signature: &[u8],
+) -> Option<()> {
+    let mut mac = <M as Mac>::new_from_slice(secret.as_bytes()).ok()?;
+    for part in parts {
+        mac.update(part);
+    }
+    mac.verify_slice(signature).ok()
+}
+
+/// Remembers accepted webhook nonces until they expire.
+#[derive(Debug, Default)]
+pub struct ReplayCache {
+    seen: Mutex<HashMap<String, i64>>,
+}
+
+impl ReplayCache {
+    /// Records `key` until `now + ttl_secs`. Returns `false` when it was already recorded and
+    /// has not expired, i.e. the request is a replay.
+    pub fn insert(&self, key: String, now: i64, ttl_secs: u64) -> bool {
+        let mut seen = self.seen.lock().unwrap_or_else(|err| err.into_inner());
+        seen.retain(|_, expires_at| *expires_at > now);
+        if seen.contains_key(&key) {
+            return false;
+        }
+        let ttl = i64::try_from(ttl_secs).unwrap_or(i64::MAX);
+        seen.insert(key, now.saturating_add(ttl));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn verifier(scheme: WebhookScheme) -> WebhookVerifier {
+        WebhookVerifier {
+            scheme,
+            header: scheme.default_header().into(),
+            secret: "secret".into(),
+            max_skew_secs: DEFAULT_MAX_SKEW_SECS,
+        }
+    }
+
+    fn hex_mac<M: Mac + hmac::digest::KeyInit>(parts: &[&[u8]]) -> String {
+        let mut mac = <M as Mac>::new_from_slice(b"secret").unwrap();
+        for part in parts {
+            mac.update(part);
+        }
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn headers(pairs: &[(&'static str, String)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn slack_headers(timestamp: i64, body: &[u8]) -> HeaderMap {
+        let prefix = format!("v0:{timestamp}:");
+        let signature = hex_mac::<Hmac<Sha256>>(&[prefix.as_bytes(), body]);
+        headers(&[
+            ("x-slack-signature", format!("v0={signature}")),
+            ("x-slack-request-timestamp", timestamp.to_string()),
+        ])
+    }
+
+    #[test]
+    fn slack_accepts_fresh_signatures_and_rejects_stale_ones() {
+        let verifier = verifier(WebhookScheme::Slack);
+        let body = br#"{"text":"hi"}"#;
+
+        assert!(
+            verifier
+                .verify(&slack_headers(NOW - 10, body), body, NOW)
+                .is_ok()
+        );
+
+        let stale = verifier
+            .verify(&slack_headers(NOW - 301, body), body, NOW)
+            .unwrap_err();
+        assert!(matches!(stale, ProviderInstallError::StaleSignature { .. }));
+
+        let tampered = verifier
+            .verify(&slack_headers(NOW, body), br#"{"text":"bye"}"#, NOW)
+            .unwrap_err();
+        assert!(matches!(
+            tampered,
+            ProviderInstallError::InvalidSignature { .. }
+        ));
+    }
+
+    #[test]
+    fn meta_and_webex_check_hex_signatures() {
+        let body = br#"{"entry":[]}"#;
+        let meta = headers(&[(
+            "x-hub-signature-256",
+            format!("sha256={}", hex_mac::<Hmac<Sha256>>(&[body])),
+        )]);
+        assert!(
+            verifier(WebhookScheme::Meta)
+                .verify(&meta, body, NOW)
+                .is_ok()
+        );
+
+        let webex = headers(&[("x-spark-signature", hex_mac::<Hmac<Sha1>>(&[body]))]);
+        assert!(
+            verifier(WebhookScheme::Webex)
+                .verify(&webex, body, NOW)
+                .is_ok()
+        );
+        assert!(
+            verifier(WebhookScheme::Webex)
+                .verify(&meta, body, NOW)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn telegram_compares_the_secret_token() {
+        let verifier = verifier(WebhookScheme::Telegram);
+        let body = br#"{"update_id":1}"#;
+        let ok = headers(&[("x-telegram-bot-api-secret-token", "secret".into())]);
+        let bad = headers(&[("x-telegram-bot-api-secret-token", "other".into())]);
+
+        let delivery = verifier.verify(&ok, body, NOW).unwrap();
+        assert_eq!(delivery.nonce, hex::encode(Sha256::digest(body)));
+        assert!(verifier.verify(&bad, body, NOW).is_err());
+    }
+
+    #[test]
+    fn signed_body_timestamps_bound_the_replay_window() {
+        let meta = |body: &[u8]| {
+            let headers = headers(&[(
+                "x-hub-signature-256",
+                format!("sha256={}", hex_mac::<Hmac<Sha256>>(&[body])),
+            )]);
+            verifier(WebhookScheme::Meta).verify(&headers, body, NOW)
+        };
+        let fresh = format!(r#"{{"entry":[{{"time":{}}}]}}"#, (NOW - 5) * 1000);
+        assert_eq!(meta(fresh.as_bytes()).unwrap().replay_ttl_secs, 600);
+        let stale = format!(r#"{{"entry":[{{"time":{}}}]}}"#, (NOW - 301) * 1000);
+        assert!(matches!(
+            meta(stale.as_bytes()).unwrap_err(),
+            ProviderInstallError::StaleSignature { .. }
+        ));
+        let whatsapp = format!(
+            r#"{{"entry":[{{"changes":[{{"value":{{"messages":[{{"timestamp":"{}"}}]}}}}]}}]}}"#,
+            NOW - 301
+        );
+        assert!(meta(whatsapp.as_bytes()).is_err());
+        assert_eq!(
+            meta(br#"{"entry":[]}"#).unwrap().replay_ttl_secs,
+            UNDATED_REPLAY_TTL_SECS
+        );
+
+        let webex = br#"{"data":{"id":"m1","created":"2023-11-14T22:00:00.000Z"}}"#;
+        let webex_headers = headers(&[("x-spark-signature", hex_mac::<Hmac<Sha1>>(&[webex]))]);
+        assert!(matches!(
+            verifier(WebhookScheme::Webex)
+                .verify(&webex_headers, webex, NOW)
+                .unwrap_err(),
+            ProviderInstallError::StaleSignature { .. }
+        ));
+
+        let telegram = verifier(WebhookScheme::Telegram);
+        let token = headers(&[("x-telegram-bot-api-secret-token", "secret".into())]);
+        let old_message = format!(r#"{{"update_id":1,"message":{{"date":{}}}}}"#, NOW - 301);
+        assert!(
+            telegram
+                .verify(&token, old_message.as_bytes(), NOW)
+                .is_err()
+        );
+        let edited = format!(
+            r#"{{"update_id":2,"edited_message":{{"date":{},"edit_date":{}}}}}"#,
+            NOW - 3600,
+            NOW
+        );
+        assert!(telegram.verify(&token, edited.as_bytes(), NOW).is_ok());
+    }
+
+    #[test]
+    fn replay_cache_rejects_repeats_until_they_expire() {
+        let cache = ReplayCache::default();
+        assert!(cache.insert("sig".into(), NOW, 600));
+        assert!(!cache.insert("sig".into(), NOW + 599, 600));
+        assert!(cache.insert("sig".into(), NOW + 600, 600));
+    }
+}
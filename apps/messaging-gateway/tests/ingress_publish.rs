@@ -4,7 +4,7 @@ use gsm_core::{
 };
 use gsm_gateway::InMemoryBusClient;
 use gsm_gateway::config::GatewayConfig;
-use gsm_gateway::http::{GatewayState, NormalizedRequest, handle_ingress};
+use gsm_gateway::http::{GatewayState, NormalizedRequest, handle_batch_ingress, handle_ingress};
 use gsm_gateway::load_adapter_registry;
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
@@ -23,9 +23,15 @@ fn test_config() -> GatewayConfig {
         default_packs: DefaultAdapterPacksConfig::default(),
         extra_pack_paths: Vec::new(),
         install_store_path: None,
+        batch: Default::default(),
+        postback_token_dir: None,
     }
 }
 
+fn json_body(payload: &NormalizedRequest) -> axum::body::Bytes {
+    serde_json::to_vec(payload).unwrap().into()
+}
+
 fn load_default_adapters() -> gsm_core::AdapterRegistry {
     load_adapter_registry(
         Path::new("packs"),
@@ -119,6 +125,9 @@ async fn ingress_is_normalized_and_published() {
         install_store,
         workers: std::collections::BTreeMap::new(),
         worker_default: None,
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
     });
 
     let payload = NormalizedRequest {
@@ -135,7 +144,7 @@ async fn ingress_is_normalized_and_published() {
         Some("team".into()),
         "slack".into(),
         state,
-        payload,
+        json_body(&payload),
         Default::default(),
     )
     .await
@@ -192,6 +201,9 @@ async fn forwards_multiple_worker_messages_including_card() {
         install_store,
         workers,
         worker_default: Some(worker_cfg),
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
     });
 
     let payload = NormalizedRequest {
@@ -208,7 +220,7 @@ async fn forwards_multiple_worker_messages_including_card() {
         Some("team".into()),
         "slack".into(),
         state,
-        payload,
+        json_body(&payload),
         Default::default(),
     )
     .await
@@ -268,6 +280,9 @@ async fn worker_failure_does_not_block_ingress_publish() {
         install_store,
         workers,
         worker_default: Some(worker_cfg),
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
     });
 
     let payload = NormalizedRequest {
@@ -284,7 +299,7 @@ async fn worker_failure_does_not_block_ingress_publish() {
         Some("team".into()),
         "slack".into(),
         state,
-        payload,
+        json_body(&payload),
         Default::default(),
     )
     .await
@@ -330,6 +345,9 @@ async fn forwards_to_worker_when_configured() {
             map
         },
         worker_default: worker_config.clone(),
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
     });
 
     let payload = NormalizedRequest {
@@ -346,7 +364,7 @@ async fn forwards_to_worker_when_configured() {
         Some("team".into()),
         "slack".into(),
         state,
-        payload,
+        json_body(&payload),
         Default::default(),
     )
     .await
@@ -383,6 +401,9 @@ async fn ingress_routes_between_multiple_installs() {
         install_store: store,
         workers: std::collections::BTreeMap::new(),
         worker_default: None,
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
     });
 
     let payload = NormalizedRequest {
@@ -399,7 +420,7 @@ async fn ingress_routes_between_multiple_installs() {
         Some("team".into()),
         "slack".into(),
         state,
-        payload,
+        json_body(&payload),
         Default::default(),
     )
     .await
@@ -426,6 +447,9 @@ async fn ingress_returns_error_for_missing_install() {
         install_store: Arc::new(InMemoryProviderInstallStore::default()),
         workers: std::collections::BTreeMap::new(),
         worker_default: None,
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
     });
 
     let payload = NormalizedRequest {
@@ -442,7 +466,7 @@ async fn ingress_returns_error_for_missing_install() {
         Some("team".into()),
         "slack".into(),
         state,
-        payload,
+        json_body(&payload),
         Default::default(),
     )
     .await
@@ -464,6 +488,9 @@ async fn ingress_returns_error_for_missing_secret() {
         install_store: install_store_missing_secret(),
         workers: std::collections::BTreeMap::new(),
         worker_default: None,
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
     });
 
     let payload = NormalizedRequest {
@@ -480,7 +507,7 @@ async fn ingress_returns_error_for_missing_secret() {
         Some("team".into()),
         "slack".into(),
         state,
-        payload,
+        json_body(&payload),
         Default::default(),
     )
     .await
@@ -502,6 +529,9 @@ async fn ingress_returns_error_for_missing_config() {
         install_store: install_store_missing_config(),
         workers: std::collections::BTreeMap::new(),
         worker_default: None,
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
     });
 
     let payload = NormalizedRequest {
@@ -518,7 +548,7 @@ async fn ingress_returns_error_for_missing_config() {
         Some("team".into()),
         "slack".into(),
         state,
-        payload,
+        json_body(&payload),
         Default::default(),
     )
     .await
@@ -527,3 +557,467 @@ async fn ingress_returns_error_for_missing_config() {
     assert_eq!(err.0, axum::http::StatusCode::BAD_REQUEST);
     assert_eq!(err.1.0.code(), Some("missing_config"));
 }
+
+fn batch_state(bus: Arc<InMemoryBusClient>, max_items: usize) -> Arc<GatewayState> {
+    let mut config = test_config();
+    config.batch.max_items = max_items;
+    Arc::new(GatewayState {
+        bus,
+        config,
+        adapters: load_default_adapters(),
+        provider_extensions: ProviderExtensionsRegistry::default(),
+        install_store: install_store_with_slack(),
+        workers: std::collections::BTreeMap::new(),
+        worker_default: None,
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
+    })
+}
+
+#[tokio::test]
+async fn batch_ingress_reports_results_per_item() {
+    let bus = Arc::new(InMemoryBusClient::default());
+    let body = serde_json::json!([
+        {"providerId": "messaging.slack", "providerChannelId": "workspace-1", "chatId": "chat-1", "text": "one", "msgId": "m-1"},
+        {"providerId": "messaging.slack", "providerChannelId": "workspace-1", "text": "no chat"},
+        {"providerId": "messaging.slack", "providerChannelId": "workspace-1", "chatId": "chat-2", "text": "two"}
+    ]);
+
+    let response = handle_batch_ingress(
+        "acme".into(),
+        Some("team".into()),
+        "slack".into(),
+        batch_state(bus.clone(), 10),
+        serde_json::to_vec(&body).unwrap().into(),
+        Default::default(),
+    )
+    .await
+    .expect("batch should be accepted");
+
+    assert_eq!(response.accepted, 2);
+    assert_eq!(response.failed, 1);
+    let results = &response.results;
+    assert_eq!(results[0].msg_id.as_deref(), Some("m-1"));
+    assert!(
+        results[0]
+            .subject
+            .as_deref()
+            .unwrap()
+            .contains("greentic.messaging.ingress.dev.acme.team.slack")
+    );
+    assert!(!results[1].is_accepted());
+    assert_eq!(results[1].index, 1);
+    assert!(results[2].is_accepted());
+    assert_eq!(bus.take_published().await.len(), 2);
+}
+
+#[tokio::test]
+async fn batch_ingress_accepts_ndjson() {
+    let bus = Arc::new(InMemoryBusClient::default());
+    let body = concat!(
+        r#"{"providerId":"messaging.slack","providerChannelId":"workspace-1","chatId":"chat-1","text":"one"}"#,
+        "\n",
+        "not json\n",
+        r#"{"providerId":"messaging.slack","providerChannelId":"workspace-1","chatId":"chat-2","text":"two"}"#,
+        "\n",
+    );
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        "application/x-ndjson".parse().unwrap(),
+    );
+
+    let response = handle_batch_ingress(
+        "acme".into(),
+        Some("team".into()),
+        "slack".into(),
+        batch_state(bus.clone(), 10),
+        body.as_bytes().to_vec().into(),
+        headers,
+    )
+    .await
+    .expect("batch should be accepted");
+
+    assert_eq!(response.accepted, 2);
+    assert_eq!(response.failed, 1);
+    assert!(!response.results[1].is_accepted());
+}
+
+#[tokio::test]
+async fn batch_ingress_rejects_oversized_batches() {
+    let bus = Arc::new(InMemoryBusClient::default());
+    let item = serde_json::json!({"providerId": "messaging.slack", "providerChannelId": "workspace-1", "chatId": "chat-1"});
+    let body = serde_json::Value::Array(vec![item; 3]);
+
+    let err = handle_batch_ingress(
+        "acme".into(),
+        Some("team".into()),
+        "slack".into(),
+        batch_state(bus.clone(), 2),
+        serde_json::to_vec(&body).unwrap().into(),
+        Default::default(),
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(err.0, axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(err.1.0.code(), Some("batch_too_large"));
+    assert!(bus.take_published().await.is_empty());
+}
+
+fn slack_signed_state(bus: Arc<InMemoryBusClient>) -> Arc<GatewayState> {
+    let store = Arc::new(InMemoryProviderInstallStore::default());
+    let mut record = test_install_record("install-a", "slack", "workspace-1");
+    record.webhook_state = serde_json::json!({"scheme": "slack", "max_skew_secs": 60});
+    let mut install = ProviderInstallState::new(record);
+    install.secrets.insert("token".into(), "secret".into());
+    install
+        .secrets
+        .insert("webhook_secret".into(), "signing-secret".into());
+    install
+        .config
+        .insert("config".into(), serde_json::json!({"ok": true}));
+    store.insert(install);
+
+    let mut provider_extensions = ProviderExtensionsRegistry::default();
+    provider_extensions.ingress.insert(
+        "messaging.slack".into(),
+        serde_json::from_value(serde_json::json!({
+            "runtime": {"component_ref": "slack@1.0.0", "export": "ingress", "world": "ingress"},
+            "capabilities": {"supports_webhook_validation": true}
+        }))
+        .unwrap(),
+    );
+    Arc::new(GatewayState {
+        bus,
+        config: test_config(),
+        adapters: load_default_adapters(),
+        provider_extensions,
+        install_store: store,
+        workers: std::collections::BTreeMap::new(),
+        worker_default: None,
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
+    })
+}
+
+fn slack_signature_headers(body: &[u8], timestamp: i64) -> axum::http::HeaderMap {
+    use hmac::{Hmac, Mac};
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"signing-secret").unwrap();
+    mac.update(format!("v0:{timestamp}:").as_bytes());
+    mac.update(body);
+    let signature = format!("v0={}", hex::encode(mac.finalize().into_bytes()));
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert("x-slack-signature", signature.parse().unwrap());
+    headers.insert(
+        "x-slack-request-timestamp",
+        timestamp.to_string().parse().unwrap(),
+    );
+    headers
+}
+
+#[tokio::test]
+async fn signed_ingress_rejects_replays_and_stale_timestamps() {
+    let bus = Arc::new(InMemoryBusClient::default());
+    let state = slack_signed_state(bus.clone());
+    let payload = NormalizedRequest {
+        provider_id: Some("messaging.slack".into()),
+        provider_channel_id: Some("workspace-1".into()),
+        chat_id: Some("chat-1".into()),
+        text: Some("hi".into()),
+        ..Default::default()
+    };
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let headers = slack_signature_headers(&json_body(&payload), now);
+
+    handle_ingress(
+        "acme".into(),
+        Some("team".into()),
+        "slack".into(),
+        state.clone(),
+        json_body(&payload),
+        headers.clone(),
+    )
+    .await
+    .expect("fresh signature should be accepted");
+
+    let replay = handle_ingress(
+        "acme".into(),
+        Some("team".into()),
+        "slack".into(),
+        state.clone(),
+        json_body(&payload),
+        headers,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(replay.0, axum::http::StatusCode::CONFLICT);
+    assert_eq!(replay.1.0.code(), Some("replayed_request"));
+
+    let stale = handle_ingress(
+        "acme".into(),
+        Some("team".into()),
+        "slack".into(),
+        state,
+        json_body(&payload),
+        slack_signature_headers(&json_body(&payload), now - 120),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(stale.0, axum::http::StatusCode::UNAUTHORIZED);
+    assert_eq!(stale.1.0.code(), Some("stale_signature"));
+
+    assert_eq!(bus.take_published().await.len(), 1);
+}
+
+#[tokio::test]
+async fn signatures_are_checked_over_the_raw_body() {
+    let bus = Arc::new(InMemoryBusClient::default());
+    let state = slack_signed_state(bus.clone());
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    // Key order and spacing differ from what re-serializing the request would produce.
+    let body = br#"{ "text": "hi", "chatId": "chat-1",
+        "providerChannelId": "workspace-1", "providerId": "messaging.slack" }"#;
+
+    handle_ingress(
+        "acme".into(),
+        Some("team".into()),
+        "slack".into(),
+        state,
+        body.to_vec().into(),
+        slack_signature_headers(body, now),
+    )
+    .await
+    .expect("signature over the raw body should be accepted");
+
+    assert_eq!(bus.take_published().await.len(), 1);
+}
+
+#[tokio::test]
+async fn batch_signature_is_checked_once_per_request() {
+    let bus = Arc::new(InMemoryBusClient::default());
+    let state = slack_signed_state(bus.clone());
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let body = serde_json::to_vec(&serde_json::json!([
+        {"providerId": "messaging.slack", "providerChannelId": "workspace-1", "chatId": "chat-1", "text": "one"},
+        {"providerId": "messaging.slack", "providerChannelId": "workspace-1", "chatId": "chat-2", "text": "two"}
+    ]))
+    .unwrap();
+
+    let response = handle_batch_ingress(
+        "acme".into(),
+        Some("team".into()),
+        "slack".into(),
+        state,
+        body.clone().into(),
+        slack_signature_headers(&body, now),
+    )
+    .await
+    .expect("batch should be accepted");
+
+    assert_eq!(response.accepted, 2);
+    assert_eq!(response.failed, 0);
+    assert_eq!(bus.take_published().await.len(), 2);
+}
+
+#[tokio::test]
+async fn bot_framework_scheme_rejects_invalid_tokens() {
+    let jwks_path =
+        std::env::temp_dir().join(format!("gsm-gateway-jwks-{}.json", std::process::id()));
+    std::fs::write(&jwks_path, r#"{"keys": []}"#).unwrap();
+
+    let bus = Arc::new(InMemoryBusClient::default());
+    let mut state = Arc::into_inner(slack_signed_state(bus.clone())).unwrap();
+    let mut record = test_install_record("install-a", "slack", "workspace-1");
+    record.webhook_state = serde_json::json!({"scheme": "bot_framework", "app_id": "app-123"});
+    let mut install = ProviderInstallState::new(record);
+    install.secrets.insert("token".into(), "secret".into());
+    install
+        .config
+        .insert("config".into(), serde_json::json!({"ok": true}));
+    let store = Arc::new(InMemoryProviderInstallStore::default());
+    store.insert(install);
+    state.install_store = store;
+    state.bot_framework = Some(Arc::new(security::BotFrameworkValidator::new(Arc::new(
+        security::FileJwksSource::new(&jwks_path),
+    ))));
+    let state = Arc::new(state);
+
+    let payload = NormalizedRequest {
+        provider_id: Some("messaging.slack".into()),
+        provider_channel_id: Some("workspace-1".into()),
+        chat_id: Some("chat-1".into()),
+        text: Some("hi".into()),
+        ..Default::default()
+    };
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert("authorization", "Bearer not-a-jwt".parse().unwrap());
+
+    let err = handle_ingress(
+        "acme".into(),
+        Some("team".into()),
+        "slack".into(),
+        state,
+        json_body(&payload),
+        headers,
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(err.0, axum::http::StatusCode::UNAUTHORIZED);
+    assert_eq!(err.1.0.code(), Some("invalid_signature"));
+    assert!(bus.take_published().await.is_empty());
+}
+
+fn with_idempotency(state: Arc<GatewayState>) -> Arc<GatewayState> {
+    let mut state = Arc::into_inner(state).unwrap();
+    state.idempotency = Some(gsm_idempotency::IdempotencyGuard::new(
+        Arc::new(gsm_idempotency::InMemoryIdemStore::new()),
+        1,
+    ));
+    Arc::new(state)
+}
+
+#[tokio::test]
+async fn retried_requests_are_published_once() {
+    let bus = Arc::new(InMemoryBusClient::default());
+    let state = with_idempotency(batch_state(bus.clone(), 10));
+    // The provider's own fields travel in the body next to the normalized ones.
+    let with_event_id = serde_json::json!({
+        "providerId": "messaging.slack",
+        "providerChannelId": "workspace-1",
+        "chatId": "chat-1",
+        "text": "hi",
+        "event_id": "Ev42",
+        "event": {"type": "message"}
+    });
+    let mut without_id = with_event_id.clone();
+    without_id.as_object_mut().unwrap().remove("event_id");
+
+    let mut duplicates = Vec::new();
+    for body in [&with_event_id, &with_event_id, &without_id, &without_id] {
+        let response = handle_ingress(
+            "acme".into(),
+            Some("team".into()),
+            "slack".into(),
+            state.clone(),
+            serde_json::to_vec(body).unwrap().into(),
+            Default::default(),
+        )
+        .await
+        .expect("ingress should succeed");
+        duplicates.push(response.is_duplicate());
+    }
+
+    assert_eq!(duplicates, vec![false, true, false, false]);
+    let published = bus.take_published().await;
+    assert_eq!(published.len(), 3);
+    let first: gsm_core::ChannelMessage = serde_json::from_value(published[0].1.clone()).unwrap();
+    assert_eq!(first.payload["msg_id"], "Ev42");
+    assert_eq!(first.payload["metadata"]["raw"]["event_id"], "Ev42");
+}
+
+#[tokio::test]
+async fn signed_retries_are_answered_as_duplicates() {
+    let bus = Arc::new(InMemoryBusClient::default());
+    let state = with_idempotency(slack_signed_state(bus.clone()));
+    let body = serde_json::to_vec(&serde_json::json!({
+        "providerId": "messaging.slack",
+        "providerChannelId": "workspace-1",
+        "chatId": "chat-1",
+        "text": "hi",
+        "event_id": "Ev7"
+    }))
+    .unwrap();
+    let headers = slack_signature_headers(&body, time::OffsetDateTime::now_utc().unix_timestamp());
+
+    let mut duplicates = Vec::new();
+    for _ in 0..2 {
+        let response = handle_ingress(
+            "acme".into(),
+            Some("team".into()),
+            "slack".into(),
+            state.clone(),
+            body.clone().into(),
+            headers.clone(),
+        )
+        .await
+        .expect("a retry of a known event is not a replay error");
+        duplicates.push(response.is_duplicate());
+    }
+
+    assert_eq!(duplicates, vec![false, true]);
+    assert_eq!(bus.take_published().await.len(), 1);
+}
+
+#[tokio::test]
+async fn telegram_postback_tokens_are_resolved_on_ingress() {
+    use gsm_core::PostbackTokenStore;
+
+    let token_dir = std::env::temp_dir().join(format!("gsm-gateway-pb-{}", std::process::id()));
+    let data = serde_json::json!({"action": "approve", "request_id": "r".repeat(80)});
+    let token = gsm_core::DirPostbackTokenStore::new(&token_dir)
+        .issue(&data, std::time::Duration::from_secs(60));
+
+    let bus = Arc::new(InMemoryBusClient::default());
+    let mut state = Arc::into_inner(batch_state(bus.clone(), 10)).unwrap();
+    state.adapters = gsm_core::AdapterRegistry::default();
+    state.config.postback_token_dir = Some(token_dir);
+    let payload = NormalizedRequest {
+        provider_id: Some("messaging.slack".into()),
+        install_id: Some("install-a".into()),
+        chat_id: Some("chat-1".into()),
+        metadata: BTreeMap::from([("callback_data".to_string(), serde_json::json!(token))]),
+        ..Default::default()
+    };
+
+    handle_ingress(
+        "acme".into(),
+        Some("team".into()),
+        "telegram".into(),
+        Arc::new(state),
+        json_body(&payload),
+        Default::default(),
+    )
+    .await
+    .expect("ingress should succeed");
+
+    let published = bus.take_published().await;
+    let msg: gsm_core::ChannelMessage = serde_json::from_value(published[0].1.clone()).unwrap();
+    assert_eq!(msg.payload["metadata"]["postback"], data);
+}
+
+#[tokio::test]
+async fn correlation_stamps_are_stripped_from_postbacks() {
+    let bus = Arc::new(InMemoryBusClient::default());
+    let mut state = Arc::into_inner(batch_state(bus.clone(), 10)).unwrap();
+    state.adapters = gsm_core::AdapterRegistry::default();
+    let callback = r#"{"decision":"approve","_gsm":"expense-7~0~rs"}"#;
+    let payload = NormalizedRequest {
+        provider_id: Some("messaging.slack".into()),
+        install_id: Some("install-a".into()),
+        chat_id: Some("chat-1".into()),
+        metadata: BTreeMap::from([("callback_data".to_string(), serde_json::json!(callback))]),
+        ..Default::default()
+    };
+
+    handle_ingress(
+        "acme".into(),
+        Some("team".into()),
+        "telegram".into(),
+        Arc::new(state),
+        json_body(&payload),
+        Default::default(),
+    )
+    .await
+    .expect("ingress should succeed");
+
+    let published = bus.take_published().await;
+    let msg: gsm_core::ChannelMessage = serde_json::from_value(published[0].1.clone()).unwrap();
+    assert_eq!(
+        msg.payload["metadata"]["postback"],
+        serde_json::json!({"decision": "approve"})
+    );
+}
@@ -11,87 +11,49 @@ pub fn render_card(
     payload: &serde_json::Value,
 ) -> Result<MessageCard> {
     // Render every string field via Handlebars
-    let mut title = None;
-    if let Some(t) = &card.title {
-        title = Some(hbs.render_template(
-            t,
-            &json!({"envelope":env, "state":state, "payload":payload}),
-        )?);
+    let ctx = json!({"envelope":env, "state":state, "payload":payload});
+    let render = |template: &mut String| -> Result<()> {
+        *template = hbs.render_template(template, &ctx)?;
+        Ok(())
+    };
+    let mut card = card.clone();
+    if let Some(title) = card.title.as_mut() {
+        render(title)?;
     }
-    let mut body = vec![];
-    for b in &card.body {
-        match b {
-            crate::model::CardBlock::Text { text, markdown } => {
-                body.push(CoreBlock::Text {
-                    text: hbs.render_template(
-                        text,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                    markdown: markdown.unwrap_or(true),
-                });
-            }
-            crate::model::CardBlock::Fact { label, value } => {
-                body.push(CoreBlock::Fact {
-                    label: hbs.render_template(
-                        label,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                    value: hbs.render_template(
-                        value,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                });
+    if let Some(footer) = card.footer.as_mut() {
+        render(footer)?;
+    }
+    for block in &mut card.body {
+        match block {
+            CoreBlock::Text { text, .. } => render(text)?,
+            CoreBlock::Fact { label, value } => {
+                render(label)?;
+                render(value)?;
             }
-            crate::model::CardBlock::Image { url } => {
-                body.push(CoreBlock::Image {
-                    url: hbs.render_template(
-                        url,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                });
+            CoreBlock::Image { url, alt } => {
+                render(url)?;
+                if let Some(alt) = alt.as_mut() {
+                    render(alt)?;
+                }
             }
         }
     }
-    let mut actions = vec![];
-    for a in &card.actions {
-        match a {
-            crate::model::CardAction::OpenUrl { title, url, jwt } => {
-                actions.push(CoreAction::OpenUrl {
-                    title: hbs.render_template(
-                        title,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                    url: hbs.render_template(
-                        url,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                    jwt: jwt.unwrap_or(false),
-                })
-            }
-            crate::model::CardAction::Postback { title, data } => {
-                let title = hbs.render_template(
-                    title,
-                    &json!({"envelope":env, "state":state, "payload":payload}),
-                )?;
-                let data_json = json!(data);
-                actions.push(CoreAction::Postback {
-                    title,
-                    data: data_json,
-                });
+    for action in &mut card.actions {
+        match action {
+            CoreAction::OpenUrl { title, url, .. } => {
+                render(title)?;
+                render(url)?;
             }
+            CoreAction::Postback { title, .. } => render(title)?,
         }
     }
-    Ok(MessageCard {
-        title,
-        body,
-        actions,
-    })
+    Ok(card)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::{CardAction, CardBlock, CardNode};
+    use crate::model::CardNode;
     use gsm_core::{MessageEnvelope, Platform};
 
     fn handlebars() -> &'static Handlebars<'static> {
@@ -119,26 +81,27 @@ mod tests {
         let card = CardNode {
             title: Some("Ticket for {{envelope.chat_id}}".into()),
             body: vec![
-                CardBlock::Text {
+                CoreBlock::Text {
                     text: "Score: {{state.score}}".into(),
-                    markdown: Some(true),
+                    markdown: true,
                 },
-                CardBlock::Fact {
+                CoreBlock::Fact {
                     label: "Link".into(),
                     value: "{{payload.url}}".into(),
                 },
             ],
             actions: vec![
-                CardAction::OpenUrl {
+                CoreAction::OpenUrl {
                     title: "Open".into(),
                     url: "{{payload.url}}".into(),
-                    jwt: Some(false),
+                    jwt: false,
                 },
-                CardAction::Postback {
+                CoreAction::Postback {
                     title: "Ack".into(),
                     data: serde_json::json!({"done": true}),
                 },
             ],
+            ..Default::default()
         };
 
         let env = sample_envelope();
@@ -199,8 +199,7 @@ pub async fn run_flow(
                 thread_id: env.thread_id.clone(),
                 kind: OutKind::Text,
                 text: Some(out),
-                message_card: None,
-                adaptive_card: None,
+                card: None,
                 meta: env.context.clone(),
             };
             let team = tenant_ctx
@@ -228,8 +227,7 @@ pub async fn run_flow(
                 thread_id: env.thread_id.clone(),
                 kind: OutKind::Card,
                 text: None,
-                message_card: Some(card),
-                adaptive_card: None,
+                card: Some(card),
                 meta: env.context.clone(),
             };
             let team = tenant_ctx
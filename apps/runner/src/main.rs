@@ -261,8 +261,7 @@ async fn run_one(ctx: RunOneContext) -> Result<()> {
                 thread_id: ctx.env.thread_id.clone(),
                 kind: OutKind::Text,
                 text: Some(out),
-                message_card: None,
-                adaptive_card: None,
+                card: None,
                 meta: Default::default(),
             };
             emit_pending_auth_telemetry(&outmsg);
@@ -293,8 +292,7 @@ async fn run_one(ctx: RunOneContext) -> Result<()> {
                 thread_id: ctx.env.thread_id.clone(),
                 kind: OutKind::Card,
                 text: None,
-                message_card: Some(card),
-                adaptive_card: None,
+                card: Some(card),
                 meta: Default::default(),
             };
             emit_pending_auth_telemetry(&outmsg);
@@ -481,8 +479,8 @@ fn message_from_channel(channel: &ChannelMessage) -> Result<MessageEnvelope> {
 }
 
 fn emit_pending_auth_telemetry(out: &OutMessage) {
-    if let Some(card) = out.adaptive_card.as_ref()
-        && matches!(card.kind, gsm_core::messaging_card::MessageCardKind::Oauth)
+    if let Some(card) = out.card.as_ref()
+        && matches!(card.kind, gsm_core::MessageCardKind::Oauth)
         && let Some(oauth) = card.oauth.as_ref()
     {
         let labels = TelemetryLabels {
@@ -25,7 +25,7 @@ pub struct Node {
     pub tool: Option<ToolNode>,
     #[serde(default)]
     pub template: Option<TemplateNode>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "gsm_core::deserialize_flow_card")]
     pub card: Option<CardNode>,
     #[serde(default)]
     pub routes: Vec<String>,
@@ -90,46 +90,8 @@ pub struct TemplateNode {
     pub template: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct CardNode {
-    pub title: Option<String>,
-    #[serde(default)]
-    pub body: Vec<CardBlock>,
-    #[serde(default)]
-    pub actions: Vec<CardAction>,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(tag = "type")]
-pub enum CardBlock {
-    #[serde(rename = "text")]
-    Text {
-        text: String,
-        #[serde(default)]
-        markdown: Option<bool>,
-    },
-    #[serde(rename = "fact")]
-    Fact { label: String, value: String },
-    #[serde(rename = "image")]
-    Image { url: String },
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(tag = "type")]
-pub enum CardAction {
-    #[serde(rename = "openUrl")]
-    OpenUrl {
-        title: String,
-        url: String,
-        #[serde(default)]
-        jwt: Option<bool>,
-    },
-    #[serde(rename = "postback")]
-    Postback {
-        title: String,
-        data: serde_json::Value,
-    },
-}
+/// Flow card templates use the canonical card model; strings are Handlebars templates.
+pub type CardNode = gsm_core::MessageCard;
 
 impl Flow {
     pub fn load_from_file(path: &str) -> anyhow::Result<Self> {
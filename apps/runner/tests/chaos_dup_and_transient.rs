@@ -286,8 +286,7 @@ fn envelope_to_out(env: &MessageEnvelope) -> OutMessage {
         thread_id: env.thread_id.clone(),
         kind: OutKind::Text,
         text: env.text.clone(),
-        message_card: None,
-        adaptive_card: None,
+        card: None,
         meta: Default::default(),
     }
 }
@@ -22,8 +22,8 @@ use greentic_types::{
 };
 use gsm_core::{
     AdapterDescriptor, AdapterPackFailure, AdapterRegistry, DefaultAdapterPacksConfig,
-    MessagingAdapterKind, Platform, ProviderInstallState, default_adapter_pack_paths,
-    load_adapters_from_pack_files_with_failures,
+    MessagingAdapterKind, Platform, ProviderCapsOverrides, ProviderCapsRegistry,
+    ProviderInstallState, default_adapter_pack_paths, load_adapters_from_pack_files_with_failures,
 };
 use semver::Version;
 use serde::{Deserialize, Serialize};
@@ -40,6 +40,30 @@ fn main() -> Result<()> {
             no_default_packs,
             strict_adapters,
         } => handle_info(pack, packs_root, no_default_packs, strict_adapters),
+        CliCommand::Caps {
+            pack,
+            packs_root,
+            no_default_packs,
+            strict_adapters,
+            overrides,
+            schema,
+        } => {
+            if schema {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&gsm_core::ProviderCapabilitiesV2::json_schema())?
+                );
+                Ok(())
+            } else {
+                handle_caps(
+                    pack,
+                    packs_root,
+                    no_default_packs,
+                    strict_adapters,
+                    overrides,
+                )
+            }
+        }
         CliCommand::Dev { command } => handle_dev(command),
         CliCommand::Serve {
             kind,
@@ -96,6 +120,27 @@ enum CliCommand {
         #[arg(long)]
         strict_adapters: bool,
     },
+    /// Print the effective capabilities of each provider as JSON
+    Caps {
+        /// Path to a messaging pack (.yaml or .gtpack); can be repeated.
+        #[arg(long, value_name = "PATH")]
+        pack: Vec<PathBuf>,
+        /// Root directory that contains the packs folder (defaults to ./packs).
+        #[arg(long, value_name = "PATH")]
+        packs_root: Option<PathBuf>,
+        /// Disable loading default packs shipped in packs/messaging.
+        #[arg(long)]
+        no_default_packs: bool,
+        /// Fail if any adapter pack fails to load.
+        #[arg(long)]
+        strict_adapters: bool,
+        /// Capability overrides (defaults to .greentic/provider_caps.json when present).
+        #[arg(long, value_name = "PATH")]
+        overrides: Option<PathBuf>,
+        /// Print the JSON Schema for capabilities v2 instead.
+        #[arg(long)]
+        schema: bool,
+    },
     /// Developer utilities (local stack helpers)
     Dev {
         #[command(subcommand)]
@@ -248,12 +293,27 @@ enum TestCommand {
         fixture: String,
         #[arg(long)]
         dry_run: bool,
+        /// Fail when the fixture card has accessibility lint findings
+        #[arg(long)]
+        a11y_strict: bool,
     },
     All {
         #[arg(long)]
         dry_run: bool,
+        /// Fail when any fixture card has accessibility lint findings
+        #[arg(long)]
+        a11y_strict: bool,
     },
     GenGolden,
+    /// Render all cards across platforms and tiers and diff against the golden matrix
+    Matrix {
+        /// Rewrite the golden matrix instead of failing on differences
+        #[arg(long)]
+        update: bool,
+        /// Write the Markdown diff report to this path
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -467,6 +527,41 @@ fn handle_info(
     Ok(())
 }
 
+fn handle_caps(
+    pack: Vec<PathBuf>,
+    packs_root: Option<PathBuf>,
+    no_default_packs: bool,
+    strict_adapters: bool,
+    overrides: Option<PathBuf>,
+) -> Result<()> {
+    let packs_root = packs_root.unwrap_or_else(|| PathBuf::from("packs"));
+    let load = load_adapter_registry_for_cli(
+        Some(packs_root.clone()),
+        &pack,
+        no_default_packs,
+        strict_adapters,
+    )?;
+    let extensions =
+        match gsm_core::load_provider_extensions_from_pack_files(&packs_root, &load.pack_paths) {
+            Ok(extensions) => extensions,
+            Err(err) => {
+                eprintln!("warning: failed to load provider extensions from packs: {err}");
+                Default::default()
+            }
+        };
+    let overrides_path = overrides.or_else(|| {
+        let default = PathBuf::from(".greentic/provider_caps.json");
+        default.exists().then_some(default)
+    });
+    let overrides = match overrides_path {
+        Some(path) => ProviderCapsOverrides::load(&path)?,
+        None => ProviderCapsOverrides::default(),
+    };
+    let caps = ProviderCapsRegistry::from_packs(&load.registry, &extensions, &overrides);
+    println!("{}", serde_json::to_string_pretty(&caps.records())?);
+    Ok(())
+}
+
 fn handle_dev(command: DevCommand) -> Result<()> {
     match command {
         DevCommand::Up {
@@ -1728,22 +1823,47 @@ fn handle_test(command: TestCommand) -> Result<()> {
     match command {
         TestCommand::Fixtures => run_messaging_test_cli(&["fixtures"]),
         TestCommand::Adapters => run_messaging_test_cli(&["adapters"]),
-        TestCommand::Run { fixture, dry_run } => {
+        TestCommand::Run {
+            fixture,
+            dry_run,
+            a11y_strict,
+        } => {
             let mut args = vec!["run".to_string(), fixture];
             if dry_run {
                 args.push("--dry-run".into());
             }
+            if a11y_strict {
+                args.push("--a11y-strict".into());
+            }
             run_messaging_test_cli_str(args)
         }
-        TestCommand::All { dry_run } => {
+        TestCommand::All {
+            dry_run,
+            a11y_strict,
+        } => {
             if !dry_run {
                 return Err(anyhow!(
                     "`greentic-messaging test all` requires --dry-run for safety"
                 ));
             }
-            run_messaging_test_cli(&["all", "--dry-run"])
+            if a11y_strict {
+                run_messaging_test_cli(&["all", "--dry-run", "--a11y-strict"])
+            } else {
+                run_messaging_test_cli(&["all", "--dry-run"])
+            }
         }
         TestCommand::GenGolden => run_messaging_test_cli(&["gen-golden"]),
+        TestCommand::Matrix { update, report } => {
+            let mut args = vec!["matrix".to_string()];
+            if update {
+                args.push("--update".into());
+            }
+            if let Some(report) = report {
+                args.push("--report".into());
+                args.push(report.display().to_string());
+            }
+            run_messaging_test_cli_str(args)
+        }
     }
 }
 
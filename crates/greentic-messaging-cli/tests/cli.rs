@@ -136,6 +136,68 @@ fn info_lists_adapters_from_pack() {
     );
 }
 
+#[test]
+fn caps_prints_effective_capabilities_per_provider() {
+    let dir = tempfile::tempdir().unwrap();
+    let overrides = dir.path().join("caps.json");
+    std::fs::write(
+        &overrides,
+        serde_json::json!({
+            "providers": {
+                "slack-main": {
+                    "version": "v1",
+                    "supports_adaptive_cards": false,
+                    "supports_markdown": true,
+                    "supports_html": false,
+                    "supports_images": true,
+                    "supports_buttons": true,
+                    "supports_threads": true,
+                    "max_text_len": 3000,
+                    "max_payload_bytes": null,
+                    "max_actions": 5,
+                    "max_buttons_per_row": null,
+                    "max_total_buttons": null
+                }
+            }
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let packs_root = concat!(env!("CARGO_MANIFEST_DIR"), "/../../packs");
+    let stdout = run_and_capture(&[
+        "caps",
+        "--packs-root",
+        packs_root,
+        "--no-default-packs",
+        "--pack",
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../packs/messaging/slack.yaml"
+        ),
+        "--pack",
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../packs/messaging/teams.yaml"
+        ),
+        "--overrides",
+        overrides.to_str().unwrap(),
+    ]);
+    let records: Vec<Value> = serde_json::from_str(&stdout).expect("caps output is JSON");
+    let record = |id: &str| {
+        records
+            .iter()
+            .find(|r| r["id"] == id)
+            .unwrap_or_else(|| panic!("{id} missing from:\n{stdout}"))
+    };
+    assert_eq!(record("slack-main")["caps_source"], "override");
+    assert_eq!(record("slack-main")["capabilities"]["max_actions"], 5);
+    assert_eq!(record("teams-main")["caps_source"], "from_pack_manifest");
+    assert_eq!(
+        record("teams-main")["capabilities"]["supports_adaptive_cards"],
+        true
+    );
+}
+
 fn write_provider_pack(path: &std::path::Path) {
     let mut extensions = BTreeMap::new();
     extensions.insert(
@@ -51,6 +51,10 @@ pub struct Cli {
     #[arg(long = "platform", global = true)]
     pub platform: Option<String>,
 
+    /// Fail fixtures whose cards have accessibility lint findings (they are always printed)
+    #[arg(long = "a11y-strict", global = true)]
+    pub a11y_strict: bool,
+
     #[command(subcommand)]
     pub command: CliCommand,
 }
@@ -79,6 +83,26 @@ pub enum CliCommand {
     },
     /// Generate golden files from artifacts
     GenGolden,
+    /// Render every card across all platforms and tiers and compare with the golden matrix
+    Matrix {
+        /// Card directories to render (repeatable)
+        #[arg(
+            long = "cards",
+            value_name = "DIR",
+            num_args = 0..,
+            default_values = ["libs/cards/samples", "libs/core/tests/fixtures/cards"]
+        )]
+        cards: Vec<PathBuf>,
+        /// Golden directory holding one file per card/platform/tier
+        #[arg(long, default_value = "crates/messaging-test/tests/golden/matrix")]
+        golden: PathBuf,
+        /// Rewrite the golden directory instead of failing on differences
+        #[arg(long)]
+        update: bool,
+        /// Optional Markdown diff report output path
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
     /// Work with provider gtpack bundles
     Packs {
         #[command(subcommand)]
@@ -282,6 +306,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn matrix_defaults_to_samples_and_fixtures() {
+        let cli = Cli::try_parse_from(["cli", "matrix", "--update"]).expect("parse cli");
+        match cli.command {
+            CliCommand::Matrix { cards, update, .. } => {
+                assert!(update);
+                assert_eq!(
+                    cards,
+                    vec![
+                        PathBuf::from("libs/cards/samples"),
+                        PathBuf::from("libs/core/tests/fixtures/cards"),
+                    ]
+                );
+            }
+            other => panic!("unexpected command parsed: {other:?}"),
+        }
+    }
+
     #[test]
     fn pack_flags_parse_for_run() {
         let cli = Cli::try_parse_from([
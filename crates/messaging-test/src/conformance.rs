@@ -250,7 +250,7 @@ fn run_setup_step(
                     }
                     OutKind::Card => {
                         let title = message
-                            .message_card
+                            .card
                             .as_ref()
                             .and_then(|card| card.title.clone())
                             .unwrap_or_else(|| "(untitled card)".into());
@@ -583,8 +583,7 @@ fn build_stub_out_message(fixture: &MessageEnvelope, adapter: &AdapterDescriptor
         thread_id: fixture.thread_id.clone(),
         kind: OutKind::Text,
         text: Some("conformance dry-run".to_string()),
-        message_card: None,
-        adaptive_card: None,
+        card: None,
         meta: {
             let mut map = BTreeMap::new();
             map.insert("source".into(), Value::String("conformance".into()));
@@ -848,7 +847,7 @@ pub struct Node {
     pub tool: Option<ToolNode>,
     #[serde(default)]
     pub template: Option<TemplateNode>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "gsm_core::deserialize_flow_card")]
     pub card: Option<CardNode>,
     #[serde(default)]
     pub routes: Vec<String>,
@@ -911,43 +910,8 @@ pub struct TemplateNode {
     pub template: String,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct CardNode {
-    pub title: Option<String>,
-    #[serde(default)]
-    pub body: Vec<CardBlock>,
-    #[serde(default)]
-    pub actions: Vec<CardAction>,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(tag = "type")]
-pub enum CardBlock {
-    #[serde(rename = "text")]
-    Text {
-        text: String,
-        #[serde(default)]
-        markdown: Option<bool>,
-    },
-    #[serde(rename = "fact")]
-    Fact { label: String, value: String },
-    #[serde(rename = "image")]
-    Image { url: String },
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(tag = "type")]
-pub enum CardAction {
-    #[serde(rename = "openUrl")]
-    OpenUrl {
-        title: String,
-        url: String,
-        #[serde(default)]
-        jwt: Option<bool>,
-    },
-    #[serde(rename = "postback")]
-    Postback { title: String, data: Value },
-}
+/// Flow card templates use the canonical card model; strings are Handlebars templates.
+pub type CardNode = gsm_core::MessageCard;
 
 impl Flow {
     pub fn load_from_file(path: &str) -> anyhow::Result<Self> {
@@ -1029,8 +993,7 @@ fn run_flow(flow: &Flow, env: &MessageEnvelope) -> Result<FlowRunOutcome> {
                 thread_id: env.thread_id.clone(),
                 kind: OutKind::Text,
                 text: Some(out),
-                message_card: None,
-                adaptive_card: None,
+                card: None,
                 meta: Default::default(),
             });
         }
@@ -1045,8 +1008,7 @@ fn run_flow(flow: &Flow, env: &MessageEnvelope) -> Result<FlowRunOutcome> {
                 thread_id: env.thread_id.clone(),
                 kind: OutKind::Card,
                 text: None,
-                message_card: Some(card),
-                adaptive_card: None,
+                card: Some(card),
                 meta: Default::default(),
             });
         }
@@ -1095,81 +1057,43 @@ fn render_card(
     state: &Value,
     payload: &Value,
 ) -> Result<gsm_core::MessageCard> {
-    let mut title = None;
-    if let Some(t) = &card.title {
-        title = Some(hbs.render_template(
-            t,
-            &json!({"envelope":env, "state":state, "payload":payload}),
-        )?);
+    let ctx = json!({"envelope":env, "state":state, "payload":payload});
+    let render = |template: &mut String| -> Result<()> {
+        *template = hbs.render_template(template, &ctx)?;
+        Ok(())
+    };
+    let mut card = card.clone();
+    if let Some(title) = card.title.as_mut() {
+        render(title)?;
     }
-    let mut body = vec![];
-    for b in &card.body {
-        match b {
-            CardBlock::Text { text, markdown } => {
-                body.push(gsm_core::CardBlock::Text {
-                    text: hbs.render_template(
-                        text,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                    markdown: markdown.unwrap_or(true),
-                });
-            }
-            CardBlock::Fact { label, value } => {
-                body.push(gsm_core::CardBlock::Fact {
-                    label: hbs.render_template(
-                        label,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                    value: hbs.render_template(
-                        value,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                });
+    if let Some(footer) = card.footer.as_mut() {
+        render(footer)?;
+    }
+    for block in &mut card.body {
+        match block {
+            gsm_core::CardBlock::Text { text, .. } => render(text)?,
+            gsm_core::CardBlock::Fact { label, value } => {
+                render(label)?;
+                render(value)?;
             }
-            CardBlock::Image { url } => {
-                body.push(gsm_core::CardBlock::Image {
-                    url: hbs.render_template(
-                        url,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                });
+            gsm_core::CardBlock::Image { url, alt } => {
+                render(url)?;
+                if let Some(alt) = alt.as_mut() {
+                    render(alt)?;
+                }
             }
         }
     }
-    let mut actions = vec![];
-    for a in &card.actions {
-        match a {
-            CardAction::OpenUrl { title, url, jwt } => {
-                actions.push(gsm_core::CardAction::OpenUrl {
-                    title: hbs.render_template(
-                        title,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                    url: hbs.render_template(
-                        url,
-                        &json!({"envelope":env, "state":state, "payload":payload}),
-                    )?,
-                    jwt: jwt.unwrap_or(false),
-                })
-            }
-            CardAction::Postback { title, data } => {
-                let title = hbs.render_template(
-                    title,
-                    &json!({"envelope":env, "state":state, "payload":payload}),
-                )?;
-                let data_json = json!(data);
-                actions.push(gsm_core::CardAction::Postback {
-                    title,
-                    data: data_json,
-                });
+    for action in &mut card.actions {
+        match action {
+            gsm_core::CardAction::OpenUrl { title, url, .. } => {
+                render(title)?;
+                render(url)?;
             }
+            gsm_core::CardAction::Postback { title, .. } => render(title)?,
         }
     }
-    Ok(gsm_core::MessageCard {
-        title,
-        body,
-        actions,
-    })
+    Ok(card)
 }
 
 fn run_qa_offline(cfg: &QaNode, env: &MessageEnvelope, state: &mut Value) -> Result<()> {
@@ -659,6 +659,8 @@ fn run_ingress_stage(
         default_packs: Default::default(),
         extra_pack_paths: Vec::new(),
         install_store_path: None,
+        batch: Default::default(),
+        postback_token_dir: None,
     };
     let store = std::sync::Arc::new(InMemoryProviderInstallStore::default());
     store.insert(install_state.clone());
@@ -670,6 +672,9 @@ fn run_ingress_stage(
         install_store: store,
         workers: Default::default(),
         worker_default: None,
+        replay_cache: Default::default(),
+        bot_framework: None,
+        idempotency: None,
     };
 
     let team = tenant_ctx.team.as_ref().map(|t| t.to_string());
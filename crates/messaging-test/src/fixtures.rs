@@ -2,13 +2,13 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use gsm_core::AdaptiveMessageCard;
+use gsm_core::MessageCard;
 use serde_json::Value;
 
 pub struct Fixture {
     pub id: String,
     pub path: PathBuf,
-    pub card: AdaptiveMessageCard,
+    pub card: MessageCard,
 }
 
 pub fn discover(root: &Path) -> Result<Vec<Fixture>> {
@@ -37,7 +37,7 @@ pub fn discover(root: &Path) -> Result<Vec<Fixture>> {
         let mut normalized = value.clone();
         normalize_adaptive(&mut normalized);
         let card = if is_adaptive_card(&normalized) {
-            AdaptiveMessageCard {
+            MessageCard {
                 adaptive: Some(normalized),
                 ..Default::default()
             }
@@ -58,7 +58,7 @@ fn is_adaptive_card(value: &Value) -> bool {
         .unwrap_or(false)
 }
 
-fn normalize_adaptive(value: &mut Value) {
+pub(crate) fn normalize_adaptive(value: &mut Value) {
     ensure_version(value);
     match value {
         Value::Object(map) => {
@@ -3,6 +3,7 @@ mod cli;
 mod conformance;
 mod e2e;
 mod fixtures;
+mod matrix;
 mod packs;
 mod run;
 
@@ -0,0 +1,139 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use gsm_core::messaging_card::{MatrixCard, MessageCardEngine, RenderMatrix};
+use serde_json::Value;
+
+use crate::fixtures::normalize_adaptive;
+
+#[derive(Debug, Clone)]
+pub struct MatrixOptions {
+    pub card_dirs: Vec<PathBuf>,
+    pub golden: PathBuf,
+    pub update: bool,
+    pub report: Option<PathBuf>,
+}
+
+/// Renders all cards, prints the diff against the golden directory and either rewrites the
+/// goldens (`--update`) or fails when they differ.
+pub fn run_matrix(engine: &MessageCardEngine, options: MatrixOptions) -> Result<()> {
+    let mut cards = Vec::new();
+    for dir in &options.card_dirs {
+        cards.extend(load_cards(engine, dir)?);
+    }
+    let matrix = engine.render_matrix(&cards);
+    let golden = RenderMatrix::load_golden(&options.golden)?;
+    let diff = matrix.diff(&golden);
+    let report = diff.to_markdown();
+
+    println!(
+        "rendered {} card(s) into {} cell(s)",
+        cards.len(),
+        matrix.len()
+    );
+    print!("{report}");
+    if let Some(path) = &options.report {
+        fs::write(path, &report)
+            .with_context(|| format!("failed to write report {}", path.display()))?;
+    }
+
+    if options.update {
+        matrix.write_golden(&options.golden)?;
+        println!("golden matrix updated at {}", options.golden.display());
+        return Ok(());
+    }
+    if diff.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{} render change(s); rerun with --update to accept them",
+            diff.changes.len()
+        ))
+    }
+}
+
+/// Loads every JSON/YAML card in `dir`, keyed as `<dir name>/<file stem>`. Cards that fail to
+/// parse are reported and skipped.
+fn load_cards(engine: &MessageCardEngine, dir: &Path) -> Result<Vec<MatrixCard>> {
+    let label = dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("cards");
+    let mut paths = Vec::new();
+    for entry in
+        fs::read_dir(dir).with_context(|| format!("failed to read cards dir {}", dir.display()))?
+    {
+        let path = entry.context("dir entry")?.path();
+        let ext = path.extension().and_then(|s| s.to_str());
+        if path.is_file() && matches!(ext, Some("json") | Some("yaml") | Some("yml")) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut cards = Vec::new();
+    for path in paths {
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unnamed");
+        let id = format!("{label}/{stem}");
+        match load_card(engine, &id, &path) {
+            Ok(card) => cards.push(card),
+            Err(err) => eprintln!("skipping {id}: {err:#}"),
+        }
+    }
+    Ok(cards)
+}
+
+fn load_card(engine: &MessageCardEngine, id: &str, path: &Path) -> Result<MatrixCard> {
+    let data = fs::read_to_string(path).context("read card")?;
+    let mut value: Value = if matches!(
+        path.extension().and_then(|s| s.to_str()),
+        Some("yaml") | Some("yml")
+    ) {
+        serde_yaml::from_str(&data)?
+    } else {
+        serde_json::from_str(&data)?
+    };
+    normalize_adaptive(&mut value);
+    MatrixCard::from_value(engine, id, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn matrix_update_then_check_passes() {
+        let cards = tempdir().unwrap();
+        fs::write(
+            cards.path().join("hello.json"),
+            json!({"title": "Hi", "body": [{"type": "text", "text": "Hello"}]}).to_string(),
+        )
+        .unwrap();
+        fs::write(cards.path().join("broken.json"), "{").unwrap();
+        let golden = tempdir().unwrap();
+        let engine = MessageCardEngine::bootstrap();
+        let options = MatrixOptions {
+            card_dirs: vec![cards.path().to_path_buf()],
+            golden: golden.path().join("matrix"),
+            update: false,
+            report: None,
+        };
+
+        assert!(run_matrix(&engine, options.clone()).is_err());
+        run_matrix(
+            &engine,
+            MatrixOptions {
+                update: true,
+                ..options.clone()
+            },
+        )
+        .unwrap();
+        run_matrix(&engine, options).unwrap();
+    }
+}
@@ -378,8 +378,7 @@ pub fn run_pack_live_egress(
         thread_id,
         kind: OutKind::Text,
         text: Some(text),
-        message_card: None,
-        adaptive_card: None,
+        card: None,
         meta: Default::default(),
     };
 
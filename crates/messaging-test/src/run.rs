@@ -12,11 +12,13 @@ use crate::cli::{Cli, CliCommand, PacksCommand, RunnerTransport};
 use crate::conformance::{self, ConformanceReport, ConformanceStatus};
 use crate::e2e;
 use crate::fixtures::{Fixture, discover};
+use crate::matrix;
 use crate::packs::{self, PackRunReport};
 use greentic_types::{EnvId, TeamId, TenantCtx, TenantId};
 use gsm_core::{
     AdapterDescriptor, AdapterRegistry, HttpRunnerClient, LoggingRunnerClient, OutKind, OutMessage,
-    Platform, RunnerClient, infer_platform_from_adapter_name, messaging_card::MessageCardEngine,
+    Platform, RunnerClient, infer_platform_from_adapter_name,
+    messaging_card::{MessageCardEngine, MessageCardIr, lint_ir},
 };
 
 struct PackAdapter {
@@ -60,6 +62,20 @@ impl RunContext {
             } => self.run_interactive(fixture, dry_run),
             CliCommand::All { dry_run } => self.run_all(dry_run),
             CliCommand::GenGolden => self.gen_golden(),
+            CliCommand::Matrix {
+                ref cards,
+                ref golden,
+                update,
+                ref report,
+            } => matrix::run_matrix(
+                &self.engine,
+                matrix::MatrixOptions {
+                    card_dirs: cards.clone(),
+                    golden: golden.clone(),
+                    update,
+                    report: report.clone(),
+                },
+            ),
             CliCommand::Packs { ref command } => self.packs(command.as_ref()),
             CliCommand::E2e {
                 packs,
@@ -543,7 +559,10 @@ impl RunContext {
         if let Some(adaptive) = card.adaptive.as_mut() {
             flatten_column_sets(adaptive);
         }
-        let spec = self.engine.render_spec(&card).context("render spec")?;
+        let spec = self.engine.render_card_spec(&card).context("render spec")?;
+        if let Some(ir) = spec.as_card() {
+            self.check_accessibility(fixture, ir)?;
+        }
         for adapter in adapters {
             if !adapter.enabled {
                 println!(" - {}: disabled", adapter.name.as_str());
@@ -568,6 +587,27 @@ impl RunContext {
         Ok(())
     }
 
+    /// Prints accessibility findings for the fixture card; fails the run in strict mode.
+    fn check_accessibility(&self, fixture: &Fixture, ir: &MessageCardIr) -> Result<()> {
+        let findings = lint_ir(ir);
+        for finding in &findings {
+            println!(
+                " ! {} at {}: {}",
+                finding.code,
+                finding.path.as_deref().unwrap_or("/"),
+                finding.message.as_deref().unwrap_or_default()
+            );
+        }
+        if self.cli.a11y_strict && !findings.is_empty() {
+            return Err(anyhow!(
+                "fixture {} has {} accessibility finding(s)",
+                fixture.id,
+                findings.len()
+            ));
+        }
+        Ok(())
+    }
+
     fn persist_artifacts(
         &self,
         fixture: &Fixture,
@@ -705,6 +745,11 @@ impl RunContext {
         runner: &dyn RunnerClient,
     ) -> Result<()> {
         println!("Processing {}", fixture.id);
+        if let Ok(spec) = self.engine.render_card_spec(&fixture.card)
+            && let Some(ir) = spec.as_card()
+        {
+            self.check_accessibility(fixture, ir)?;
+        }
         let chat_id = self.pack_chat_id()?;
         for adapter in adapters {
             if !adapter.target.enabled {
@@ -804,8 +849,7 @@ impl RunContext {
             thread_id: None,
             kind: OutKind::Card,
             text: None,
-            message_card: None,
-            adaptive_card: Some(fixture.card.clone()),
+            card: Some(fixture.card.clone()),
             meta,
         }
     }
@@ -116,8 +116,7 @@ async fn run_slack_e2e(token: String, channel: String) -> Result<()> {
         thread_id: None,
         kind: OutKind::Card,
         text: None,
-        message_card: Some(card),
-        adaptive_card: None,
+        card: Some(card),
         meta: Default::default(),
     };
 
@@ -12,7 +12,7 @@ use gsm_core::messaging_card::{MessageCardEngine, MessageCardKind, RenderSpec};
 use gsm_core::oauth::{OauthClient, ReqwestTransport};
 use gsm_core::platforms::teams::TeamsSender;
 use gsm_core::prelude::DefaultResolver;
-use gsm_core::{AdaptiveMessageCard, NodeError, OutKind, OutMessage, Platform, TenantCtx};
+use gsm_core::{NodeError, OutKind, OutMessage, Platform, TenantCtx};
 use gsm_dlq::{DlqError, DlqPublisher};
 use gsm_egress_common::{
     egress::bootstrap,
@@ -217,7 +217,7 @@ async fn build_outbound(
 ) -> Result<OutboundMessage, anyhow::Error> {
     let channel = out.chat_id.clone();
 
-    if let Some(card) = &out.adaptive_card {
+    if let Some(card) = out.card.as_ref().filter(|card| card.is_rich()) {
         match render_adaptive_card(out, ctx, card, engine, oauth_client).await {
             Ok(payload) => {
                 return Ok(OutboundMessage {
@@ -240,7 +240,7 @@ async fn build_outbound(
         }),
         OutKind::Card => {
             let card = out
-                .message_card
+                .card
                 .as_ref()
                 .ok_or_else(|| anyhow!("missing card"))?;
             let adaptive = to_teams_adaptive(card, out)?;
@@ -256,7 +256,7 @@ async fn build_outbound(
 async fn render_adaptive_card(
     out: &OutMessage,
     ctx: &MessageContext,
-    card: &AdaptiveMessageCard,
+    card: &gsm_core::MessageCard,
     engine: &MessageCardEngine,
     oauth_client: Option<&OauthClient<ReqwestTransport>>,
 ) -> Result<serde_json::Value> {
@@ -277,7 +277,7 @@ async fn render_adaptive_card(
     }
 
     let spec = engine
-        .render_spec(&working)
+        .render_card_spec(&working)
         .map_err(|err| anyhow!("message card normalization failed: {err}"))?;
     let snapshot = engine
         .render_snapshot_tracked("teams", &spec)
@@ -408,13 +408,13 @@ mod tests {
     #[tokio::test]
     async fn build_outbound_card_wraps_payload() {
         let mut out = sample_out(OutKind::Card);
-        out.message_card = Some(gsm_core::MessageCard {
+        out.card = Some(gsm_core::MessageCard {
             title: Some("Title".into()),
             body: vec![gsm_core::CardBlock::Text {
                 text: "Body".into(),
                 markdown: false,
             }],
-            actions: vec![],
+            ..Default::default()
         });
         let engine = MessageCardEngine::bootstrap();
         let ctx = context_from_out(&out);
@@ -431,8 +431,7 @@ mod tests {
             thread_id: None,
             kind,
             text: None,
-            message_card: None,
-            adaptive_card: None,
+            card: None,
             meta: Default::default(),
         }
     }
@@ -146,8 +146,7 @@ async fn run_teams_e2e(
             thread_id: None,
             kind: OutKind::Card,
             text: None,
-            message_card: None,
-            adaptive_card: None,
+            card: None,
             meta: Default::default(),
         },
     )
@@ -163,7 +163,7 @@ where
         return Ok(());
     }
     let mut drop_adaptive = false;
-    if let (Some(card), Some(client)) = (out.adaptive_card.as_mut(), oauth_client)
+    if let (Some(card), Some(client)) = (out.card.as_mut(), oauth_client)
         && matches!(card.kind, MessageCardKind::Oauth)
         && let Err(err) = ensure_oauth_start_url(card, &out.ctx, client, None).await
     {
@@ -171,11 +171,11 @@ where
         drop_adaptive = true;
     }
     if drop_adaptive {
-        out.adaptive_card = None;
+        out.card = None;
     }
 
     let ctx = context_from_out(&out);
-    if let Some(card) = out.adaptive_card.as_ref()
+    if let Some(card) = out.card.as_ref()
         && matches!(card.kind, MessageCardKind::Oauth)
         && let Some(oauth) = card.oauth.as_ref()
     {
@@ -262,8 +262,7 @@ mod tests {
             thread_id: thread.map(|s| s.into()),
             kind: OutKind::Text,
             text: Some("hello".into()),
-            message_card: None,
-            adaptive_card: None,
+            card: None,
             meta: Default::default(),
         }
     }
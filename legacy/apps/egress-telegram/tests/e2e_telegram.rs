@@ -131,8 +131,7 @@ async fn run_telegram_e2e(token: String, chat: ChatSpecifier) -> Result<()> {
         thread_id: None,
         kind: OutKind::Card,
         text: None,
-        message_card: Some(card),
-        adaptive_card: None,
+        card: Some(card),
         meta: Default::default(),
     };
 
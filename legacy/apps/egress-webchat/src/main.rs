@@ -16,7 +16,7 @@ use gsm_backpressure::BackpressureLimiter;
 use gsm_core::messaging_card::ensure_oauth_start_url;
 use gsm_core::messaging_card::{MessageCardEngine, MessageCardKind, RenderSpec};
 use gsm_core::oauth::{OauthClient, ReqwestTransport};
-use gsm_core::{AdaptiveMessageCard, OutMessage, Platform, TenantCtx};
+use gsm_core::{OutMessage, Platform, TenantCtx};
 use gsm_dlq::{DlqError, DlqPublisher};
 use gsm_egress_common::{
     egress::bootstrap,
@@ -338,7 +338,7 @@ async fn translate_payloads(
     engine: &MessageCardEngine,
     oauth_client: Option<&OauthClient<ReqwestTransport>>,
 ) -> Result<Vec<Value>> {
-    if let Some(card) = &out.adaptive_card {
+    if let Some(card) = out.card.as_ref().filter(|card| card.is_rich()) {
         match render_webchat_card(out, ctx, card, engine, oauth_client).await {
             Ok(value) => return Ok(vec![value]),
             Err(err) => {
@@ -352,7 +352,7 @@ async fn translate_payloads(
 async fn render_webchat_card(
     out: &OutMessage,
     ctx: &MessageContext,
-    card: &AdaptiveMessageCard,
+    card: &gsm_core::MessageCard,
     engine: &MessageCardEngine,
     oauth_client: Option<&OauthClient<ReqwestTransport>>,
 ) -> Result<Value> {
@@ -373,7 +373,7 @@ async fn render_webchat_card(
     }
 
     let spec = engine
-        .render_spec(&working)
+        .render_card_spec(&working)
         .map_err(|err| anyhow!("message card normalization failed: {err}"))?;
     let snapshot = engine
         .render_snapshot_tracked("webchat", &spec)
@@ -177,7 +177,7 @@ async fn handle_message(
 
     let mut translated_out = out.clone();
     let mut drop_adaptive = false;
-    if let (Some(card), Some(client)) = (translated_out.adaptive_card.as_mut(), oauth_client)
+    if let (Some(card), Some(client)) = (translated_out.card.as_mut(), oauth_client)
         && matches!(card.kind, MessageCardKind::Oauth)
         && let Err(err) = ensure_oauth_start_url(card, &translated_out.ctx, client, None).await
     {
@@ -185,10 +185,10 @@ async fn handle_message(
         drop_adaptive = true;
     }
     if drop_adaptive {
-        translated_out.adaptive_card = None;
+        translated_out.card = None;
     }
 
-    if let Some(card) = translated_out.adaptive_card.as_ref()
+    if let Some(card) = translated_out.card.as_ref()
         && matches!(card.kind, MessageCardKind::Oauth)
         && let Some(oauth) = card.oauth.as_ref()
     {
@@ -412,8 +412,7 @@ mod tests {
             thread_id: None,
             kind: OutKind::Text,
             text: Some("hi".into()),
-            message_card: None,
-            adaptive_card: None,
+            card: None,
             meta,
         }
     }
@@ -119,12 +119,11 @@ async fn run_webex_e2e(token: String, room_id: String) -> Result<()> {
         thread_id: None,
         kind: OutKind::Card,
         text: None,
-        message_card: Some(card),
-        adaptive_card: None,
+        card: Some(card),
         meta: Default::default(),
     };
 
-    let mut adaptive_card = to_teams_adaptive(out.message_card.as_ref().unwrap(), &out)
+    let mut adaptive_card = to_teams_adaptive(out.card.as_ref().unwrap(), &out)
         .context("failed to translate card to adaptive")?;
     if let Some(obj) = adaptive_card.as_object_mut() {
         obj.insert("version".into(), Value::String("1.3".into()));
@@ -115,7 +115,7 @@ async fn main() -> Result<()> {
         }
 
         let mut drop_adaptive = false;
-        if let (Some(card), Some(client)) = (out.adaptive_card.as_mut(), oauth_client.as_deref())
+        if let (Some(card), Some(client)) = (out.card.as_mut(), oauth_client.as_deref())
             && let Err(err) = ensure_oauth_start_url(card, &out.ctx, client, None).await
         {
             tracing::warn!(
@@ -126,11 +126,11 @@ async fn main() -> Result<()> {
             drop_adaptive = true;
         }
         if drop_adaptive {
-            out.adaptive_card = None;
+            out.card = None;
         }
 
         let ctx = context_from_out(&out);
-        if let Some(card) = out.adaptive_card.as_ref()
+        if let Some(card) = out.card.as_ref()
             && matches!(card.kind, MessageCardKind::Oauth)
             && let Some(oauth) = card.oauth.as_ref()
         {
@@ -284,7 +284,7 @@ struct FallbackLink {
 }
 
 fn determine_fallback_link(cfg: &AppConfig, out: &OutMessage, text: &str) -> FallbackLink {
-    if let Some(card) = &out.adaptive_card
+    if let Some(card) = &out.card
         && matches!(card.kind, MessageCardKind::Oauth)
         && let Some(oauth) = &card.oauth
         && let Some(start_url) = &oauth.start_url
@@ -301,10 +301,9 @@ fn determine_fallback_link(cfg: &AppConfig, out: &OutMessage, text: &str) -> Fal
     }
 
     let title = out
-        .message_card
+        .card
         .as_ref()
         .and_then(|c| c.title.clone())
-        .or_else(|| out.adaptive_card.as_ref().and_then(|c| c.title.clone()))
         .unwrap_or_else(|| text.to_string());
 
     let url = secure_action_url(out, "fallback", &cfg.fallback_url);
@@ -438,9 +437,8 @@ async fn send_template(
 mod tests {
     use super::*;
     use gsm_core::make_tenant_ctx;
-    use gsm_core::messaging_card::{
-        MessageCard as AdaptiveCard, MessageCardKind, OauthCard, OauthProvider,
-    };
+    use gsm_core::MessageCard;
+    use gsm_core::messaging_card::{OauthCard, OauthProvider};
 
     fn sample_message(timestamp_offset_hours: i64) -> OutMessage {
         let last = OffsetDateTime::now_utc() - Duration::hours(timestamp_offset_hours);
@@ -460,8 +458,7 @@ mod tests {
             thread_id: None,
             kind: OutKind::Text,
             text: Some("Hello".into()),
-            message_card: None,
-            adaptive_card: None,
+            card: None,
             meta: meta.into_iter().collect(),
         }
     }
@@ -501,7 +498,7 @@ mod tests {
     fn oauth_message(start_url: &str) -> OutMessage {
         let mut out = sample_message(48);
         out.kind = OutKind::Card;
-        let card = AdaptiveCard {
+        let card = MessageCard {
             kind: MessageCardKind::Oauth,
             title: Some("Sign in with Microsoft".into()),
             oauth: Some(OauthCard {
@@ -515,7 +512,7 @@ mod tests {
             }),
             ..Default::default()
         };
-        out.adaptive_card = Some(card);
+        out.card = Some(card);
         out
     }
 
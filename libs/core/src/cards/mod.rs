@@ -17,7 +17,7 @@ impl Card {
                 text: text.to_string(),
                 markdown: false,
             }],
-            actions: Vec::new(),
+            ..Default::default()
         }
     }
 
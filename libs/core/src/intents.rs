@@ -0,0 +1,214 @@
+//! Outbound intents: non-card actions such as typing indicators, reactions, ephemeral replies
+//! and threaded replies.
+//!
+//! Flows attach an intent to `OutMessage.meta` under [`INTENT_META_KEY`]. Egress negotiates it
+//! against the provider's capabilities with [`apply_intent`]: a supported intent is passed to the
+//! adapter unchanged, an unsupported one is degraded to an ordinary message or skipped, and each
+//! degradation is reported as a [`RenderWarning`].
+//!
+//! Native delivery is up to the provider pack's adapter, which must read `meta.intent`; the
+//! senders in this crate do not. Only declare `typing`, `reactions` or `ephemeral` for adapters
+//! that handle them, otherwise rely on the degraded forms, which every adapter sends.
+use serde::{Deserialize, Serialize};
+
+use crate::provider_capabilities::ProviderCapabilitiesV2;
+use crate::render_plan::RenderWarning;
+use crate::types::OutMessage;
+
+/// `OutMessage.meta` key holding the [`OutboundIntent`] to deliver.
+pub const INTENT_META_KEY: &str = "intent";
+/// `OutMessage.meta` key under which [`apply_intent`] records degradation warnings.
+pub const INTENT_WARNINGS_META_KEY: &str = "intent_warnings";
+/// `OutMessage.meta` key naming the user a degraded ephemeral reply is sent to directly. The
+/// message's `chat_id` is rewritten to the same user.
+pub const DIRECT_MESSAGE_META_KEY: &str = "direct_message_to";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutboundIntent {
+    /// Shows a typing indicator in the conversation.
+    Typing,
+    /// Reacts to an existing message.
+    Reaction { message_id: String, emoji: String },
+    /// A reply only `user_id` can see.
+    EphemeralReply { user_id: String, text: String },
+    /// A reply inside the thread started by `thread_id`.
+    ThreadedReply { thread_id: String, text: String },
+}
+
+impl OutboundIntent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            OutboundIntent::Typing => "typing",
+            OutboundIntent::Reaction { .. } => "reaction",
+            OutboundIntent::EphemeralReply { .. } => "ephemeral_reply",
+            OutboundIntent::ThreadedReply { .. } => "threaded_reply",
+        }
+    }
+}
+
+/// How a negotiated intent is delivered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "delivery", rename_all = "snake_case")]
+pub enum IntentDelivery {
+    /// The provider supports the intent; the adapter sends it as is.
+    Native { intent: OutboundIntent },
+    /// Sent as an ordinary message instead.
+    Message {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        thread_id: Option<String>,
+        /// Set when the message goes to this user directly rather than to the conversation.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        direct_to: Option<String>,
+    },
+    /// Nothing is sent.
+    Skip,
+}
+
+impl IntentDelivery {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IntentDelivery::Native { .. } => "native",
+            IntentDelivery::Message { .. } => "message",
+            IntentDelivery::Skip => "skip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntentPlan {
+    pub delivery: IntentDelivery,
+    pub warnings: Vec<RenderWarning>,
+}
+
+impl IntentPlan {
+    fn native(intent: &OutboundIntent) -> Self {
+        Self {
+            delivery: IntentDelivery::Native {
+                intent: intent.clone(),
+            },
+            warnings: Vec::new(),
+        }
+    }
+
+    fn degraded(delivery: IntentDelivery, code: &str, message: &str) -> Self {
+        Self {
+            delivery,
+            warnings: vec![RenderWarning {
+                code: code.into(),
+                message: Some(message.into()),
+                path: Some(format!("/{INTENT_META_KEY}")),
+            }],
+        }
+    }
+
+    pub fn is_native(&self) -> bool {
+        matches!(self.delivery, IntentDelivery::Native { .. })
+    }
+}
+
+/// Decides how `intent` is delivered by a provider with `caps`:
+///
+/// - typing indicators are skipped when unsupported;
+/// - reactions become a short reply carrying the emoji;
+/// - ephemeral replies become a direct message when the provider has them, otherwise a plain
+///   reply;
+/// - threaded replies become a plain reply.
+pub fn negotiate_intent(intent: &OutboundIntent, caps: &ProviderCapabilitiesV2) -> IntentPlan {
+    match intent {
+        OutboundIntent::Typing if caps.supports_typing => IntentPlan::native(intent),
+        OutboundIntent::Typing => IntentPlan::degraded(
+            IntentDelivery::Skip,
+            "typing_not_supported",
+            "typing indicator skipped",
+        ),
+        OutboundIntent::Reaction { .. } if caps.supports_reactions => IntentPlan::native(intent),
+        OutboundIntent::Reaction { emoji, .. } => IntentPlan::degraded(
+            IntentDelivery::Message {
+                text: emoji.clone(),
+                thread_id: None,
+                direct_to: None,
+            },
+            "reactions_not_supported",
+            "reaction sent as a reply",
+        ),
+        OutboundIntent::EphemeralReply { .. } if caps.supports_ephemeral => {
+            IntentPlan::native(intent)
+        }
+        OutboundIntent::EphemeralReply { user_id, text } if caps.supports_direct_messages => {
+            IntentPlan::degraded(
+                IntentDelivery::Message {
+                    text: text.clone(),
+                    thread_id: None,
+                    direct_to: Some(user_id.clone()),
+                },
+                "ephemeral_as_direct_message",
+                "ephemeral reply sent as a direct message",
+            )
+        }
+        OutboundIntent::EphemeralReply { text, .. } => IntentPlan::degraded(
+            IntentDelivery::Message {
+                text: text.clone(),
+                thread_id: None,
+                direct_to: None,
+            },
+            "ephemeral_as_reply",
+            "ephemeral reply is visible to the whole conversation",
+        ),
+        OutboundIntent::ThreadedReply { .. } if caps.supports_threads => IntentPlan::native(intent),
+        OutboundIntent::ThreadedReply { text, .. } => IntentPlan::degraded(
+            IntentDelivery::Message {
+                text: text.clone(),
+                thread_id: None,
+                direct_to: None,
+            },
+            "threads_not_supported",
+            "threaded reply sent to the conversation",
+        ),
+    }
+}
+
+/// Negotiates the intent in `out.meta`, if any, and rewrites `out` to match: native intents stay
+/// in place (a threaded reply also fills `text` and `thread_id`), degraded ones are replaced by
+/// `text` and `thread_id`. A direct message is addressed by setting `chat_id` to the user, so
+/// adapters that know nothing about intents still deliver it privately, and the user is also
+/// recorded under [`DIRECT_MESSAGE_META_KEY`]. Warnings are stored under
+/// [`INTENT_WARNINGS_META_KEY`].
+///
+/// Returns `None` when the message carries no intent or the intent does not parse. Callers
+/// must not send the message when the plan's delivery is [`IntentDelivery::Skip`].
+pub fn apply_intent(out: &mut OutMessage, caps: &ProviderCapabilitiesV2) -> Option<IntentPlan> {
+    let intent: OutboundIntent = serde_json::from_value(out.meta.get(INTENT_META_KEY)?.clone())
+        .inspect_err(|err| tracing::warn!(error = %err, "ignoring invalid outbound intent"))
+        .ok()?;
+    let plan = negotiate_intent(&intent, caps);
+    if let IntentDelivery::Native {
+        intent: OutboundIntent::ThreadedReply { thread_id, text },
+    } = &plan.delivery
+    {
+        out.text = Some(text.clone());
+        out.thread_id = Some(thread_id.clone());
+    }
+    if let IntentDelivery::Message {
+        text,
+        thread_id,
+        direct_to,
+    } = &plan.delivery
+    {
+        out.meta.remove(INTENT_META_KEY);
+        out.text = Some(text.clone());
+        out.thread_id = thread_id.clone();
+        if let Some(user) = direct_to {
+            out.chat_id = user.clone();
+            out.meta
+                .insert(DIRECT_MESSAGE_META_KEY.into(), user.clone().into());
+        }
+    }
+    if !plan.warnings.is_empty()
+        && let Ok(warnings) = serde_json::to_value(&plan.warnings)
+    {
+        out.meta.insert(INTENT_WARNINGS_META_KEY.into(), warnings);
+    }
+    Some(plan)
+}
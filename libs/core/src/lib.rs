@@ -15,6 +15,7 @@ pub mod egress;
 pub mod http;
 pub mod idempotency;
 pub mod ingress;
+pub mod intents;
 pub mod interfaces;
 #[cfg(feature = "adaptive-cards")]
 pub mod messaging_card;
@@ -29,8 +30,10 @@ pub mod provider_capabilities;
 pub mod provider_install_store;
 pub mod provider_ops;
 pub mod provider_registry;
+#[cfg(feature = "adaptive-cards")]
 pub mod render_mode;
 pub mod render_plan;
+#[cfg(feature = "adaptive-cards")]
 pub mod render_planner;
 pub mod runner_client;
 pub mod secrets_paths;
@@ -53,6 +56,7 @@ pub use greentic_pack::messaging::MessagingAdapterKind;
 pub use http::*;
 pub use idempotency::*;
 pub use ingress::*;
+pub use intents::*;
 pub use interfaces::*;
 #[cfg(feature = "adaptive-cards")]
 pub use messaging_card::types::{
@@ -63,15 +67,23 @@ pub use messaging_card::types::{
 #[cfg(feature = "adaptive-cards")]
 pub use messaging_card::{
     MessageCardEngine,
-    adaptive::{AdaptiveCardPayload, AdaptiveCardVersion, ValidateError, normalizer},
+    adaptive::{
+        AdaptiveCardHost, AdaptiveCardPayload, AdaptiveCardVersion, HostValidation, ValidateError,
+        normalizer,
+    },
     downgrade::{CapabilityProfile, DowngradeContext, DowngradeEngine, PolicyDowngradeEngine},
     ir::{AppLink, Element, InputChoice, MessageCardIr, MessageCardIrBuilder},
+    postback_tokens::{
+        DirPostbackTokenStore, InMemoryPostbackTokenStore, POSTBACK_TOKEN_PREFIX,
+        PostbackTokenStore, resolve_callback_data,
+    },
     renderers::{
-        NullRenderer, PlatformRenderer, RendererRegistry, SlackRenderer, TeamsRenderer,
-        TelegramRenderer, WebChatRenderer, WebexRenderer,
+        NullRenderer, PlatformRenderer, RendererRegistry, SlackRenderer, SlackViewTarget,
+        TaskModuleSize, TeamsRenderer, TelegramRenderer, WebChatRenderer, WebexRenderer,
+        task_module_message,
     },
-    spec::{AuthRenderSpec, FallbackButton, RenderIntent, RenderSpec},
-    telemetry::{CardTelemetry, NullTelemetry, TelemetryEvent, TelemetryHook},
+    spec::{AuthRenderSpec, CardUpdate, FallbackButton, RenderIntent, RenderSpec},
+    telemetry::{CardTelemetry, Correlation, NullTelemetry, TelemetryEvent, TelemetryHook},
     tier::{Tier, TierPolicy},
 };
 pub use messaging_subjects::*;
@@ -90,9 +102,14 @@ pub use provider_ops::{
     AttachmentInput, IngestInput, IngestOutput, MessageEnvelope as ProviderMessageEnvelope,
     ReplyInput, ReplyMetadata, ReplyOutput, SendInput, SendMetadata, SendOutput, SendStatus,
 };
-pub use provider_registry::{CapsSource, ProviderCapsRegistry, ProviderRecord};
+pub use provider_registry::{
+    CapsSource, ProviderCapsOverrides, ProviderCapsRegistry, ProviderRecord,
+    caps_from_adapter_features, load_provider_caps_from_pack_files,
+};
+#[cfg(feature = "adaptive-cards")]
 pub use render_mode::*;
 pub use render_plan::*;
+#[cfg(feature = "adaptive-cards")]
 pub use render_planner::*;
 pub use runner_client::*;
 pub use secrets_paths::*;
@@ -0,0 +1,223 @@
+//! Accessibility lint pass over [`MessageCardIr`].
+//!
+//! Findings reuse [`RenderWarning`] so they can be reported next to render-planning warnings.
+//! Paths are JSON pointers into the serialized IR (e.g. `/elements/2/alt`).
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::messaging_card::ir::{Element, MessageCardIr};
+use crate::render_plan::RenderWarning;
+
+pub const A11Y_MISSING_TITLE: &str = "a11y.missing_title";
+pub const A11Y_IMAGE_MISSING_ALT: &str = "a11y.image_missing_alt";
+pub const A11Y_NON_DESCRIPTIVE_ACTION: &str = "a11y.non_descriptive_action";
+pub const A11Y_COLOR_ONLY_MEANING: &str = "a11y.color_only_meaning";
+pub const A11Y_FACT_LABEL_TOO_LONG: &str = "a11y.fact_label_too_long";
+
+/// Fact labels longer than this are hard to follow when read out by a screen reader.
+pub const MAX_FACT_LABEL_CHARS: usize = 40;
+
+/// Action titles that do not describe what the action does.
+const NON_DESCRIPTIVE_TITLES: &[&str] = &[
+    "click",
+    "click here",
+    "tap here",
+    "here",
+    "link",
+    "this link",
+    "this",
+    "more",
+    "read more",
+    "learn more",
+    "go",
+    "button",
+];
+
+/// Text that points at something only by its colour ("items in red", "green means ready").
+static COLOR_REFERENCE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:in|marked|shown|highlighted|colou?red)\s+(?:red|green|amber|yellow|orange|blue)\b|\b(?:red|green|amber|yellow|orange|blue)\s+(?:items?|rows?|entries|ones?|means?|indicates?)\b",
+    )
+    .unwrap()
+});
+
+/// Values that convey status with a colour swatch alone.
+const COLOR_SWATCHES: &[&str] = &[
+    "🔴", "🟠", "🟡", "🟢", "🔵", "🟣", "⚫", "⚪", "🟥", "🟧", "🟨", "🟩", "🟦",
+];
+
+/// Runs every accessibility check over `ir` and returns findings in document order.
+pub fn lint_ir(ir: &MessageCardIr) -> Vec<RenderWarning> {
+    let mut findings = Vec::new();
+
+    if ir.head.title.as_deref().is_none_or(|t| t.trim().is_empty()) {
+        findings.push(finding(
+            A11Y_MISSING_TITLE,
+            "card has no title for assistive technology to announce",
+            "/head/title",
+        ));
+    }
+    // The primary text usually also appears as the first text element; report it once.
+    if let Some(text) = &ir.head.text
+        && !ir
+            .elements
+            .iter()
+            .any(|element| matches!(element, Element::Text { text: t, .. } if t == text))
+    {
+        check_color_reference(text, "/head/text", &mut findings);
+    }
+
+    for (index, element) in ir.elements.iter().enumerate() {
+        match element {
+            Element::Text { text, .. } => {
+                check_color_reference(text, &format!("/elements/{index}/text"), &mut findings);
+            }
+            Element::Image { alt, .. } => {
+                if alt.as_deref().is_none_or(|alt| alt.trim().is_empty()) {
+                    findings.push(finding(
+                        A11Y_IMAGE_MISSING_ALT,
+                        "image has no alt text",
+                        &format!("/elements/{index}/alt"),
+                    ));
+                }
+            }
+            Element::FactSet { facts } => {
+                for (fact_index, fact) in facts.iter().enumerate() {
+                    let path = format!("/elements/{index}/facts/{fact_index}");
+                    let label_chars = fact.label.chars().count();
+                    if label_chars > MAX_FACT_LABEL_CHARS {
+                        findings.push(finding(
+                            A11Y_FACT_LABEL_TOO_LONG,
+                            &format!(
+                                "fact label is {label_chars} characters; keep it under {MAX_FACT_LABEL_CHARS}"
+                            ),
+                            &format!("{path}/label"),
+                        ));
+                    }
+                    if is_color_swatch(&fact.value) {
+                        findings.push(finding(
+                            A11Y_COLOR_ONLY_MEANING,
+                            "fact value conveys status by colour alone",
+                            &format!("{path}/value"),
+                        ));
+                    }
+                }
+            }
+            Element::Input { .. } => {}
+        }
+    }
+
+    for (index, action) in ir.actions.iter().enumerate() {
+        let title = action.title();
+        if is_non_descriptive(title) {
+            findings.push(finding(
+                A11Y_NON_DESCRIPTIVE_ACTION,
+                &format!("action title {title:?} does not describe the action"),
+                &format!("/actions/{index}/title"),
+            ));
+        }
+    }
+
+    findings
+}
+
+fn check_color_reference(text: &str, path: &str, findings: &mut Vec<RenderWarning>) {
+    if let Some(found) = COLOR_REFERENCE.find(text) {
+        findings.push(finding(
+            A11Y_COLOR_ONLY_MEANING,
+            &format!(
+                "text refers to content by colour ({:?}); add a label or icon",
+                found.as_str()
+            ),
+            path,
+        ));
+    }
+}
+
+fn is_non_descriptive(title: &str) -> bool {
+    let normalized = title
+        .trim()
+        .trim_end_matches(['.', '!', '…', '>', ' '])
+        .to_lowercase();
+    normalized.is_empty() || NON_DESCRIPTIVE_TITLES.contains(&normalized.as_str())
+}
+
+fn is_color_swatch(value: &str) -> bool {
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return false;
+    }
+    while !rest.is_empty() {
+        let Some(swatch) = COLOR_SWATCHES.iter().find(|s| rest.starts_with(**s)) else {
+            return false;
+        };
+        rest = rest[swatch.len()..].trim_start();
+    }
+    true
+}
+
+fn finding(code: &str, message: &str, path: &str) -> RenderWarning {
+    RenderWarning {
+        code: code.into(),
+        message: Some(message.into()),
+        path: Some(path.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging_card::ir::{Fact, MessageCardIrBuilder};
+    use serde_json::json;
+
+    fn codes(findings: &[RenderWarning]) -> Vec<(&str, &str)> {
+        findings
+            .iter()
+            .map(|f| (f.code.as_str(), f.path.as_deref().unwrap_or_default()))
+            .collect()
+    }
+
+    #[test]
+    fn accessible_card_has_no_findings() {
+        let ir = MessageCardIrBuilder::default()
+            .title("Deployment finished")
+            .primary_text("All services are healthy.", false)
+            .image(
+                "https://example.com/graph.png".into(),
+                Some("Latency graph".into()),
+            )
+            .fact("Status", "🟢 Healthy")
+            .open_url("Open dashboard", "https://example.com")
+            .build();
+        assert!(lint_ir(&ir).is_empty());
+    }
+
+    #[test]
+    fn flags_each_issue_with_pointer_paths() {
+        let mut ir = MessageCardIrBuilder::default()
+            .primary_text("Failed jobs are shown in red.", false)
+            .image("https://example.com/graph.png".into(), None)
+            .fact("Status", "🔴")
+            .open_url("Click here", "https://example.com")
+            .postback("Approve request", json!({}))
+            .build();
+        if let Element::FactSet { facts } = &mut ir.elements[2] {
+            facts.push(Fact {
+                label: "Number of requests processed during the last window".into(),
+                value: "12".into(),
+            });
+        }
+
+        assert_eq!(
+            codes(&lint_ir(&ir)),
+            vec![
+                (A11Y_MISSING_TITLE, "/head/title"),
+                (A11Y_COLOR_ONLY_MEANING, "/elements/0/text"),
+                (A11Y_IMAGE_MISSING_ALT, "/elements/1/alt"),
+                (A11Y_COLOR_ONLY_MEANING, "/elements/2/facts/0/value"),
+                (A11Y_FACT_LABEL_TOO_LONG, "/elements/2/facts/1/label"),
+                (A11Y_NON_DESCRIPTIVE_ACTION, "/actions/0/title"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,560 @@
+//! Per-host Adaptive Card feature matrices and version downgrading.
+//!
+//! Hosts render different Adaptive Card versions and element subsets. Cards are checked against
+//! the host they are sent to: features the host cannot render are replaced with an element-level
+//! `fallback` or a built-in equivalent, `version` is lowered to what the host supports, and the
+//! result is validated against that version's bundled schema.
+use serde_json::{Map, Value, json};
+
+use super::AdaptiveCardVersion;
+use super::validator::{ValidateError, validate_ac_json_for_version};
+
+/// Adaptive Card host a payload is rendered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AdaptiveCardHost {
+    TeamsDesktop,
+    TeamsMobile,
+    Webex,
+    WebChat,
+}
+
+/// What a host can render.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostFeatures {
+    /// Newest Adaptive Card version the host renders.
+    pub max_version: AdaptiveCardVersion,
+    /// Element and action types the host does not render even within `max_version`.
+    pub unsupported: &'static [&'static str],
+}
+
+impl AdaptiveCardHost {
+    pub const ALL: [AdaptiveCardHost; 4] = [
+        AdaptiveCardHost::TeamsDesktop,
+        AdaptiveCardHost::TeamsMobile,
+        AdaptiveCardHost::Webex,
+        AdaptiveCardHost::WebChat,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AdaptiveCardHost::TeamsDesktop => "teams",
+            AdaptiveCardHost::TeamsMobile => "teams_mobile",
+            AdaptiveCardHost::Webex => "webex",
+            AdaptiveCardHost::WebChat => "webchat",
+        }
+    }
+
+    /// Host used when rendering for a platform renderer name.
+    pub fn for_platform(platform: &str) -> Option<Self> {
+        match platform {
+            "teams" => Some(AdaptiveCardHost::TeamsDesktop),
+            "teams_mobile" => Some(AdaptiveCardHost::TeamsMobile),
+            "webex" => Some(AdaptiveCardHost::Webex),
+            "webchat" => Some(AdaptiveCardHost::WebChat),
+            _ => None,
+        }
+    }
+
+    pub fn features(self) -> HostFeatures {
+        match self {
+            AdaptiveCardHost::TeamsDesktop => HostFeatures {
+                max_version: AdaptiveCardVersion::V1_5,
+                unsupported: &[],
+            },
+            AdaptiveCardHost::TeamsMobile => HostFeatures {
+                max_version: AdaptiveCardVersion::V1_4,
+                unsupported: &["Media"],
+            },
+            AdaptiveCardHost::Webex => HostFeatures {
+                max_version: AdaptiveCardVersion::V1_3,
+                unsupported: &["Media"],
+            },
+            AdaptiveCardHost::WebChat => HostFeatures {
+                max_version: AdaptiveCardVersion::V1_6,
+                unsupported: &["Carousel"],
+            },
+        }
+    }
+}
+
+/// Card content ready for a host.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostValidation {
+    pub content: Value,
+    /// Version the content declares and was validated against.
+    pub version: AdaptiveCardVersion,
+    /// Human-readable notes for every change made to fit the host.
+    pub changes: Vec<String>,
+}
+
+impl HostValidation {
+    pub fn downgraded(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    /// Whether elements or actions were changed, beyond lowering the declared version.
+    pub fn features_changed(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| !change.starts_with("version "))
+    }
+}
+
+/// Version an element or action type was introduced in.
+fn introduced_in(kind: &str) -> (u32, u32) {
+    match kind {
+        "Media" => (1, 1),
+        "ActionSet" | "RichTextBlock" | "Action.ToggleVisibility" => (1, 2),
+        "Action.Execute" => (1, 4),
+        "Table" => (1, 5),
+        "Carousel" => (1, 6),
+        _ => (1, 0),
+    }
+}
+
+/// Validates `content` for `host`, downgrading it where possible.
+pub fn validate_for_host(
+    content: &Value,
+    host: AdaptiveCardHost,
+) -> Result<HostValidation, ValidateError> {
+    if !content.is_object() {
+        return Err(ValidateError::NotObject);
+    }
+    let features = host.features();
+    let max = features
+        .max_version
+        .number()
+        .expect("bundled versions are numeric");
+
+    let declared_raw = content
+        .get("version")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let declared = AdaptiveCardVersion::parse(declared_raw);
+    let declared_number = declared
+        .number()
+        .ok_or_else(|| ValidateError::UnsupportedVersion(declared_raw.to_string()))?;
+    let target = declared_number.min(max);
+
+    let mut downgrader = Downgrader {
+        host,
+        target,
+        unsupported: features.unsupported,
+        changes: Vec::new(),
+    };
+    let mut content = content.clone();
+    downgrader.card(&mut content, "")?;
+    let mut changes = downgrader.changes;
+
+    // Schemas start at 1.2; older cards are valid 1.2 cards.
+    let schema_version = AdaptiveCardVersion::parse(&format!("{}.{}", target.0, target.1.max(2)));
+    if declared_number > max {
+        content["version"] = json!(features.max_version.as_str());
+        changes.push(format!(
+            "version {declared_raw} lowered to {}",
+            features.max_version.as_str()
+        ));
+    }
+    validate_ac_json_for_version(&content, &schema_version)?;
+
+    let version = AdaptiveCardVersion::parse(
+        content
+            .get("version")
+            .and_then(Value::as_str)
+            .unwrap_or(declared_raw),
+    );
+    Ok(HostValidation {
+        content,
+        version,
+        changes,
+    })
+}
+
+struct Downgrader {
+    host: AdaptiveCardHost,
+    target: (u32, u32),
+    unsupported: &'static [&'static str],
+    changes: Vec<String>,
+}
+
+impl Downgrader {
+    fn supports(&self, kind: &str) -> bool {
+        introduced_in(kind) <= self.target && !self.unsupported.contains(&kind)
+    }
+
+    fn unsupported_error(&self, kind: &str, path: &str) -> ValidateError {
+        ValidateError::UnsupportedFeature {
+            host: self.host.as_str(),
+            feature: kind.to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    fn card(&mut self, card: &mut Value, path: &str) -> Result<(), ValidateError> {
+        let Some(obj) = card.as_object_mut() else {
+            return Ok(());
+        };
+        let mut hoisted = Vec::new();
+        if let Some(Value::Array(body)) = obj.get_mut("body") {
+            self.elements(body, &format!("{path}/body"), &mut hoisted)?;
+        }
+        if !hoisted.is_empty() {
+            let actions = obj
+                .entry("actions")
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(actions) = actions {
+                actions.extend(hoisted);
+            }
+        }
+        if let Some(Value::Array(actions)) = obj.get_mut("actions") {
+            self.actions(actions, &format!("{path}/actions"))?;
+        }
+        Ok(())
+    }
+
+    /// Rewrites `items` in place. Actions from removed `ActionSet`s are pushed to `hoisted`.
+    fn elements(
+        &mut self,
+        items: &mut Vec<Value>,
+        path: &str,
+        hoisted: &mut Vec<Value>,
+    ) -> Result<(), ValidateError> {
+        let mut index = 0;
+        while index < items.len() {
+            let item_path = format!("{path}/{index}");
+            let kind = element_type(&items[index]);
+            if !self.supports(&kind) {
+                match self.replace_element(&items[index], &kind, &item_path, hoisted)? {
+                    Some(replacement) => items[index] = replacement,
+                    None => {
+                        items.remove(index);
+                        continue;
+                    }
+                }
+                // Re-check the replacement, which may itself need downgrading.
+                continue;
+            }
+            if kind.starts_with("Input.")
+                && self.target < (1, 3)
+                && let Some(label) = items[index]
+                    .as_object_mut()
+                    .and_then(|input| input.remove("label"))
+            {
+                self.changes
+                    .push(format!("{item_path}: input label moved to a TextBlock"));
+                items.insert(
+                    index,
+                    json!({ "type": "TextBlock", "text": label, "wrap": true }),
+                );
+                index += 1;
+            }
+            self.children(
+                &mut items[index],
+                &kind,
+                &format!("{path}/{index}"),
+                hoisted,
+            )?;
+            index += 1;
+        }
+        Ok(())
+    }
+
+    fn children(
+        &mut self,
+        element: &mut Value,
+        kind: &str,
+        path: &str,
+        hoisted: &mut Vec<Value>,
+    ) -> Result<(), ValidateError> {
+        let Some(obj) = element.as_object_mut() else {
+            return Ok(());
+        };
+        match kind {
+            "Container" => {
+                if let Some(Value::Array(items)) = obj.get_mut("items") {
+                    self.elements(items, &format!("{path}/items"), hoisted)?;
+                }
+            }
+            "ColumnSet" => {
+                if let Some(Value::Array(columns)) = obj.get_mut("columns") {
+                    for (index, column) in columns.iter_mut().enumerate() {
+                        if let Some(Value::Array(items)) = column.get_mut("items") {
+                            self.elements(
+                                items,
+                                &format!("{path}/columns/{index}/items"),
+                                hoisted,
+                            )?;
+                        }
+                    }
+                }
+            }
+            "ActionSet" => {
+                if let Some(Value::Array(actions)) = obj.get_mut("actions") {
+                    self.actions(actions, &format!("{path}/actions"))?;
+                }
+            }
+            _ => {}
+        }
+        if let Some(Value::Array(actions)) = obj
+            .get_mut("selectAction")
+            .map(|action| Value::Array(vec![action.take()]))
+            .as_mut()
+        {
+            self.actions(actions, &format!("{path}/selectAction"))?;
+            match actions.pop() {
+                Some(action) => {
+                    obj.insert("selectAction".into(), action);
+                }
+                None => {
+                    obj.remove("selectAction");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn replace_element(
+        &mut self,
+        element: &Value,
+        kind: &str,
+        path: &str,
+        hoisted: &mut Vec<Value>,
+    ) -> Result<Option<Value>, ValidateError> {
+        if let Some(fallback) = element.get("fallback") {
+            if fallback.as_str() == Some("drop") {
+                self.changes.push(format!("{path}: {kind} dropped"));
+                return Ok(None);
+            }
+            if fallback.is_object() {
+                self.changes
+                    .push(format!("{path}: {kind} replaced by its fallback"));
+                return Ok(Some(fallback.clone()));
+            }
+        }
+        let replacement = match kind {
+            "ActionSet" => {
+                if let Some(actions) = element.get("actions").and_then(Value::as_array) {
+                    hoisted.extend(actions.iter().cloned());
+                }
+                self.changes
+                    .push(format!("{path}: ActionSet actions moved to the card"));
+                return Ok(None);
+            }
+            "RichTextBlock" => {
+                let text: String = element
+                    .get("inlines")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|inline| {
+                        inline
+                            .as_str()
+                            .or_else(|| inline.get("text").and_then(Value::as_str))
+                    })
+                    .collect();
+                json!({ "type": "TextBlock", "text": text, "wrap": true })
+            }
+            "Table" => {
+                let rows: Vec<Value> = element
+                    .get("rows")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .map(|row| {
+                        let columns: Vec<Value> = row
+                            .get("cells")
+                            .and_then(Value::as_array)
+                            .into_iter()
+                            .flatten()
+                            .map(|cell| {
+                                json!({
+                                    "type": "Column",
+                                    "items": cell.get("items").cloned().unwrap_or(json!([])),
+                                })
+                            })
+                            .collect();
+                        json!({ "type": "ColumnSet", "columns": columns })
+                    })
+                    .collect();
+                json!({ "type": "Container", "items": rows })
+            }
+            "Carousel" => {
+                let items: Vec<Value> = element
+                    .get("pages")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|page| {
+                        page.get("items")
+                            .and_then(Value::as_array)
+                            .cloned()
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                json!({ "type": "Container", "items": items })
+            }
+            "Media" => {
+                let url = element
+                    .get("sources")
+                    .and_then(Value::as_array)
+                    .and_then(|sources| sources.first())
+                    .and_then(|source| source.get("url"))
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| self.unsupported_error(kind, path))?;
+                let title = element
+                    .get("altText")
+                    .and_then(Value::as_str)
+                    .unwrap_or("Play media");
+                json!({ "type": "TextBlock", "text": format!("[{title}]({url})"), "wrap": true })
+            }
+            _ => return Err(self.unsupported_error(kind, path)),
+        };
+        self.changes.push(format!(
+            "{path}: {kind} replaced by {}",
+            element_type(&replacement)
+        ));
+        Ok(Some(replacement))
+    }
+
+    fn actions(&mut self, actions: &mut Vec<Value>, path: &str) -> Result<(), ValidateError> {
+        let mut index = 0;
+        while index < actions.len() {
+            let action_path = format!("{path}/{index}");
+            let kind = element_type(&actions[index]);
+            if !self.supports(&kind) {
+                match self.replace_action(&actions[index], &kind, &action_path)? {
+                    Some(replacement) => actions[index] = replacement,
+                    None => {
+                        actions.remove(index);
+                        continue;
+                    }
+                }
+                continue;
+            }
+            if kind == "Action.ShowCard"
+                && let Some(card) = actions[index].get_mut("card")
+            {
+                self.card(card, &format!("{action_path}/card"))?;
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+
+    fn replace_action(
+        &mut self,
+        action: &Value,
+        kind: &str,
+        path: &str,
+    ) -> Result<Option<Value>, ValidateError> {
+        if let Some(fallback) = action.get("fallback") {
+            if fallback.as_str() == Some("drop") {
+                self.changes.push(format!("{path}: {kind} dropped"));
+                return Ok(None);
+            }
+            if fallback.is_object() {
+                self.changes
+                    .push(format!("{path}: {kind} replaced by its fallback"));
+                return Ok(Some(fallback.clone()));
+            }
+        }
+        match kind {
+            "Action.Execute" => {
+                let mut submit = action.as_object().cloned().unwrap_or_default();
+                submit.insert("type".into(), json!("Action.Submit"));
+                let verb = submit.remove("verb");
+                let data = match (submit.remove("data"), verb) {
+                    (Some(Value::Object(mut data)), Some(verb)) => {
+                        data.entry("verb").or_insert(verb);
+                        Value::Object(data)
+                    }
+                    (Some(data), Some(verb)) => json!({ "data": data, "verb": verb }),
+                    (None, Some(verb)) => json!({ "verb": verb }),
+                    (Some(data), None) => data,
+                    (None, None) => Value::Object(Map::new()),
+                };
+                submit.insert("data".into(), data);
+                self.changes
+                    .push(format!("{path}: Action.Execute replaced by Action.Submit"));
+                Ok(Some(Value::Object(submit)))
+            }
+            "Action.ToggleVisibility" => {
+                self.changes.push(format!("{path}: {kind} dropped"));
+                Ok(None)
+            }
+            _ => Err(self.unsupported_error(kind, path)),
+        }
+    }
+}
+
+fn element_type(value: &Value) -> String {
+    value
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(version: &str, body: Value, actions: Value) -> Value {
+        json!({ "type": "AdaptiveCard", "version": version, "body": body, "actions": actions })
+    }
+
+    #[test]
+    fn supported_card_passes_unchanged() {
+        let content = card(
+            "1.3",
+            json!([{ "type": "TextBlock", "text": "Hi" }]),
+            json!([{ "type": "Action.Submit", "title": "Ok", "data": {} }]),
+        );
+        let validated = validate_for_host(&content, AdaptiveCardHost::Webex).expect("valid");
+        assert!(!validated.downgraded());
+        assert_eq!(validated.content, content);
+        assert_eq!(validated.version, AdaptiveCardVersion::V1_3);
+    }
+
+    #[test]
+    fn downgrades_newer_card_for_webex() {
+        let content = card(
+            "1.6",
+            json!([
+                { "type": "Table", "rows": [
+                    { "type": "TableRow", "cells": [
+                        { "type": "TableCell", "items": [{ "type": "TextBlock", "text": "a" }] }
+                    ]}
+                ]},
+                { "type": "Input.Text", "id": "name", "label": "Name" },
+                { "type": "Carousel", "pages": [], "fallback": "drop" }
+            ]),
+            json!([{ "type": "Action.Execute", "title": "Run", "verb": "run", "data": { "x": 1 } }]),
+        );
+        let validated = validate_for_host(&content, AdaptiveCardHost::Webex).expect("valid");
+        assert!(validated.downgraded());
+        assert_eq!(validated.version, AdaptiveCardVersion::V1_3);
+        assert_eq!(validated.content["version"], "1.3");
+        assert_eq!(validated.content["body"][0]["type"], "Container");
+        assert_eq!(validated.content["body"][1]["type"], "Input.Text");
+        assert_eq!(validated.content["body"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            validated.content["actions"][0],
+            json!({ "type": "Action.Submit", "title": "Run", "data": { "x": 1, "verb": "run" } })
+        );
+    }
+
+    #[test]
+    fn unsupported_feature_without_fallback_fails() {
+        let content = card(
+            "1.3",
+            json!([{ "type": "Media", "sources": [] }]),
+            json!([]),
+        );
+        let err = validate_for_host(&content, AdaptiveCardHost::Webex).unwrap_err();
+        assert!(matches!(
+            err,
+            ValidateError::UnsupportedFeature { host: "webex", ref path, .. } if path == "/body/0"
+        ));
+    }
+}
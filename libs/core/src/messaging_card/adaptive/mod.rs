@@ -1,20 +1,66 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+pub mod hosts;
 pub mod normalizer;
 pub mod validator;
 
-pub use validator::{ValidateError, validate_ac_json};
+pub use hosts::{AdaptiveCardHost, HostFeatures, HostValidation, validate_for_host};
+pub use validator::{ValidateError, validate_ac_json, validate_ac_json_for_version};
 
-/// Supported Adaptive Card schema versions for the bootstrap phase.
+/// Adaptive Card schema versions with a bundled schema.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum AdaptiveCardVersion {
+    V1_2,
+    V1_3,
+    V1_4,
+    V1_5,
     #[default]
     V1_6,
     Custom(String),
 }
 
+impl AdaptiveCardVersion {
+    /// Bundled versions, oldest first.
+    pub const BUNDLED: [AdaptiveCardVersion; 5] = [
+        AdaptiveCardVersion::V1_2,
+        AdaptiveCardVersion::V1_3,
+        AdaptiveCardVersion::V1_4,
+        AdaptiveCardVersion::V1_5,
+        AdaptiveCardVersion::V1_6,
+    ];
+
+    /// Parses a card `version` string such as `"1.4"`; unknown versions become `Custom`.
+    pub fn parse(version: &str) -> Self {
+        match version.trim() {
+            "1.2" => AdaptiveCardVersion::V1_2,
+            "1.3" => AdaptiveCardVersion::V1_3,
+            "1.4" => AdaptiveCardVersion::V1_4,
+            "1.5" => AdaptiveCardVersion::V1_5,
+            "1.6" => AdaptiveCardVersion::V1_6,
+            other => AdaptiveCardVersion::Custom(other.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            AdaptiveCardVersion::V1_2 => "1.2",
+            AdaptiveCardVersion::V1_3 => "1.3",
+            AdaptiveCardVersion::V1_4 => "1.4",
+            AdaptiveCardVersion::V1_5 => "1.5",
+            AdaptiveCardVersion::V1_6 => "1.6",
+            AdaptiveCardVersion::Custom(version) => version,
+        }
+    }
+
+    /// `(major, minor)` for ordering, or `None` when the version string is malformed.
+    pub fn number(&self) -> Option<(u32, u32)> {
+        let (major, minor) = self.as_str().split_once('.')?;
+        Some((major.parse().ok()?, minor.parse().ok()?))
+    }
+}
+
 /// Lightweight wrapper that keeps the original Adaptive Card JSON around the pipeline.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct AdaptiveCardPayload {
@@ -26,9 +72,20 @@ pub struct AdaptiveCardPayload {
 
 impl AdaptiveCardPayload {
     pub fn new(content: Value) -> Self {
-        Self {
-            content,
-            ..Default::default()
-        }
+        let version = content
+            .get("version")
+            .and_then(Value::as_str)
+            .map(AdaptiveCardVersion::parse)
+            .unwrap_or_default();
+        Self { version, content }
+    }
+
+    /// Validates the card for `host`, downgrading it to the host's Adaptive Card version and
+    /// feature set where possible.
+    pub fn validate_for_host(
+        &self,
+        host: AdaptiveCardHost,
+    ) -> Result<HostValidation, ValidateError> {
+        hosts::validate_for_host(&self.content, host)
     }
 }
@@ -192,6 +192,9 @@ fn normalize_action(value: &Value, meta: &mut Meta) -> Result<Option<IrAction>>
                 .unwrap_or("Submit")
                 .to_string();
             let data = obj.get("data").cloned().unwrap_or(Value::Null);
+            if let Some(data) = strip_task_fetch(&data) {
+                return Ok(Some(IrAction::Dialog { title, data }));
+            }
             Ok(Some(IrAction::Postback { title, data }))
         }
         "Action.ShowCard" => {
@@ -202,6 +205,23 @@ fn normalize_action(value: &Value, meta: &mut Meta) -> Result<Option<IrAction>>
     }
 }
 
+/// Teams opens a task module when `Action.Submit` data carries `msteams.type = task/fetch`.
+/// Returns the remaining data when the marker is present, unwrapping scalars the Teams renderer
+/// nested under `data`.
+fn strip_task_fetch(data: &Value) -> Option<Value> {
+    let obj = data.as_object()?;
+    let marker = obj.get("msteams")?;
+    if marker.get("type").and_then(|v| v.as_str()) != Some("task/fetch") {
+        return None;
+    }
+    let mut rest = obj.clone();
+    rest.remove("msteams");
+    if marker.get("wrapped").and_then(|v| v.as_bool()) == Some(true) {
+        return Some(rest.remove("data").unwrap_or(Value::Null));
+    }
+    Some(Value::Object(rest))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,6 +241,50 @@ mod tests {
         assert_eq!(ir.elements.len(), 1);
     }
 
+    #[test]
+    fn task_fetch_submit_becomes_dialog() {
+        let card = json!({
+            "type": "AdaptiveCard",
+            "version": "1.6",
+            "body": [],
+            "actions": [
+                {
+                    "type": "Action.Submit",
+                    "title": "Open form",
+                    "data": { "msteams": { "type": "task/fetch" }, "form": "expense" }
+                }
+            ]
+        });
+
+        let ir = ac_to_ir(&card).expect("normalize");
+        assert_eq!(
+            ir.actions,
+            vec![IrAction::Dialog {
+                title: "Open form".into(),
+                data: json!({"form": "expense"}),
+            }]
+        );
+    }
+
+    #[test]
+    fn scalar_dialog_data_round_trips() {
+        let ir = MessageCardIr {
+            actions: vec![IrAction::Dialog {
+                title: "Open".into(),
+                data: json!("expense"),
+            }],
+            ..Default::default()
+        };
+        let rendered = crate::messaging_card::renderers::adaptive_from_ir(
+            &ir,
+            &mut Default::default(),
+            &mut Vec::new(),
+        );
+
+        let parsed = ac_to_ir(&rendered).expect("normalize");
+        assert_eq!(parsed.actions, ir.actions);
+    }
+
     #[test]
     fn column_set_body_elements_are_normalized() {
         let card = json!({
@@ -3,13 +3,30 @@ use once_cell::sync::Lazy;
 use serde_json::Value;
 use thiserror::Error;
 
+use super::AdaptiveCardVersion;
+
 pub static AC_16_SCHEMA: &str = include_str!("schema/ac-1.6.schema.json");
 
-static COMPILED_SCHEMA: Lazy<Validator> = Lazy::new(|| {
+// Per-version schemas used for host validation. Each admits only the elements and actions
+// introduced up to its version; `AC_16_SCHEMA` above keeps the original bootstrap subset.
+pub static AC_12_SCHEMA: &str = include_str!("schema/versions/ac-1.2.schema.json");
+pub static AC_13_SCHEMA: &str = include_str!("schema/versions/ac-1.3.schema.json");
+pub static AC_14_SCHEMA: &str = include_str!("schema/versions/ac-1.4.schema.json");
+pub static AC_15_SCHEMA: &str = include_str!("schema/versions/ac-1.5.schema.json");
+pub static AC_16_FULL_SCHEMA: &str = include_str!("schema/versions/ac-1.6.schema.json");
+
+fn compile(schema: &str) -> Validator {
     let schema: Value =
-        serde_json::from_str(AC_16_SCHEMA).expect("adaptive card schema must be valid JSON");
+        serde_json::from_str(schema).expect("adaptive card schema must be valid JSON");
     validator_for(&schema).expect("adaptive card schema must compile")
-});
+}
+
+static COMPILED_SCHEMA: Lazy<Validator> = Lazy::new(|| compile(AC_16_SCHEMA));
+static COMPILED_12: Lazy<Validator> = Lazy::new(|| compile(AC_12_SCHEMA));
+static COMPILED_13: Lazy<Validator> = Lazy::new(|| compile(AC_13_SCHEMA));
+static COMPILED_14: Lazy<Validator> = Lazy::new(|| compile(AC_14_SCHEMA));
+static COMPILED_15: Lazy<Validator> = Lazy::new(|| compile(AC_15_SCHEMA));
+static COMPILED_16: Lazy<Validator> = Lazy::new(|| compile(AC_16_FULL_SCHEMA));
 
 #[derive(Debug, Error)]
 pub enum ValidateError {
@@ -17,14 +34,50 @@ pub enum ValidateError {
     NotObject,
     #[error("adaptive card validation failed: {0}")]
     Schema(String),
+    #[error("adaptive card version {0} has no bundled schema")]
+    UnsupportedVersion(String),
+    #[error("{feature} at {path} is not supported by {host} and has no fallback")]
+    UnsupportedFeature {
+        host: &'static str,
+        feature: String,
+        path: String,
+    },
 }
 
+/// Validates against the Adaptive Card 1.6 schema.
 pub fn validate_ac_json(value: &Value) -> Result<(), ValidateError> {
     if !value.is_object() {
         return Err(ValidateError::NotObject);
     }
+    collect_errors(&COMPILED_SCHEMA, value)
+}
+
+/// Validates against the bundled schema for `version`, which only admits elements and actions
+/// available in that version.
+pub fn validate_ac_json_for_version(
+    value: &Value,
+    version: &AdaptiveCardVersion,
+) -> Result<(), ValidateError> {
+    if !value.is_object() {
+        return Err(ValidateError::NotObject);
+    }
+
+    let validator: &Validator = match version {
+        AdaptiveCardVersion::V1_2 => &COMPILED_12,
+        AdaptiveCardVersion::V1_3 => &COMPILED_13,
+        AdaptiveCardVersion::V1_4 => &COMPILED_14,
+        AdaptiveCardVersion::V1_5 => &COMPILED_15,
+        AdaptiveCardVersion::V1_6 => &COMPILED_16,
+        AdaptiveCardVersion::Custom(other) => {
+            return Err(ValidateError::UnsupportedVersion(other.clone()));
+        }
+    };
+
+    collect_errors(validator, value)
+}
 
-    let mut iter = COMPILED_SCHEMA.iter_errors(value);
+fn collect_errors(validator: &Validator, value: &Value) -> Result<(), ValidateError> {
+    let mut iter = validator.iter_errors(value);
     if let Some(first) = iter.next() {
         let mut messages: Vec<String> = Vec::new();
         messages.push(first.to_string());
@@ -58,4 +111,19 @@ mod tests {
         });
         validate_ac_json(&payload).expect("valid card");
     }
+
+    #[test]
+    fn older_schemas_reject_newer_features() {
+        let payload = json!({
+            "type": "AdaptiveCard",
+            "version": "1.3",
+            "body": [],
+            "actions": [{ "type": "Action.Execute", "title": "Run", "verb": "run" }]
+        });
+        assert!(validate_ac_json_for_version(&payload, &AdaptiveCardVersion::V1_3).is_err());
+
+        let mut payload = payload;
+        payload["version"] = json!("1.4");
+        validate_ac_json_for_version(&payload, &AdaptiveCardVersion::V1_4).expect("valid card");
+    }
 }
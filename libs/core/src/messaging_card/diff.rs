@@ -0,0 +1,325 @@
+//! Structural diff between two `MessageCardIr` values.
+//!
+//! Elements and actions are aligned on their longest common subsequence, so inserting a block
+//! reports one addition rather than a change to every block after it. Unaligned pairs are
+//! compared field by field. `Meta.warnings` is ignored because it only records how a card was
+//! rendered.
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::messaging_card::ir::MessageCardIr;
+
+/// Differences between two cards, in card order (head, elements, actions, meta).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct IrDiff {
+    pub changes: Vec<IrChange>,
+}
+
+/// A single difference. Paths look like `head.title`, `elements[2].text` or `actions[0]`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum IrChange {
+    Added {
+        path: String,
+        value: Value,
+    },
+    Removed {
+        path: String,
+        value: Value,
+    },
+    Changed {
+        path: String,
+        before: Value,
+        after: Value,
+    },
+}
+
+impl IrChange {
+    pub fn path(&self) -> &str {
+        match self {
+            IrChange::Added { path, .. }
+            | IrChange::Removed { path, .. }
+            | IrChange::Changed { path, .. } => path,
+        }
+    }
+}
+
+impl IrDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// One line per change: `+ path: value`, `- path: value` or `~ path: before -> after`.
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return "No card changes.\n".into();
+        }
+        let mut out = String::new();
+        for change in &self.changes {
+            let _ = match change {
+                IrChange::Added { path, value } => writeln!(out, "+ {path}: {value}"),
+                IrChange::Removed { path, value } => writeln!(out, "- {path}: {value}"),
+                IrChange::Changed {
+                    path,
+                    before,
+                    after,
+                } => writeln!(out, "~ {path}: {before} -> {after}"),
+            };
+        }
+        out
+    }
+}
+
+impl MessageCardIr {
+    /// Reports what changed going from `self` to `other`.
+    pub fn diff(&self, other: &MessageCardIr) -> IrDiff {
+        let mut changes = Vec::new();
+        diff_values(
+            "tier",
+            &to_value(&self.tier),
+            &to_value(&other.tier),
+            &mut changes,
+        );
+        diff_values(
+            "head",
+            &to_value(&self.head),
+            &to_value(&other.head),
+            &mut changes,
+        );
+        diff_lists(
+            "elements",
+            &to_values(&self.elements),
+            &to_values(&other.elements),
+            &mut changes,
+        );
+        diff_lists(
+            "actions",
+            &to_values(&self.actions),
+            &to_values(&other.actions),
+            &mut changes,
+        );
+        diff_values("meta", &meta_value(self), &meta_value(other), &mut changes);
+        IrDiff { changes }
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+fn to_values<T: Serialize>(items: &[T]) -> Vec<Value> {
+    items.iter().map(to_value).collect()
+}
+
+fn meta_value(ir: &MessageCardIr) -> Value {
+    let mut meta = to_value(&ir.meta);
+    if let Some(map) = meta.as_object_mut() {
+        map.remove("warnings");
+    }
+    meta
+}
+
+/// Diffs two lists aligned on their longest common subsequence. Within each gap between
+/// aligned items, items are paired up by position and compared; the remainder is reported as
+/// added or removed.
+fn diff_lists(name: &str, before: &[Value], after: &[Value], out: &mut Vec<IrChange>) {
+    let mut anchors = lcs_pairs(before, after);
+    anchors.push((before.len(), after.len()));
+    let (mut i, mut j) = (0, 0);
+    for (anchor_i, anchor_j) in anchors {
+        let paired = (anchor_i - i).min(anchor_j - j);
+        for offset in 0..paired {
+            let (b, a) = (&before[i + offset], &after[j + offset]);
+            let path = format!("{name}[{}]", j + offset);
+            if b.get("type") == a.get("type") {
+                diff_values(&path, b, a, out);
+            } else {
+                out.push(IrChange::Changed {
+                    path,
+                    before: b.clone(),
+                    after: a.clone(),
+                });
+            }
+        }
+        for (index, value) in before.iter().enumerate().take(anchor_i).skip(i + paired) {
+            out.push(IrChange::Removed {
+                path: format!("{name}[{index}]"),
+                value: value.clone(),
+            });
+        }
+        for (index, value) in after.iter().enumerate().take(anchor_j).skip(j + paired) {
+            out.push(IrChange::Added {
+                path: format!("{name}[{index}]"),
+                value: value.clone(),
+            });
+        }
+        (i, j) = (anchor_i + 1, anchor_j + 1);
+    }
+}
+
+/// Index pairs of equal items forming a longest common subsequence.
+fn lcs_pairs(before: &[Value], after: &[Value]) -> Vec<(usize, usize)> {
+    let (n, m) = (before.len(), after.len());
+    let mut table = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if before[i] == after[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if before[i] == after[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+fn diff_values(path: &str, before: &Value, after: &Value, out: &mut Vec<IrChange>) {
+    if before == after {
+        return;
+    }
+    match (before, after) {
+        (Value::Object(b), Value::Object(a)) => diff_objects(path, b, a, out),
+        (Value::Array(b), Value::Array(a)) if b.len() == a.len() => {
+            for (index, (b, a)) in b.iter().zip(a).enumerate() {
+                diff_values(&format!("{path}[{index}]"), b, a, out);
+            }
+        }
+        _ => out.push(IrChange::Changed {
+            path: path.to_string(),
+            before: before.clone(),
+            after: after.clone(),
+        }),
+    }
+}
+
+fn diff_objects(
+    path: &str,
+    before: &Map<String, Value>,
+    after: &Map<String, Value>,
+    out: &mut Vec<IrChange>,
+) {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    for key in keys {
+        let field = format!("{path}.{key}");
+        match (before.get(key), after.get(key)) {
+            (Some(b), Some(a)) => diff_values(&field, b, a, out),
+            (Some(b), None) => out.push(IrChange::Removed {
+                path: field,
+                value: b.clone(),
+            }),
+            (None, Some(a)) => out.push(IrChange::Added {
+                path: field,
+                value: a.clone(),
+            }),
+            (None, None) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging_card::ir::{Element, Fact, IrAction, MessageCardIrBuilder};
+    use crate::messaging_card::tier::Tier;
+    use serde_json::json;
+
+    fn card() -> MessageCardIrBuilder {
+        MessageCardIrBuilder::default()
+            .tier(Tier::Advanced)
+            .title("Deploy")
+            .primary_text("Build 41 is ready", false)
+            .fact("Env", "staging")
+            .postback("Approve", json!({"ok": true}))
+    }
+
+    #[test]
+    fn identical_cards_have_no_changes() {
+        let mut after = card().build();
+        after.meta.warn("slack.inputs_not_supported");
+        let diff = card().build().diff(&after);
+        assert!(diff.is_empty());
+        assert_eq!(diff.summary(), "No card changes.\n");
+    }
+
+    #[test]
+    fn reports_field_changes_with_paths() {
+        let before = card().build();
+        let mut after = card().build();
+        after.head.title = Some("Deployed".into());
+        after.elements[1] = Element::FactSet {
+            facts: vec![Fact {
+                label: "Env".into(),
+                value: "prod".into(),
+            }],
+        };
+        let diff = before.diff(&after);
+        let paths: Vec<_> = diff.changes.iter().map(IrChange::path).collect();
+        assert_eq!(paths, vec!["head.title", "elements[1].facts[0].value"]);
+        assert!(
+            diff.summary()
+                .contains("~ head.title: \"Deploy\" -> \"Deployed\"")
+        );
+    }
+
+    #[test]
+    fn inserted_action_is_a_single_addition() {
+        let before = card().build();
+        let mut after = card().build();
+        after.actions.insert(
+            0,
+            IrAction::OpenUrl {
+                title: "Logs".into(),
+                url: "https://example.com/logs".into(),
+            },
+        );
+        after.elements.pop();
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.changes,
+            vec![
+                IrChange::Removed {
+                    path: "elements[1]".into(),
+                    value: json!({"type": "fact_set", "facts": [{"label": "Env", "value": "staging"}]}),
+                },
+                IrChange::Added {
+                    path: "actions[0]".into(),
+                    value: json!({"type": "open_url", "title": "Logs", "url": "https://example.com/logs"}),
+                },
+            ]
+        );
+        let summary = diff.summary();
+        assert!(summary.starts_with("- elements[1]: "));
+        assert!(summary.contains("+ actions[0]: "));
+    }
+
+    #[test]
+    fn element_kind_change_replaces_whole_element() {
+        let before = card().build();
+        let mut after = card().build();
+        after.elements[1] = Element::Image {
+            url: "https://example.com/a.png".into(),
+            alt: None,
+        };
+        let diff = before.diff(&after);
+        assert_eq!(diff.changes.len(), 1);
+        assert!(
+            matches!(&diff.changes[0], IrChange::Changed { path, .. } if path == "elements[1]")
+        );
+    }
+}
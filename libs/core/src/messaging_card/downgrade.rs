@@ -130,6 +130,7 @@ fn describe_action(action: &IrAction) -> &'static str {
     match action {
         IrAction::OpenUrl { .. } => "open_url",
         IrAction::Postback { .. } => "postback",
+        IrAction::Dialog { .. } => "dialog",
     }
 }
 
@@ -185,7 +186,7 @@ impl CapabilityProfile {
     fn supports_action(&self, action: &IrAction) -> bool {
         match action {
             IrAction::OpenUrl { .. } => true,
-            IrAction::Postback { .. } => self.allow_postbacks,
+            IrAction::Postback { .. } | IrAction::Dialog { .. } => self.allow_postbacks,
         }
     }
 }
@@ -1,10 +1,11 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::messaging_card::tier::Tier;
-use crate::messaging_card::types::{Action, MessageCard};
+use crate::messaging_card::types::MessageCard;
+use crate::types::{CardAction, CardBlock};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MessageCardIr {
@@ -31,29 +32,63 @@ impl Default for MessageCardIr {
 }
 
 impl MessageCardIr {
+    /// Builds an IR from the flat card shape by way of the canonical model, so both shapes
+    /// normalize identically.
     pub fn from_plain(card: &MessageCard) -> Self {
-        let mut builder = MessageCardIrBuilder::default().tier(Tier::Basic);
+        Self::from_v1(&card.clone().into())
+    }
 
+    /// Builds an IR from the canonical card (`gsm_core::MessageCard`) used by flows, `OutMessage`
+    /// and the `libs/cards` samples.
+    pub fn from_v1(card: &crate::types::MessageCard) -> Self {
+        let mut builder = MessageCardIrBuilder::default().tier(Tier::Basic);
         if let Some(title) = &card.title {
             builder = builder.title(title);
         }
-        if let Some(text) = &card.text {
-            builder = builder.primary_text(text, card.allow_markdown);
-        }
         if let Some(footer) = &card.footer {
             builder = builder.footer(footer);
         }
-        for image in &card.images {
-            builder = builder.image(image.url.clone(), image.alt.clone());
+        let mut built = builder.build();
+        for block in &card.body {
+            match block {
+                CardBlock::Text { text, markdown } => {
+                    if built.head.text.is_none() {
+                        built.head.text = Some(text.clone());
+                    }
+                    built.elements.push(Element::Text {
+                        text: text.clone(),
+                        markdown: *markdown,
+                    });
+                }
+                CardBlock::Fact { label, value } => {
+                    let fact = Fact {
+                        label: label.clone(),
+                        value: value.clone(),
+                    };
+                    // Consecutive fact blocks render as a single fact set.
+                    match built.elements.last_mut() {
+                        Some(Element::FactSet { facts }) => facts.push(fact),
+                        _ => built.elements.push(Element::FactSet { facts: vec![fact] }),
+                    }
+                }
+                CardBlock::Image { url, alt } => built.elements.push(Element::Image {
+                    url: url.clone(),
+                    alt: alt.clone(),
+                }),
+            }
         }
         for action in &card.actions {
-            builder = match action {
-                Action::OpenUrl { title, url } => builder.open_url(title, url),
-                Action::PostBack { title, data } => builder.postback(title, data.clone()),
-            };
+            built.actions.push(match action {
+                CardAction::OpenUrl { title, url, .. } => IrAction::OpenUrl {
+                    title: title.clone(),
+                    url: url.clone(),
+                },
+                CardAction::Postback { title, data } => IrAction::Postback {
+                    title: title.clone(),
+                    data: data.clone(),
+                },
+            });
         }
-
-        let mut built = builder.build();
         built.auto_tier();
         built
     }
@@ -80,10 +115,9 @@ impl MessageCardIr {
             .elements
             .iter()
             .any(|element| matches!(element, Element::Image { .. } | Element::FactSet { .. }))
-            || self
-                .actions
-                .iter()
-                .any(|action| matches!(action, IrAction::Postback { .. }));
+            || self.actions.iter().any(|action| {
+                matches!(action, IrAction::Postback { .. } | IrAction::Dialog { .. })
+            });
 
         if advanced {
             Tier::Advanced
@@ -152,12 +186,38 @@ pub struct InputChoice {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum IrAction {
-    OpenUrl { title: String, url: String },
-    Postback { title: String, data: Value },
+    OpenUrl {
+        title: String,
+        url: String,
+    },
+    Postback {
+        title: String,
+        data: Value,
+    },
+    /// Postback that asks the host to open a dialog (Teams task module, Slack modal) instead of
+    /// submitting inline. Hosts without dialog support treat it as a plain postback.
+    Dialog {
+        title: String,
+        data: Value,
+    },
+}
+
+impl IrAction {
+    pub fn title(&self) -> &str {
+        match self {
+            IrAction::OpenUrl { title, .. }
+            | IrAction::Postback { title, .. }
+            | IrAction::Dialog { title, .. } => title,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct Meta {
+    /// Stable id for this card instance. When set, postbacks carry a correlation stamp so
+    /// interactions can be attributed to the card.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_id: Option<String>,
     #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
     pub capabilities: BTreeSet<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -168,6 +228,20 @@ pub struct Meta {
     pub adaptive_payload: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub app_link: Option<AppLink>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyboard: Option<KeyboardLayout>,
+    /// Pre-approved template used when free-form messages are not allowed (WhatsApp outside
+    /// the customer-service window).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<TemplateMessage>,
+    /// Whether the recipient can currently receive free-form messages. `None` means unknown
+    /// and is treated as open.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_window_open: Option<bool>,
+    /// Platform media ids keyed by image URL, filled in by `ImagePipeline::attach` for the
+    /// platform about to render the card.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub media: BTreeMap<String, String>,
 }
 
 impl Meta {
@@ -180,6 +254,43 @@ impl Meta {
     }
 }
 
+/// Layout hints for platforms that render actions as a keyboard (Telegram).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct KeyboardLayout {
+    #[serde(default)]
+    pub kind: KeyboardKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buttons_per_row: Option<usize>,
+    /// Reply keyboards only: hide the keyboard after one use.
+    #[serde(default)]
+    pub one_time: bool,
+    /// Reply keyboards only: shrink the keyboard to fit its buttons.
+    #[serde(default)]
+    pub resize: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyboardKind {
+    /// Buttons attached to the message; postbacks arrive as callback queries.
+    #[default]
+    Inline,
+    /// Buttons replace the user's keyboard; a tap sends the button title as a text message.
+    Reply,
+}
+
+/// Reference to a pre-approved (HSM) template with its body parameters.
+///
+/// Parameters are literal strings or bind to card content via `{{title}}`, `{{text}}`,
+/// `{{footer}}` and `{{fact:<label>}}`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TemplateMessage {
+    pub name: String,
+    pub language: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AppLink {
     pub base_url: String,
@@ -294,6 +405,19 @@ impl MessageCardIrBuilder {
         self
     }
 
+    pub fn dialog(mut self, title: &str, data: Value) -> Self {
+        self.inner.actions.push(IrAction::Dialog {
+            title: title.into(),
+            data,
+        });
+        self
+    }
+
+    pub fn keyboard(mut self, layout: KeyboardLayout) -> Self {
+        self.inner.meta.keyboard = Some(layout);
+        self
+    }
+
     pub fn build(self) -> MessageCardIr {
         self.inner
     }
@@ -334,4 +458,15 @@ mod tests {
         assert_eq!(ir.actions.len(), 2);
         assert_eq!(ir.tier, Tier::Advanced);
     }
+
+    #[test]
+    fn dialog_actions_promote_to_advanced() {
+        let mut ir = MessageCardIrBuilder::default()
+            .title("Dialog")
+            .dialog("Open form", json!({"form": "expense"}))
+            .build();
+        ir.auto_tier();
+        assert_eq!(ir.tier, Tier::Advanced);
+        assert_eq!(ir.actions[0].title(), "Open form");
+    }
 }
@@ -0,0 +1,618 @@
+//! CommonMark to platform Markdown dialect conversion.
+//!
+//! Card text marked as Markdown is authored in CommonMark. Each chat platform understands a
+//! different subset with its own escaping rules, so renderers convert through this module
+//! instead of passing the source through verbatim.
+use std::ops::Range;
+
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+
+/// Markdown flavour understood by a target platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkdownDialect {
+    /// Slack `mrkdwn`.
+    SlackMrkdwn,
+    /// Telegram `parse_mode = MarkdownV2`.
+    TelegramMarkdownV2,
+    /// Telegram `parse_mode = HTML`.
+    TelegramHtml,
+    /// WhatsApp text formatting.
+    WhatsApp,
+    /// Webex message Markdown.
+    Webex,
+    /// Adaptive Card `TextBlock` Markdown subset (Teams, WebChat, Webex cards).
+    AdaptiveCard,
+}
+
+impl MarkdownDialect {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MarkdownDialect::SlackMrkdwn => "slack_mrkdwn",
+            MarkdownDialect::TelegramMarkdownV2 => "telegram_markdown_v2",
+            MarkdownDialect::TelegramHtml => "telegram_html",
+            MarkdownDialect::WhatsApp => "whatsapp",
+            MarkdownDialect::Webex => "webex",
+            MarkdownDialect::AdaptiveCard => "adaptive_card",
+        }
+    }
+}
+
+/// Converts CommonMark `source` into `dialect`, escaping literal text as the dialect requires.
+/// Raw HTML is dropped, matching the renderer sanitizer, and links to anything but `http`,
+/// `https`, `mailto` or `tel` URLs keep only their text.
+pub fn convert(source: &str, dialect: MarkdownDialect) -> String {
+    convert_with_links(source, dialect, |_| true)
+}
+
+/// Like [`convert`], but a link is only emitted when `allow_link` accepts its URL; rejected
+/// links keep only their text. Renderers pass their URL policy here so text links cannot
+/// bypass the checks applied to buttons and images.
+pub fn convert_with_links(
+    source: &str,
+    dialect: MarkdownDialect,
+    mut allow_link: impl FnMut(&str) -> bool,
+) -> String {
+    let mut writer = Writer::new(dialect, &mut allow_link);
+    for event in Parser::new_ext(source, parser_options()) {
+        writer.event(event);
+    }
+    writer.finish()
+}
+
+/// Rewrites CommonMark `source`, replacing every link and image whose URL `allow_link` rejects
+/// with its text. Unlike [`convert_with_links`] the result is still CommonMark, so a URL policy
+/// can be applied to card text before any renderer sees it.
+pub fn strip_links(source: &str, mut allow_link: impl FnMut(&str) -> bool) -> String {
+    // Open links and images, innermost last; `None` for ones that stay.
+    let mut open: Vec<Option<BlockedLink>> = Vec::new();
+    let mut blocked = Vec::new();
+    for (event, range) in Parser::new_ext(source, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. }) => {
+                extend_inner(&mut open, &range);
+                let allowed = has_link_scheme(&dest_url) && allow_link(&dest_url);
+                open.push((!allowed).then_some(BlockedLink {
+                    outer: range,
+                    inner: None,
+                }));
+            }
+            Event::End(TagEnd::Link | TagEnd::Image) => {
+                if let Some(Some(link)) = open.pop() {
+                    blocked.push(link);
+                }
+            }
+            _ => extend_inner(&mut open, &range),
+        }
+    }
+    blocked.sort_by_key(|link| link.outer.start);
+    let mut out = String::with_capacity(source.len());
+    splice(source, 0..source.len(), &blocked, &mut out);
+    out
+}
+
+fn parser_options() -> Options {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options
+}
+
+/// Source span of a rejected link and of the text inside it.
+struct BlockedLink {
+    outer: Range<usize>,
+    inner: Option<Range<usize>>,
+}
+
+fn extend_inner(open: &mut [Option<BlockedLink>], range: &Range<usize>) {
+    for link in open.iter_mut().flatten() {
+        link.inner = Some(match link.inner.take() {
+            Some(inner) => inner.start.min(range.start)..inner.end.max(range.end),
+            None => range.clone(),
+        });
+    }
+}
+
+/// Copies `source[range]` into `out`, replacing each blocked link inside it with its text.
+fn splice(source: &str, range: Range<usize>, blocked: &[BlockedLink], out: &mut String) {
+    let mut pos = range.start;
+    for link in blocked {
+        if link.outer.start < pos || link.outer.end > range.end {
+            continue;
+        }
+        out.push_str(&source[pos..link.outer.start]);
+        if let Some(inner) = &link.inner {
+            splice(source, inner.clone(), blocked, out);
+        }
+        pos = link.outer.end;
+    }
+    out.push_str(&source[pos..range.end]);
+}
+
+const LINK_SCHEMES: [&str; 4] = ["http", "https", "mailto", "tel"];
+
+fn has_link_scheme(url: &str) -> bool {
+    url.split_once(':').is_some_and(|(scheme, _)| {
+        LINK_SCHEMES
+            .iter()
+            .any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+    })
+}
+
+/// Escapes literal text so it renders verbatim in `dialect`.
+pub fn escape(text: &str, dialect: MarkdownDialect) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match dialect {
+            MarkdownDialect::SlackMrkdwn => match ch {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                _ => out.push(ch),
+            },
+            MarkdownDialect::TelegramHtml => match ch {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                _ => out.push(ch),
+            },
+            MarkdownDialect::TelegramMarkdownV2 => {
+                if TELEGRAM_V2_RESERVED.contains(ch) {
+                    out.push('\\');
+                }
+                out.push(ch);
+            }
+            MarkdownDialect::Webex | MarkdownDialect::AdaptiveCard => {
+                if COMMONMARK_RESERVED.contains(ch) {
+                    out.push('\\');
+                }
+                out.push(ch);
+            }
+            // WhatsApp has no escape syntax; text is passed through.
+            MarkdownDialect::WhatsApp => out.push(ch),
+        }
+    }
+    out
+}
+
+const TELEGRAM_V2_RESERVED: &str = "_*[]()~`>#+-=|{}.!\\";
+const COMMONMARK_RESERVED: &str = "\\`*_[]#~";
+
+struct Frame {
+    buf: String,
+    kind: FrameKind,
+}
+
+enum FrameKind {
+    Root,
+    Link(String),
+    Image(String),
+    Quote,
+    CodeBlock(Option<String>),
+}
+
+struct Writer<'a> {
+    dialect: MarkdownDialect,
+    allow_link: &'a mut dyn FnMut(&str) -> bool,
+    stack: Vec<Frame>,
+    /// Numbering state per open list: `Some(next)` for ordered lists.
+    lists: Vec<Option<u64>>,
+    /// Whether the next block needs a blank line before it.
+    pending_break: bool,
+}
+
+impl<'a> Writer<'a> {
+    fn new(dialect: MarkdownDialect, allow_link: &'a mut dyn FnMut(&str) -> bool) -> Self {
+        Self {
+            dialect,
+            allow_link,
+            stack: vec![Frame {
+                buf: String::new(),
+                kind: FrameKind::Root,
+            }],
+            lists: Vec::new(),
+            pending_break: false,
+        }
+    }
+
+    fn buf(&mut self) -> &mut String {
+        &mut self.stack.last_mut().expect("root frame").buf
+    }
+
+    fn push(&mut self, text: &str) {
+        self.buf().push_str(text);
+    }
+
+    fn push_frame(&mut self, kind: FrameKind) {
+        self.stack.push(Frame {
+            buf: String::new(),
+            kind,
+        });
+    }
+
+    fn pop_frame(&mut self) -> Frame {
+        self.stack.pop().expect("balanced frames")
+    }
+
+    fn in_code_block(&self) -> bool {
+        matches!(
+            self.stack.last().map(|f| &f.kind),
+            Some(FrameKind::CodeBlock(_))
+        )
+    }
+
+    /// Starts a block, separating it from the previous one.
+    fn start_block(&mut self) {
+        let in_list = !self.lists.is_empty();
+        let buf = self.buf();
+        if buf.is_empty() {
+            return;
+        }
+        if !buf.ends_with('\n') {
+            buf.push('\n');
+        }
+        if !in_list && !buf.ends_with("\n\n") {
+            buf.push('\n');
+        }
+    }
+
+    fn end_block(&mut self) {
+        self.pending_break = true;
+    }
+
+    fn event(&mut self, event: Event<'_>) {
+        if self.pending_break {
+            self.pending_break = false;
+            if !matches!(event, Event::End(_)) {
+                self.start_block();
+            }
+        }
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => {
+                if self.in_code_block() {
+                    let escaped = self.escape_code(&text);
+                    self.push(&escaped);
+                } else {
+                    let escaped = escape(&text, self.dialect);
+                    self.push(&escaped);
+                }
+            }
+            Event::Code(code) => {
+                let rendered = self.inline_code(&code);
+                self.push(&rendered);
+            }
+            Event::SoftBreak | Event::HardBreak => self.push("\n"),
+            Event::Rule => {
+                self.start_block();
+                let rule = match self.dialect {
+                    MarkdownDialect::Webex | MarkdownDialect::AdaptiveCard => "---",
+                    MarkdownDialect::TelegramMarkdownV2 => "\\-\\-\\-",
+                    _ => "———",
+                };
+                self.push(rule);
+                self.end_block();
+            }
+            Event::TaskListMarker(done) => self.push(if done { "☑ " } else { "☐ " }),
+            // Raw HTML is stripped like the renderer sanitizer does.
+            Event::Html(_) | Event::InlineHtml(_) => {}
+            Event::InlineMath(text) | Event::DisplayMath(text) => {
+                let escaped = escape(&text, self.dialect);
+                self.push(&escaped);
+            }
+            Event::FootnoteReference(_) => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::Paragraph => self.start_block(),
+            Tag::Heading { .. } => {
+                self.start_block();
+                let open = self.strong_marker(true);
+                self.push(open);
+            }
+            Tag::BlockQuote(_) => {
+                self.start_block();
+                self.push_frame(FrameKind::Quote);
+            }
+            Tag::CodeBlock(kind) => {
+                self.start_block();
+                let lang = match kind {
+                    CodeBlockKind::Fenced(lang) if !lang.is_empty() => Some(lang.to_string()),
+                    _ => None,
+                };
+                self.push_frame(FrameKind::CodeBlock(lang));
+            }
+            Tag::List(start) => {
+                if self.lists.is_empty() {
+                    self.start_block();
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                let depth = self.lists.len().saturating_sub(1);
+                let buf = self.buf();
+                if !buf.is_empty() && !buf.ends_with('\n') {
+                    buf.push('\n');
+                }
+                let indent = "  ".repeat(depth);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(next)) => {
+                        let marker = format!("{next}. ");
+                        *next += 1;
+                        if self.dialect == MarkdownDialect::TelegramMarkdownV2 {
+                            marker.replace('.', "\\.")
+                        } else {
+                            marker
+                        }
+                    }
+                    _ => match self.dialect {
+                        MarkdownDialect::Webex | MarkdownDialect::AdaptiveCard => "- ".to_string(),
+                        _ => "• ".to_string(),
+                    },
+                };
+                self.push(&indent);
+                self.push(&marker);
+            }
+            Tag::Emphasis => {
+                let marker = self.emphasis_marker(true);
+                self.push(marker);
+            }
+            Tag::Strong => {
+                let marker = self.strong_marker(true);
+                self.push(marker);
+            }
+            Tag::Strikethrough => {
+                let marker = self.strike_marker(true);
+                self.push(marker);
+            }
+            Tag::Link { dest_url, .. } => self.push_frame(FrameKind::Link(dest_url.to_string())),
+            Tag::Image { dest_url, .. } => self.push_frame(FrameKind::Image(dest_url.to_string())),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => self.end_block(),
+            TagEnd::Heading(_) => {
+                let close = self.strong_marker(false);
+                self.push(close);
+                self.end_block();
+            }
+            TagEnd::BlockQuote(_) => {
+                let frame = self.pop_frame();
+                let quoted = self.quote(frame.buf.trim_end());
+                self.push(&quoted);
+                self.end_block();
+            }
+            TagEnd::CodeBlock => {
+                let frame = self.pop_frame();
+                let lang = match frame.kind {
+                    FrameKind::CodeBlock(lang) => lang,
+                    _ => None,
+                };
+                let block = self.code_block(frame.buf.trim_end_matches('\n'), lang.as_deref());
+                self.push(&block);
+                self.end_block();
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.end_block();
+                }
+            }
+            TagEnd::Item => {
+                // Loose list items wrap their text in paragraphs; keep items on adjacent lines.
+                self.pending_break = false;
+            }
+            TagEnd::Emphasis => {
+                let marker = self.emphasis_marker(false);
+                self.push(marker);
+            }
+            TagEnd::Strong => {
+                let marker = self.strong_marker(false);
+                self.push(marker);
+            }
+            TagEnd::Strikethrough => {
+                let marker = self.strike_marker(false);
+                self.push(marker);
+            }
+            TagEnd::Link | TagEnd::Image => {
+                let frame = self.pop_frame();
+                let rendered = match frame.kind {
+                    FrameKind::Link(url) | FrameKind::Image(url)
+                        if has_link_scheme(&url) && (self.allow_link)(&url) =>
+                    {
+                        self.link(&frame.buf, &url)
+                    }
+                    _ => frame.buf,
+                };
+                self.push(&rendered);
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(mut self) -> String {
+        while self.stack.len() > 1 {
+            let frame = self.pop_frame();
+            self.push(&frame.buf);
+        }
+        self.pop_frame().buf.trim_end().to_string()
+    }
+
+    fn emphasis_marker(&self, open: bool) -> &'static str {
+        match self.dialect {
+            MarkdownDialect::TelegramHtml => {
+                if open {
+                    "<i>"
+                } else {
+                    "</i>"
+                }
+            }
+            MarkdownDialect::Webex => "*",
+            _ => "_",
+        }
+    }
+
+    fn strong_marker(&self, open: bool) -> &'static str {
+        match self.dialect {
+            MarkdownDialect::TelegramHtml => {
+                if open {
+                    "<b>"
+                } else {
+                    "</b>"
+                }
+            }
+            MarkdownDialect::Webex | MarkdownDialect::AdaptiveCard => "**",
+            _ => "*",
+        }
+    }
+
+    fn strike_marker(&self, open: bool) -> &'static str {
+        match self.dialect {
+            MarkdownDialect::TelegramHtml => {
+                if open {
+                    "<s>"
+                } else {
+                    "</s>"
+                }
+            }
+            MarkdownDialect::Webex => "~~",
+            // Adaptive Cards have no strikethrough; keep the text plain.
+            MarkdownDialect::AdaptiveCard => "",
+            _ => "~",
+        }
+    }
+
+    fn inline_code(&self, code: &str) -> String {
+        match self.dialect {
+            MarkdownDialect::TelegramHtml => format!("<code>{}</code>", self.escape_code(code)),
+            // Adaptive Cards have no code spans; escape so the content shows literally.
+            MarkdownDialect::AdaptiveCard => escape(code, self.dialect),
+            _ => format!("`{}`", self.escape_code(code)),
+        }
+    }
+
+    fn escape_code(&self, code: &str) -> String {
+        match self.dialect {
+            MarkdownDialect::TelegramHtml | MarkdownDialect::SlackMrkdwn => {
+                escape(code, MarkdownDialect::TelegramHtml)
+            }
+            MarkdownDialect::TelegramMarkdownV2 => code.replace('\\', "\\\\").replace('`', "\\`"),
+            MarkdownDialect::AdaptiveCard => escape(code, self.dialect),
+            _ => code.to_string(),
+        }
+    }
+
+    fn code_block(&self, code: &str, lang: Option<&str>) -> String {
+        match self.dialect {
+            MarkdownDialect::TelegramHtml => match lang {
+                Some(lang) => format!(
+                    "<pre><code class=\"language-{}\">{code}</code></pre>",
+                    escape(lang, self.dialect)
+                ),
+                None => format!("<pre>{code}</pre>"),
+            },
+            MarkdownDialect::TelegramMarkdownV2 | MarkdownDialect::Webex => {
+                format!("```{}\n{code}\n```", lang.unwrap_or_default())
+            }
+            MarkdownDialect::AdaptiveCard => code.to_string(),
+            _ => format!("```\n{code}\n```"),
+        }
+    }
+
+    fn quote(&self, text: &str) -> String {
+        match self.dialect {
+            MarkdownDialect::TelegramHtml => format!("<blockquote>{text}</blockquote>"),
+            MarkdownDialect::SlackMrkdwn
+            | MarkdownDialect::TelegramMarkdownV2
+            | MarkdownDialect::WhatsApp
+            | MarkdownDialect::Webex => text
+                .lines()
+                .map(|line| format!(">{}{line}", if line.is_empty() { "" } else { " " }))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            MarkdownDialect::AdaptiveCard => text.to_string(),
+        }
+    }
+
+    fn link(&self, text: &str, url: &str) -> String {
+        match self.dialect {
+            MarkdownDialect::SlackMrkdwn => {
+                let url = url.replace('|', "%7C").replace('>', "%3E");
+                if text.is_empty() {
+                    format!("<{url}>")
+                } else {
+                    format!("<{url}|{text}>")
+                }
+            }
+            MarkdownDialect::TelegramHtml => {
+                let href = escape(url, self.dialect).replace('"', "&quot;");
+                format!("<a href=\"{href}\">{text}</a>")
+            }
+            MarkdownDialect::TelegramMarkdownV2 => {
+                let url = url.replace('\\', "\\\\").replace(')', "\\)");
+                format!("[{text}]({url})")
+            }
+            MarkdownDialect::Webex | MarkdownDialect::AdaptiveCard => {
+                let url = url.replace(')', "%29");
+                format!("[{text}]({url})")
+            }
+            MarkdownDialect::WhatsApp => {
+                if text.is_empty() || text == url {
+                    url.to_string()
+                } else {
+                    format!("{text} ({url})")
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn telegram_v2_escapes_reserved_characters() {
+        assert_eq!(
+            convert(
+                "Price: 1.5 (approx) - done!",
+                MarkdownDialect::TelegramMarkdownV2
+            ),
+            "Price: 1\\.5 \\(approx\\) \\- done\\!"
+        );
+    }
+
+    #[test]
+    fn slack_links_use_angle_syntax() {
+        assert_eq!(
+            convert(
+                "See [docs](https://example.com/a?b=1) & more",
+                MarkdownDialect::SlackMrkdwn
+            ),
+            "See <https://example.com/a?b=1|docs> &amp; more"
+        );
+    }
+
+    #[test]
+    fn html_is_dropped() {
+        assert_eq!(
+            convert("Hello <b>world</b>", MarkdownDialect::TelegramHtml),
+            "Hello world"
+        );
+    }
+
+    #[test]
+    fn strip_links_keeps_text_of_rejected_links() {
+        let source = "[ok](https://a.example/x) [bad](https://b.example/) \
+            [![logo](https://b.example/l.png)](https://a.example/) [js](javascript:alert(1))";
+        assert_eq!(
+            strip_links(source, |url| url.starts_with("https://a.example/")),
+            "[ok](https://a.example/x) bad \
+            [logo](https://a.example/) js"
+        );
+    }
+}
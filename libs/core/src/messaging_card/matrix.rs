@@ -0,0 +1,422 @@
+//! Renders a set of cards across every registered platform and tier.
+//!
+//! The resulting [`RenderMatrix`] is written to a golden directory with one file per
+//! `(card, platform, tier)` cell and compared against a previous run, so renderer changes
+//! show up as reviewable diffs.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::messaging_card::ir::MessageCardIr;
+use crate::messaging_card::spec::RenderSpec;
+use crate::messaging_card::tier::Tier;
+use crate::messaging_card::types::MessageCard;
+use crate::messaging_card::{MessageCardEngine, RenderSnapshot};
+
+const TIERS: [Tier; 3] = [Tier::Basic, Tier::Advanced, Tier::Premium];
+
+/// Card to render in the matrix, identified by a path-like id such as `samples/weather`.
+#[derive(Debug, Clone)]
+pub struct MatrixCard {
+    pub id: String,
+    pub spec: RenderSpec,
+}
+
+impl MatrixCard {
+    /// Parses a card file in any of the supported shapes: a bare Adaptive Card, a
+    /// `MessageCard`, or a canonical v1 card with `body` blocks.
+    pub fn from_value(engine: &MessageCardEngine, id: &str, value: Value) -> Result<Self> {
+        let spec = if value.get("type").and_then(Value::as_str) == Some("AdaptiveCard") {
+            let card: MessageCard = serde_json::from_value(json!({ "adaptive": value }))?;
+            engine.render_spec(&card)?
+        } else if value.get("body").is_some_and(Value::is_array) {
+            let card: crate::types::MessageCard =
+                serde_json::from_value(value).context("invalid v1 card")?;
+            RenderSpec::Card(Box::new(MessageCardIr::from_v1(&card)))
+        } else {
+            let card: MessageCard = serde_json::from_value(value).context("invalid MessageCard")?;
+            engine.render_spec(&card)?
+        };
+        Ok(Self {
+            id: id.to_string(),
+            spec,
+        })
+    }
+}
+
+/// One rendered `(card, platform, tier)` combination.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatrixCell {
+    pub card: String,
+    pub platform: String,
+    pub tier: Tier,
+    pub target_tier: Tier,
+    pub downgraded: bool,
+    pub limit_exceeded: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    pub payloads: Vec<Value>,
+}
+
+impl MatrixCell {
+    fn from_snapshot(card: &str, platform: &str, tier: Tier, snapshot: RenderSnapshot) -> Self {
+        let warnings = match &snapshot.ir {
+            Some(ir) => ir.meta.warnings.clone(),
+            None => snapshot.output.warnings.clone(),
+        };
+        Self {
+            card: card.to_string(),
+            platform: platform.to_string(),
+            tier,
+            target_tier: snapshot.target_tier,
+            downgraded: snapshot.downgraded,
+            limit_exceeded: snapshot.output.limit_exceeded,
+            warnings,
+            payloads: snapshot.output.into_payloads(),
+        }
+    }
+
+    fn key(&self) -> CellKey {
+        (self.card.clone(), self.platform.clone(), self.tier)
+    }
+
+    fn relative_path(&self) -> PathBuf {
+        Path::new(&self.card)
+            .join(&self.platform)
+            .join(format!("{}.json", self.tier.as_str()))
+    }
+}
+
+type CellKey = (String, String, Tier);
+
+/// Rendered cells ordered by card, platform and tier.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderMatrix {
+    cells: BTreeMap<CellKey, MatrixCell>,
+}
+
+impl MessageCardEngine {
+    /// Renders every card on every registered platform. Cards are rendered at their own tier
+    /// and at each lower tier, so downgrade paths are covered too.
+    pub fn render_matrix(&self, cards: &[MatrixCard]) -> RenderMatrix {
+        let mut matrix = RenderMatrix::default();
+        for card in cards {
+            for platform in self.registry().platforms() {
+                match &card.spec {
+                    RenderSpec::Card(ir) => {
+                        for tier in TIERS.into_iter().filter(|tier| *tier <= ir.tier) {
+                            let ir = self.downgrade(ir, tier);
+                            if let Some(snapshot) = self.render_card_snapshot(&platform, &ir) {
+                                matrix.insert(MatrixCell::from_snapshot(
+                                    &card.id, &platform, tier, snapshot,
+                                ));
+                            }
+                        }
+                    }
+                    RenderSpec::Auth(_) => {
+                        if let Some(snapshot) = self.render_snapshot(&platform, &card.spec) {
+                            let tier = snapshot.tier;
+                            matrix.insert(MatrixCell::from_snapshot(
+                                &card.id, &platform, tier, snapshot,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        matrix
+    }
+}
+
+impl RenderMatrix {
+    pub fn insert(&mut self, cell: MatrixCell) {
+        self.cells.insert(cell.key(), cell);
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &MatrixCell> {
+        self.cells.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Reads a golden directory written by [`RenderMatrix::write_golden`]. A missing
+    /// directory yields an empty matrix.
+    pub fn load_golden(dir: &Path) -> Result<Self> {
+        let mut matrix = Self::default();
+        if !dir.exists() {
+            return Ok(matrix);
+        }
+        for path in golden_files(dir)? {
+            let data = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let cell: MatrixCell = serde_json::from_str(&data)
+                .with_context(|| format!("invalid golden cell {}", path.display()))?;
+            matrix.insert(cell);
+        }
+        Ok(matrix)
+    }
+
+    /// Writes one pretty-printed file per cell under `dir` and removes golden files for cells
+    /// that no longer exist.
+    pub fn write_golden(&self, dir: &Path) -> Result<()> {
+        let expected: BTreeSet<PathBuf> = self
+            .cells()
+            .map(|cell| dir.join(cell.relative_path()))
+            .collect();
+        if dir.exists() {
+            for stale in golden_files(dir)?
+                .into_iter()
+                .filter(|path| !expected.contains(path))
+            {
+                fs::remove_file(&stale)
+                    .with_context(|| format!("failed to remove {}", stale.display()))?;
+            }
+        }
+        for cell in self.cells() {
+            let path = dir.join(cell.relative_path());
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("failed to create {}", parent.display()))?;
+            }
+            let mut data = serde_json::to_string_pretty(cell)?;
+            data.push('\n');
+            fs::write(&path, data)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Compares this matrix against `baseline`, typically the committed golden directory.
+    pub fn diff(&self, baseline: &RenderMatrix) -> MatrixDiff {
+        let keys: BTreeSet<&CellKey> = self.cells.keys().chain(baseline.cells.keys()).collect();
+        let mut changes = Vec::new();
+        for key in keys {
+            let change = match (baseline.cells.get(key), self.cells.get(key)) {
+                (None, Some(_)) => CellChange::Added,
+                (Some(_), None) => CellChange::Removed,
+                (Some(before), Some(after)) if before != after => CellChange::Changed {
+                    payload_changed: before.payloads != after.payloads,
+                    warnings_added: added(&before.warnings, &after.warnings),
+                    warnings_removed: added(&after.warnings, &before.warnings),
+                    downgraded: flag_change(before.downgraded, after.downgraded),
+                    limit_exceeded: flag_change(before.limit_exceeded, after.limit_exceeded),
+                },
+                _ => continue,
+            };
+            changes.push(CellDiff {
+                card: key.0.clone(),
+                platform: key.1.clone(),
+                tier: key.2,
+                change,
+            });
+        }
+        MatrixDiff { changes }
+    }
+}
+
+/// Differences between two render matrices.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MatrixDiff {
+    pub changes: Vec<CellDiff>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CellDiff {
+    pub card: String,
+    pub platform: String,
+    pub tier: Tier,
+    pub change: CellChange,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CellChange {
+    Added,
+    Removed,
+    Changed {
+        payload_changed: bool,
+        warnings_added: Vec<String>,
+        warnings_removed: Vec<String>,
+        /// `(before, after)` when the downgrade flag flipped.
+        downgraded: Option<(bool, bool)>,
+        /// `(before, after)` when the limit flag flipped.
+        limit_exceeded: Option<(bool, bool)>,
+    },
+}
+
+impl MatrixDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Markdown report suitable for pasting into a review.
+    pub fn to_markdown(&self) -> String {
+        if self.is_empty() {
+            return "No render changes.\n".into();
+        }
+        let mut out = String::from("| card | platform | tier | change |\n|---|---|---|---|\n");
+        for diff in &self.changes {
+            let summary = match &diff.change {
+                CellChange::Added => "added".to_string(),
+                CellChange::Removed => "removed".to_string(),
+                CellChange::Changed {
+                    payload_changed,
+                    warnings_added,
+                    warnings_removed,
+                    downgraded,
+                    limit_exceeded,
+                } => {
+                    let mut parts = Vec::new();
+                    if *payload_changed {
+                        parts.push("payload changed".to_string());
+                    }
+                    if !warnings_added.is_empty() {
+                        parts.push(format!("+warnings: {}", warnings_added.join(", ")));
+                    }
+                    if !warnings_removed.is_empty() {
+                        parts.push(format!("-warnings: {}", warnings_removed.join(", ")));
+                    }
+                    if let Some((before, after)) = downgraded {
+                        parts.push(format!("downgraded {before} -> {after}"));
+                    }
+                    if let Some((before, after)) = limit_exceeded {
+                        parts.push(format!("limit exceeded {before} -> {after}"));
+                    }
+                    if parts.is_empty() {
+                        parts.push("target tier changed".to_string());
+                    }
+                    parts.join("; ")
+                }
+            };
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                diff.card,
+                diff.platform,
+                diff.tier.as_str(),
+                summary
+            );
+        }
+        out
+    }
+}
+
+fn added(before: &[String], after: &[String]) -> Vec<String> {
+    after
+        .iter()
+        .filter(|warning| !before.contains(warning))
+        .cloned()
+        .collect()
+}
+
+fn flag_change(before: bool, after: bool) -> Option<(bool, bool)> {
+    (before != after).then_some((before, after))
+}
+
+fn golden_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)
+            .with_context(|| format!("failed to read {}", current.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn cards(engine: &MessageCardEngine) -> Vec<MatrixCard> {
+        let v1 = json!({
+            "title": "Approval",
+            "body": [
+                {"type": "text", "text": "Please review"},
+                {"type": "fact", "label": "Amount", "value": "$10"}
+            ],
+            "actions": [{"type": "postback", "title": "Approve", "data": {"ok": true}}]
+        });
+        let plain = json!({"title": "Hello", "text": "World"});
+        vec![
+            MatrixCard::from_value(engine, "samples/approval", v1).unwrap(),
+            MatrixCard::from_value(engine, "fixtures/plain", plain).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn matrix_covers_every_platform_and_lower_tier() {
+        let engine = MessageCardEngine::bootstrap();
+        let matrix = engine.render_matrix(&cards(&engine));
+        let platforms = engine.registry().platforms().len();
+        // The v1 card is Advanced (facts + postback), so it renders at two tiers.
+        assert_eq!(matrix.len(), platforms * 3);
+        assert!(
+            matrix
+                .cells()
+                .any(|cell| cell.card == "samples/approval" && cell.tier == Tier::Basic)
+        );
+    }
+
+    #[test]
+    fn golden_round_trip_has_no_diff_and_detects_changes() {
+        let engine = MessageCardEngine::bootstrap();
+        let matrix = engine.render_matrix(&cards(&engine));
+        let dir = tempfile::tempdir().unwrap();
+        matrix.write_golden(dir.path()).unwrap();
+
+        let golden = RenderMatrix::load_golden(dir.path()).unwrap();
+        assert!(matrix.diff(&golden).is_empty());
+
+        let mut changed = matrix.clone();
+        let mut cell = changed.cells().next().unwrap().clone();
+        cell.payloads = vec![json!({"changed": true})];
+        cell.warnings.push("new.warning".into());
+        changed.insert(cell);
+        let diff = changed.diff(&golden);
+        assert_eq!(diff.changes.len(), 1);
+        let CellChange::Changed {
+            payload_changed,
+            warnings_added,
+            ..
+        } = &diff.changes[0].change
+        else {
+            panic!("expected a changed cell");
+        };
+        assert!(payload_changed);
+        assert_eq!(warnings_added, &vec!["new.warning".to_string()]);
+        assert!(diff.to_markdown().contains("+warnings: new.warning"));
+    }
+
+    #[test]
+    fn write_golden_removes_stale_cells() {
+        let engine = MessageCardEngine::bootstrap();
+        let all = cards(&engine);
+        let dir = tempfile::tempdir().unwrap();
+        engine.render_matrix(&all).write_golden(dir.path()).unwrap();
+
+        let smaller = engine.render_matrix(&all[1..]);
+        smaller.write_golden(dir.path()).unwrap();
+        assert_eq!(RenderMatrix::load_golden(dir.path()).unwrap(), smaller);
+    }
+}
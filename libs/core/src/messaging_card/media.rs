@@ -0,0 +1,472 @@
+//! Image pipeline that turns card image URLs into platform media ids.
+//!
+//! WhatsApp and Telegram send images as uploaded media rather than links, each with its own
+//! size and format limits. The pipeline fetches the source image, checks its format, scales and
+//! recompresses it to fit the target platform, uploads it once per content hash and platform,
+//! and records the resulting media id on the IR so renderers can reference it.
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::messaging_card::ir::{Element, MessageCardIr};
+use crate::path_safety::normalize_under_root;
+
+/// Source images larger than this are rejected before decoding.
+const MAX_SOURCE_BYTES: usize = 25 * 1024 * 1024;
+/// JPEG qualities tried in order until the encoded image fits the platform size limit.
+const JPEG_QUALITIES: [u8; 4] = [85, 70, 55, 40];
+
+#[derive(Debug, Error)]
+pub enum MediaError {
+    #[error("failed to fetch image {url}: {reason}")]
+    Fetch { url: String, reason: String },
+    #[error("unsupported image type {0}")]
+    UnsupportedType(String),
+    #[error("image is {size} bytes, limit is {limit}")]
+    TooLarge { size: usize, limit: usize },
+    #[error("failed to process image: {0}")]
+    Decode(String),
+    #[error("failed to upload image to {platform}: {reason}")]
+    Upload { platform: String, reason: String },
+}
+
+/// Raw image bytes as returned by an [`ImageFetcher`].
+#[derive(Debug, Clone)]
+pub struct FetchedImage {
+    pub bytes: Vec<u8>,
+    /// Content type reported by the source. Informational only; the format is sniffed from
+    /// the bytes.
+    pub content_type: Option<String>,
+}
+
+/// Loads image bytes for a card image URL.
+pub trait ImageFetcher: Send + Sync {
+    fn fetch(&self, url: &str) -> Result<FetchedImage, MediaError>;
+}
+
+/// Fetcher that reads `file://` URLs and relative paths from a local directory. Stands in for
+/// an HTTP fetcher in tests and offline tooling.
+#[derive(Debug, Clone)]
+pub struct LocalFileFetcher {
+    root: PathBuf,
+}
+
+impl LocalFileFetcher {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn resolve(&self, url: &str) -> anyhow::Result<PathBuf> {
+        let relative = match url.strip_prefix("file://") {
+            Some(path) => path.trim_start_matches('/'),
+            None if url.contains("://") => anyhow::bail!("unsupported URL scheme"),
+            None => url,
+        };
+        let root = self.root.canonicalize()?;
+        normalize_under_root(&root, Path::new(relative))
+    }
+}
+
+impl ImageFetcher for LocalFileFetcher {
+    fn fetch(&self, url: &str) -> Result<FetchedImage, MediaError> {
+        let fetch_error = |reason: String| MediaError::Fetch {
+            url: url.to_string(),
+            reason,
+        };
+        let path = self
+            .resolve(url)
+            .map_err(|err| fetch_error(err.to_string()))?;
+        let bytes = std::fs::read(&path).map_err(|err| fetch_error(err.to_string()))?;
+        Ok(FetchedImage {
+            bytes,
+            content_type: None,
+        })
+    }
+}
+
+/// Size and format constraints a platform places on uploaded images.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaLimits {
+    pub max_bytes: usize,
+    /// Longest allowed edge in pixels; larger images are scaled down.
+    pub max_dimension: u32,
+    pub mime_types: &'static [&'static str],
+}
+
+impl MediaLimits {
+    pub fn for_platform(platform: &str) -> Self {
+        match platform {
+            "whatsapp" => Self {
+                max_bytes: 5 * 1024 * 1024,
+                max_dimension: 4096,
+                mime_types: &["image/jpeg", "image/png"],
+            },
+            // Telegram recompresses photos to 2560px, so larger uploads only cost bandwidth.
+            "telegram" => Self {
+                max_bytes: 10 * 1024 * 1024,
+                max_dimension: 2560,
+                mime_types: &["image/jpeg", "image/png"],
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+impl Default for MediaLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 10 * 1024 * 1024,
+            max_dimension: 4096,
+            mime_types: &["image/jpeg", "image/png"],
+        }
+    }
+}
+
+/// Image ready for upload to a specific platform.
+#[derive(Debug, Clone)]
+pub struct PreparedImage {
+    pub bytes: Vec<u8>,
+    pub mime_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+    /// SHA-256 of the source bytes, hex encoded.
+    pub content_hash: String,
+}
+
+/// Uploads prepared images and returns the platform media id.
+pub trait MediaUploader: Send + Sync {
+    fn upload(&self, platform: &str, image: &PreparedImage) -> Result<String, MediaError>;
+}
+
+/// Remembers media ids by platform and source content hash so identical images are uploaded
+/// once per platform.
+pub trait MediaCache: Send + Sync {
+    fn get(&self, platform: &str, content_hash: &str) -> Option<String>;
+    fn put(&self, platform: &str, content_hash: &str, media_id: String);
+}
+
+/// Media cache held in this process's memory. Entries are lost on restart and not shared, so
+/// with several egress replicas each one uploads the same image again and the platform keeps
+/// one copy per replica; plug a shared `MediaCache` in for those deployments.
+#[derive(Default)]
+pub struct InMemoryMediaCache {
+    entries: Mutex<HashMap<(String, String), String>>,
+}
+
+impl InMemoryMediaCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("media cache poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl MediaCache for InMemoryMediaCache {
+    fn get(&self, platform: &str, content_hash: &str) -> Option<String> {
+        self.entries
+            .lock()
+            .expect("media cache poisoned")
+            .get(&(platform.to_string(), content_hash.to_string()))
+            .cloned()
+    }
+
+    fn put(&self, platform: &str, content_hash: &str, media_id: String) {
+        self.entries
+            .lock()
+            .expect("media cache poisoned")
+            .insert((platform.to_string(), content_hash.to_string()), media_id);
+    }
+}
+
+/// Fetches, normalises and uploads card images for platforms that need media ids.
+#[derive(Clone)]
+pub struct ImagePipeline {
+    fetcher: Arc<dyn ImageFetcher>,
+    uploader: Arc<dyn MediaUploader>,
+    cache: Arc<dyn MediaCache>,
+}
+
+impl ImagePipeline {
+    pub fn new(fetcher: Arc<dyn ImageFetcher>, uploader: Arc<dyn MediaUploader>) -> Self {
+        Self {
+            fetcher,
+            uploader,
+            cache: Arc::new(InMemoryMediaCache::new()),
+        }
+    }
+
+    pub fn with_cache(mut self, cache: Arc<dyn MediaCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Returns the media id for `url` on `platform`, uploading the image unless an identical
+    /// one was uploaded before.
+    pub fn media_id(&self, platform: &str, url: &str) -> Result<String, MediaError> {
+        let fetched = self.fetcher.fetch(url)?;
+        let content_hash = content_hash(&fetched.bytes);
+        if let Some(media_id) = self.cache.get(platform, &content_hash) {
+            return Ok(media_id);
+        }
+        let prepared = prepare_image(
+            &fetched.bytes,
+            content_hash,
+            &MediaLimits::for_platform(platform),
+        )?;
+        let media_id = self.uploader.upload(platform, &prepared)?;
+        self.cache
+            .put(platform, &prepared.content_hash, media_id.clone());
+        Ok(media_id)
+    }
+
+    /// Resolves every image in `ir` for `platform` and records the media ids in
+    /// `ir.meta.media`. Images that fail are left as URLs and reported as warnings.
+    pub fn attach(&self, platform: &str, ir: &mut MessageCardIr) {
+        let urls: Vec<String> = ir
+            .elements
+            .iter()
+            .filter_map(|element| match element {
+                Element::Image { url, .. } => Some(url.clone()),
+                _ => None,
+            })
+            .collect();
+        for url in urls {
+            if ir.meta.media.contains_key(&url) {
+                continue;
+            }
+            match self.media_id(platform, &url) {
+                Ok(media_id) => {
+                    ir.meta.media.insert(url, media_id);
+                }
+                Err(err) => ir
+                    .meta
+                    .warn(format!("image {url} kept as link for {platform}: {err}")),
+            }
+        }
+    }
+}
+
+/// Validates `bytes` against `limits`, scaling and recompressing the image when needed.
+pub fn prepare_image(
+    bytes: &[u8],
+    content_hash: String,
+    limits: &MediaLimits,
+) -> Result<PreparedImage, MediaError> {
+    if bytes.len() > MAX_SOURCE_BYTES {
+        return Err(MediaError::TooLarge {
+            size: bytes.len(),
+            limit: MAX_SOURCE_BYTES,
+        });
+    }
+    let format =
+        image::guess_format(bytes).map_err(|_| MediaError::UnsupportedType("unknown".into()))?;
+    let mime_type = format.to_mime_type();
+    if !limits.mime_types.contains(&mime_type) {
+        return Err(MediaError::UnsupportedType(mime_type.into()));
+    }
+
+    let decoded = image::load_from_memory_with_format(bytes, format)
+        .map_err(|err| MediaError::Decode(err.to_string()))?;
+    let oversized = decoded.width().max(decoded.height()) > limits.max_dimension;
+    if !oversized && bytes.len() <= limits.max_bytes {
+        return Ok(PreparedImage {
+            bytes: bytes.to_vec(),
+            mime_type,
+            width: decoded.width(),
+            height: decoded.height(),
+            content_hash,
+        });
+    }
+
+    let image = if oversized {
+        decoded.resize(
+            limits.max_dimension,
+            limits.max_dimension,
+            FilterType::Triangle,
+        )
+    } else {
+        decoded
+    };
+    // PNG keeps transparency, so re-encode losslessly first and fall back to JPEG.
+    if format == ImageFormat::Png {
+        let encoded = encode(&image, ImageFormat::Png)?;
+        if encoded.len() <= limits.max_bytes {
+            return Ok(PreparedImage {
+                bytes: encoded,
+                mime_type,
+                width: image.width(),
+                height: image.height(),
+                content_hash,
+            });
+        }
+    }
+    if !limits
+        .mime_types
+        .contains(&ImageFormat::Jpeg.to_mime_type())
+    {
+        return Err(MediaError::TooLarge {
+            size: bytes.len(),
+            limit: limits.max_bytes,
+        });
+    }
+    let rgb = image.to_rgb8();
+    let mut smallest = usize::MAX;
+    for quality in JPEG_QUALITIES {
+        let mut encoded = Vec::new();
+        JpegEncoder::new_with_quality(&mut encoded, quality)
+            .encode_image(&rgb)
+            .map_err(|err| MediaError::Decode(err.to_string()))?;
+        if encoded.len() <= limits.max_bytes {
+            return Ok(PreparedImage {
+                bytes: encoded,
+                mime_type: ImageFormat::Jpeg.to_mime_type(),
+                width: rgb.width(),
+                height: rgb.height(),
+                content_hash,
+            });
+        }
+        smallest = smallest.min(encoded.len());
+    }
+    Err(MediaError::TooLarge {
+        size: smallest,
+        limit: limits.max_bytes,
+    })
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>, MediaError> {
+    let mut buffer = Cursor::new(Vec::new());
+    image
+        .write_to(&mut buffer, format)
+        .map_err(|err| MediaError::Decode(err.to_string()))?;
+    Ok(buffer.into_inner())
+}
+
+/// Hex-encoded SHA-256 of `bytes`, used as the media cache key.
+pub fn content_hash(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    let mut hex = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct CountingUploader {
+        calls: AtomicUsize,
+    }
+
+    impl MediaUploader for CountingUploader {
+        fn upload(&self, platform: &str, image: &PreparedImage) -> Result<String, MediaError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("{platform}-{}-{call}", image.width))
+        }
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbImage::from_pixel(width, height, Rgb([200, 40, 40]));
+        encode(&DynamicImage::ImageRgb8(image), ImageFormat::Png).unwrap()
+    }
+
+    #[test]
+    fn small_images_pass_through_unchanged() {
+        let bytes = png(32, 16);
+        let prepared =
+            prepare_image(&bytes, content_hash(&bytes), &MediaLimits::default()).unwrap();
+        assert_eq!(prepared.bytes, bytes);
+        assert_eq!(prepared.mime_type, "image/png");
+        assert_eq!((prepared.width, prepared.height), (32, 16));
+    }
+
+    #[test]
+    fn oversized_images_are_scaled_to_the_platform_limit() {
+        let bytes = png(400, 100);
+        let limits = MediaLimits {
+            max_dimension: 200,
+            ..MediaLimits::default()
+        };
+        let prepared = prepare_image(&bytes, content_hash(&bytes), &limits).unwrap();
+        assert_eq!((prepared.width, prepared.height), (200, 50));
+    }
+
+    #[test]
+    fn heavy_images_are_recompressed_as_jpeg() {
+        let noise = RgbImage::from_fn(256, 256, |x, y| {
+            let v = ((x * 7919 + y * 104_729) % 251) as u8;
+            Rgb([v, v.wrapping_mul(3), v.wrapping_mul(7)])
+        });
+        let bytes = encode(&DynamicImage::ImageRgb8(noise), ImageFormat::Png).unwrap();
+        let limits = MediaLimits {
+            max_bytes: bytes.len() / 2,
+            ..MediaLimits::default()
+        };
+        let prepared = prepare_image(&bytes, content_hash(&bytes), &limits).unwrap();
+        assert_eq!(prepared.mime_type, "image/jpeg");
+        assert!(prepared.bytes.len() <= limits.max_bytes);
+    }
+
+    #[test]
+    fn unsupported_types_are_rejected() {
+        let err = prepare_image(b"GIF89a....", "x".into(), &MediaLimits::default()).unwrap_err();
+        assert!(matches!(err, MediaError::UnsupportedType(_)), "{err}");
+    }
+
+    #[test]
+    fn uploads_are_cached_by_content_and_platform() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.png"), png(10, 10)).unwrap();
+        std::fs::write(dir.path().join("b.png"), png(10, 10)).unwrap();
+        let uploader = Arc::new(CountingUploader::default());
+        let pipeline = ImagePipeline::new(
+            Arc::new(LocalFileFetcher::new(dir.path())),
+            uploader.clone(),
+        );
+
+        let first = pipeline.media_id("whatsapp", "file:///a.png").unwrap();
+        assert_eq!(pipeline.media_id("whatsapp", "b.png").unwrap(), first);
+        assert_ne!(pipeline.media_id("telegram", "a.png").unwrap(), first);
+        assert_eq!(uploader.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn attach_records_media_ids_and_warns_on_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("logo.png"), png(10, 10)).unwrap();
+        let pipeline = ImagePipeline::new(
+            Arc::new(LocalFileFetcher::new(dir.path())),
+            Arc::new(CountingUploader::default()),
+        );
+        let mut ir = crate::messaging_card::MessageCardIrBuilder::default()
+            .image("file:///logo.png".into(), None)
+            .image("../escape.png".into(), None)
+            .build();
+
+        pipeline.attach("whatsapp", &mut ir);
+        assert_eq!(
+            ir.meta.media.get("file:///logo.png").map(String::as_str),
+            Some("whatsapp-10-0")
+        );
+        assert_eq!(ir.meta.media.len(), 1);
+        assert_eq!(ir.meta.warnings.len(), 1);
+    }
+}
@@ -1,33 +1,61 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Result, anyhow};
 use serde_json::Value;
 
 use crate::messaging_card::renderers::RenderOutput;
+use crate::render_plan::RenderPlan;
+use crate::render_planner::apply_plan;
 
+pub mod accessibility;
 pub mod adaptive;
+pub mod diff;
 pub mod downgrade;
 pub mod ir;
+pub mod markdown;
+pub mod matrix;
+pub mod media;
 pub mod oauth_support;
+pub mod postback_tokens;
 pub mod renderers;
 pub mod spec;
+pub mod split;
 pub mod telemetry;
 pub mod tier;
 pub mod types;
 
+pub use accessibility::lint_ir;
 pub use adaptive::{
-    AdaptiveCardPayload, AdaptiveCardVersion, normalizer,
+    AdaptiveCardHost, AdaptiveCardPayload, AdaptiveCardVersion, HostValidation, normalizer,
     validator::{ValidateError, validate_ac_json},
 };
+pub use diff::{IrChange, IrDiff};
 pub use downgrade::{CapabilityProfile, DowngradeContext, DowngradeEngine, PolicyDowngradeEngine};
 pub use ir::{MessageCardIr, MessageCardIrBuilder};
-pub use oauth_support::ensure_oauth_start_url;
+pub use markdown::MarkdownDialect;
+pub use matrix::{CellChange, CellDiff, MatrixCard, MatrixCell, MatrixDiff, RenderMatrix};
+pub use media::{
+    FetchedImage, ImageFetcher, ImagePipeline, InMemoryMediaCache, LocalFileFetcher, MediaCache,
+    MediaError, MediaLimits, MediaUploader, PreparedImage,
+};
+pub use oauth_support::{OauthCardHolder, ensure_oauth_start_url};
+pub use postback_tokens::{
+    DirPostbackTokenStore, InMemoryPostbackTokenStore, POSTBACK_TOKEN_PREFIX, PostbackTokenStore,
+    resolve_callback_data,
+};
 pub use renderers::{
-    NullRenderer, PlatformRenderer, RendererRegistry, SlackRenderer, TeamsRenderer,
-    TelegramRenderer, WebChatRenderer, WebexRenderer, WhatsAppRenderer,
+    NullRenderer, PlatformRenderer, RendererRegistry, SlackRenderer, SlackViewTarget,
+    TaskModuleSize, TeamsRenderer, TelegramRenderer, WebChatRenderer, WebexRenderer,
+    WhatsAppRenderer, task_module_message,
+};
+#[cfg(feature = "component-host")]
+pub use renderers::{WasmRenderer, register_pack_renderers};
+pub use spec::{AuthRenderSpec, CardUpdate, FallbackButton, RenderIntent, RenderSpec};
+pub use telemetry::{
+    CORRELATION_KEY, CardTelemetry, Correlation, NullTelemetry, TelemetryEvent, TelemetryHook,
+    stamp_correlation, take_correlation,
 };
-pub use spec::{AuthRenderSpec, FallbackButton, RenderIntent, RenderSpec};
-pub use telemetry::{CardTelemetry, NullTelemetry, TelemetryEvent, TelemetryHook};
 pub use tier::Tier;
 pub use types::{
     Action, ImageRef, MessageCard, MessageCardKind, OauthCard, OauthPrompt, OauthProvider,
@@ -38,21 +66,37 @@ pub struct MessageCardEngine {
     renderer_registry: RendererRegistry,
     downgrade: PolicyDowngradeEngine,
     telemetry: Arc<dyn TelemetryHook>,
+    card_ttl: Option<Duration>,
+    postback_tokens: Arc<dyn PostbackTokenStore>,
+}
+
+/// Result of [`MessageCardEngine::record_interaction`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum InteractionOutcome {
+    /// The payload carried no correlation stamp.
+    Untracked,
+    Recorded(Correlation),
+    /// The card is older than the configured time to live.
+    Expired(Correlation),
 }
 
 impl Default for MessageCardEngine {
     fn default() -> Self {
+        let postback_tokens: Arc<dyn PostbackTokenStore> =
+            Arc::new(InMemoryPostbackTokenStore::new());
         let mut registry = RendererRegistry::default();
         registry.register(TeamsRenderer);
         registry.register(WebChatRenderer);
         registry.register(SlackRenderer);
         registry.register(WebexRenderer);
-        registry.register(TelegramRenderer);
+        registry.register(TelegramRenderer::default().with_token_store(postback_tokens.clone()));
         registry.register(WhatsAppRenderer);
         Self {
             renderer_registry: registry,
             downgrade: PolicyDowngradeEngine,
             telemetry: Arc::new(NullTelemetry),
+            card_ttl: None,
+            postback_tokens,
         }
     }
 }
@@ -63,6 +107,8 @@ impl MessageCardEngine {
             renderer_registry,
             downgrade: PolicyDowngradeEngine,
             telemetry: Arc::new(NullTelemetry),
+            card_ttl: None,
+            postback_tokens: Arc::new(InMemoryPostbackTokenStore::new()),
         }
     }
 
@@ -77,6 +123,44 @@ impl MessageCardEngine {
         self
     }
 
+    /// Interactions with cards older than `ttl` are reported as `CardExpired`.
+    pub fn with_card_ttl(mut self, ttl: Duration) -> Self {
+        self.card_ttl = Some(ttl);
+        self
+    }
+
+    /// Stores oversized postbacks in `store` instead of the default in-process one. The Telegram
+    /// renderer is re-registered to issue its tokens there; ingress must resolve against the
+    /// same store.
+    pub fn with_postback_token_store(mut self, store: Arc<dyn PostbackTokenStore>) -> Self {
+        self.renderer_registry
+            .register(TelegramRenderer::default().with_token_store(store.clone()));
+        self.postback_tokens = store;
+        self
+    }
+
+    /// Loads the renderers packs declare through `messaging.renderer.v1`; see
+    /// [`register_pack_renderers`].
+    #[cfg(feature = "component-host")]
+    pub fn with_pack_renderers(
+        mut self,
+        extensions: &crate::pack_extensions::ProviderExtensionsRegistry,
+        components_root: &std::path::Path,
+    ) -> Self {
+        register_pack_renderers(&mut self.renderer_registry, extensions, components_root);
+        self
+    }
+
+    pub fn postback_tokens(&self) -> Arc<dyn PostbackTokenStore> {
+        self.postback_tokens.clone()
+    }
+
+    /// Resolves inbound callback data against this engine's token store; see
+    /// [`resolve_callback_data`].
+    pub fn resolve_callback_data(&self, callback_data: &str) -> Option<Value> {
+        resolve_callback_data(self.postback_tokens.as_ref(), callback_data)
+    }
+
     pub fn registry(&self) -> &RendererRegistry {
         &self.renderer_registry
     }
@@ -99,21 +183,35 @@ impl MessageCardEngine {
         self.normalize_ir(card)
     }
 
-    /// Produces a normalized render specification for downstream renderers.
+    /// Produces a normalized render specification for downstream renderers. The flat card is
+    /// converted to the canonical model and takes the [`Self::render_card_spec`] path.
     pub fn render_spec(&self, card: &MessageCard) -> Result<RenderSpec> {
-        match card.kind {
-            MessageCardKind::Standard => {
-                let ir = self.normalize_ir(card)?;
-                Ok(RenderSpec::Card(Box::new(ir)))
-            }
-            MessageCardKind::Oauth => {
-                let oauth = card
-                    .oauth
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("oauth card missing oauth block"))?;
-                Ok(RenderSpec::Auth(AuthRenderSpec::from_card(card, oauth)))
-            }
+        self.render_card_spec(&card.clone().into())
+    }
+
+    /// Produces a render specification from the canonical card model. Raw Adaptive Cards are
+    /// validated and normalized, OAuth prompts become [`RenderSpec::Auth`], and everything else
+    /// keeps its block order, facts included.
+    pub fn render_card_spec(&self, card: &crate::types::MessageCard) -> Result<RenderSpec> {
+        if matches!(card.kind, MessageCardKind::Oauth) {
+            let oauth = card
+                .oauth
+                .as_ref()
+                .ok_or_else(|| anyhow!("oauth card missing oauth block"))?;
+            return Ok(RenderSpec::Auth(AuthRenderSpec::with_title(
+                card.title.as_deref(),
+                oauth,
+            )));
         }
+        let ir = match &card.adaptive {
+            Some(ac) => self.normalize_adaptive(ac)?,
+            None => {
+                let mut ir = MessageCardIr::from_v1(card);
+                ir.meta.source = Some("plain".into());
+                ir
+            }
+        };
+        Ok(RenderSpec::Card(Box::new(ir)))
     }
 
     pub fn render(&self, platform: &str, ir: &MessageCardIr) -> Option<Value> {
@@ -133,6 +231,13 @@ impl MessageCardEngine {
             .map(|snapshot| snapshot.output.payload)
     }
 
+    /// Renders `spec` into every payload egress must send, in order. Content that exceeds
+    /// platform limits may be split across several messages.
+    pub fn render_spec_payloads(&self, platform: &str, spec: &RenderSpec) -> Option<Vec<Value>> {
+        self.render_snapshot_tracked(platform, spec)
+            .map(|snapshot| snapshot.output.into_payloads())
+    }
+
     pub fn render_snapshot_tracked(
         &self,
         platform: &str,
@@ -152,6 +257,24 @@ impl MessageCardEngine {
         }
     }
 
+    /// Renders `spec` after applying a planner decision: dropped elements are removed and
+    /// downgraded ones rewritten before the platform renderer sees the card. Auth specs are
+    /// rendered unchanged.
+    pub fn render_planned(
+        &self,
+        platform: &str,
+        spec: &RenderSpec,
+        plan: &RenderPlan,
+    ) -> Option<RenderSnapshot> {
+        match spec {
+            RenderSpec::Card(ir) => self.render_snapshot_tracked(
+                platform,
+                &RenderSpec::Card(Box::new(apply_plan(ir, plan))),
+            ),
+            RenderSpec::Auth(_) => self.render_snapshot_tracked(platform, spec),
+        }
+    }
+
     pub fn render_snapshot(&self, platform: &str, spec: &RenderSpec) -> Option<RenderSnapshot> {
         match spec {
             RenderSpec::Card(ir) => self.render_card_snapshot(platform, ir.as_ref()),
@@ -164,7 +287,10 @@ impl MessageCardEngine {
         platform: &str,
         ir: &MessageCardIr,
     ) -> Option<RenderSnapshot> {
-        let renderer = self.renderer_registry.get(platform)?;
+        let Some(renderer) = self.renderer_registry.get(platform) else {
+            self.record_render_failure(platform, ir.meta.card_id.as_deref());
+            return None;
+        };
         let target_tier = renderer.target_tier();
         let downgraded = ir.tier > target_tier;
         let mut render_ir = if downgraded {
@@ -172,7 +298,23 @@ impl MessageCardEngine {
         } else {
             ir.clone()
         };
-        let rendered = renderer.render(&render_ir);
+        stamp_correlation(&mut render_ir, telemetry::now_ms());
+        let rendered = match renderer.try_render(&render_ir) {
+            Ok(rendered) => rendered,
+            Err(err) => {
+                CardTelemetry::new(self.telemetry.as_ref()).render_failed(
+                    platform,
+                    ir.meta.card_id.as_deref(),
+                    format!("{err:#}"),
+                );
+                let mut rendered = self
+                    .renderer_registry
+                    .fallback(platform)?
+                    .render(&render_ir);
+                rendered.warnings.push(format!("{platform}.render_failed"));
+                rendered
+            }
+        };
         if !rendered.warnings.is_empty() {
             render_ir
                 .meta
@@ -189,12 +331,43 @@ impl MessageCardEngine {
         })
     }
 
+    /// Renders `update.card` as an in-place edit of the previously sent message. Platforms that
+    /// cannot edit get the card as a new message plus a `<platform>.edit_unsupported` warning.
+    pub fn render_update(&self, platform: &str, update: &CardUpdate) -> Option<RenderSnapshot> {
+        let mut snapshot = self.render_card_snapshot(platform, &update.card)?;
+        let renderer = self.renderer_registry.get(platform)?;
+        match renderer.render_update(&update.provider_message_id, &snapshot.output) {
+            Some(payload) => {
+                snapshot.output.payload = payload;
+                snapshot.output.edit_message_id = Some(update.provider_message_id.clone());
+            }
+            None => {
+                let warning = format!("{platform}.edit_unsupported");
+                if let Some(ir) = snapshot.ir.as_mut() {
+                    ir.meta.warn(warning.clone());
+                }
+                snapshot.output.warnings.push(warning);
+            }
+        }
+        self.record_render_event(
+            platform,
+            snapshot.tier,
+            snapshot.warning_count(),
+            &snapshot.output,
+            snapshot.downgraded,
+        );
+        Some(snapshot)
+    }
+
     fn render_auth_snapshot(
         &self,
         platform: &str,
         auth: &AuthRenderSpec,
     ) -> Option<RenderSnapshot> {
-        let renderer = self.renderer_registry.get(platform)?;
+        let Some(renderer) = self.renderer_registry.get(platform) else {
+            self.record_render_failure(platform, None);
+            return None;
+        };
         if let Some(rendered) = renderer.render_auth(auth) {
             return Some(RenderSnapshot {
                 output: rendered,
@@ -243,6 +416,33 @@ impl MessageCardEngine {
         telemetry.downgrading(ir.tier, ctx.target);
         self.downgrade.downgrade(ir, ctx)
     }
+    /// Strips the correlation stamp from an inbound postback payload and emits the matching
+    /// interaction event.
+    pub fn record_interaction(&self, platform: &str, data: &mut Value) -> InteractionOutcome {
+        let Some(correlation) = take_correlation(data) else {
+            return InteractionOutcome::Untracked;
+        };
+        let now = telemetry::now_ms();
+        let telemetry = CardTelemetry::new(self.telemetry.as_ref());
+        let age_ms = correlation.age_ms(now);
+        if let Some(ttl) = self.card_ttl
+            && u128::from(age_ms) > ttl.as_millis()
+        {
+            telemetry.expired(platform, &correlation.card_id, age_ms);
+            return InteractionOutcome::Expired(correlation);
+        }
+        telemetry.interaction(platform, &correlation, now);
+        InteractionOutcome::Recorded(correlation)
+    }
+
+    fn record_render_failure(&self, platform: &str, card_id: Option<&str>) {
+        CardTelemetry::new(self.telemetry.as_ref()).render_failed(
+            platform,
+            card_id,
+            "no renderer registered for platform",
+        );
+    }
+
     fn record_render_event(
         &self,
         platform: &str,
@@ -312,18 +512,22 @@ impl MessageCardEngine {
     fn normalize_ir(&self, card: &MessageCard) -> Result<MessageCardIr> {
         #[cfg(feature = "adaptive-cards")]
         if let Some(ac) = &card.adaptive {
-            validate_ac_json(ac)?;
-            let mut ir = normalizer::ac_to_ir(ac)?;
-            ir.auto_tier();
-            ir.meta.source = Some("adaptive".into());
-            ir.meta.adaptive_payload = Some(ac.clone());
-            return Ok(ir);
+            return self.normalize_adaptive(ac);
         }
 
         let mut ir = MessageCardIr::from_plain(card);
         ir.meta.source = Some("plain".into());
         Ok(ir)
     }
+
+    fn normalize_adaptive(&self, ac: &Value) -> Result<MessageCardIr> {
+        validate_ac_json(ac)?;
+        let mut ir = normalizer::ac_to_ir(ac)?;
+        ir.auto_tier();
+        ir.meta.source = Some("adaptive".into());
+        ir.meta.adaptive_payload = Some(ac.clone());
+        Ok(ir)
+    }
 }
 
 #[cfg(test)]
@@ -435,4 +639,41 @@ mod tests {
             Some("acme")
         );
     }
+
+    #[test]
+    fn flat_cards_render_through_the_canonical_model() {
+        let engine = MessageCardEngine::bootstrap();
+        let card = MessageCard {
+            footer: Some("Sent by bot".into()),
+            images: vec![ImageRef {
+                url: "https://example.com/a.png".into(),
+                alt: Some("chart".into()),
+            }],
+            actions: vec![
+                Action::OpenUrl {
+                    title: "Open".into(),
+                    url: "https://example.com".into(),
+                },
+                Action::PostBack {
+                    title: "Ack".into(),
+                    data: json!({"ok": true}),
+                },
+            ],
+            ..base_card()
+        };
+
+        let canonical = crate::types::MessageCard::from(card.clone());
+        let wire = serde_json::to_value(&canonical).expect("serialize");
+        let restored: crate::types::MessageCard = serde_json::from_value(wire).expect("restore");
+        assert_eq!(restored, canonical);
+
+        assert_eq!(
+            engine.render_spec(&card).expect("flat spec"),
+            engine.render_card_spec(&canonical).expect("canonical spec")
+        );
+        let ir = engine.normalize(&card).expect("ir");
+        assert_eq!(ir.head.text.as_deref(), Some("Hello"));
+        assert_eq!(ir.elements.len(), 2);
+        assert_eq!(ir.actions.len(), 2);
+    }
 }
@@ -1,22 +1,47 @@
 use anyhow::{Result, anyhow};
 use greentic_types::TenantCtx;
 
-use crate::messaging_card::types::{MessageCard, MessageCardKind};
+use crate::messaging_card::types::{MessageCard, MessageCardKind, OauthCard};
 use crate::oauth::{OauthClient, OauthRelayContext, StartLink, StartTransport, make_start_request};
 
-pub async fn ensure_oauth_start_url<T: StartTransport>(
-    card: &mut MessageCard,
+/// Card models that can carry an OAuth block.
+pub trait OauthCardHolder {
+    fn kind(&self) -> &MessageCardKind;
+    fn oauth_mut(&mut self) -> Option<&mut OauthCard>;
+}
+
+impl OauthCardHolder for MessageCard {
+    fn kind(&self) -> &MessageCardKind {
+        &self.kind
+    }
+
+    fn oauth_mut(&mut self) -> Option<&mut OauthCard> {
+        self.oauth.as_mut()
+    }
+}
+
+impl OauthCardHolder for crate::types::MessageCard {
+    fn kind(&self) -> &MessageCardKind {
+        &self.kind
+    }
+
+    fn oauth_mut(&mut self) -> Option<&mut OauthCard> {
+        self.oauth.as_mut()
+    }
+}
+
+pub async fn ensure_oauth_start_url<C: OauthCardHolder, T: StartTransport>(
+    card: &mut C,
     ctx: &TenantCtx,
     client: &OauthClient<T>,
     relay: Option<OauthRelayContext>,
 ) -> Result<()> {
-    if !matches!(card.kind, MessageCardKind::Oauth) {
+    if !matches!(card.kind(), MessageCardKind::Oauth) {
         return Ok(());
     }
 
     let oauth = card
-        .oauth
-        .as_mut()
+        .oauth_mut()
         .ok_or_else(|| anyhow!("oauth card missing oauth block"))?;
 
     if oauth.start_url.is_some() {
@@ -0,0 +1,198 @@
+//! Server-side storage for postback payloads that do not fit a platform's callback field.
+//!
+//! Telegram limits `callback_data` to 64 bytes, so renderers swap large payloads for a short
+//! opaque token and ingress resolves the token back into the original payload.
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::{Value, json};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Prefix that marks callback data as a token rather than an inline payload.
+pub const POSTBACK_TOKEN_PREFIX: &str = "pb:";
+
+/// Maps short opaque tokens to full postback payloads.
+pub trait PostbackTokenStore: Send + Sync {
+    /// Stores `payload` and returns a token that resolves to it until `ttl` elapses.
+    fn issue(&self, payload: &Value, ttl: Duration) -> String;
+
+    /// Returns the payload for `token`, or `None` when unknown or expired.
+    fn resolve(&self, token: &str) -> Option<Value>;
+}
+
+/// In-process token store. Intended for single-replica deployments and tests.
+#[derive(Default)]
+pub struct InMemoryPostbackTokenStore {
+    entries: Mutex<HashMap<String, (Value, Instant)>>,
+}
+
+impl InMemoryPostbackTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .expect("postback token store poisoned")
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl PostbackTokenStore for InMemoryPostbackTokenStore {
+    fn issue(&self, payload: &Value, ttl: Duration) -> String {
+        let now = Instant::now();
+        let token = format!("{POSTBACK_TOKEN_PREFIX}{}", Uuid::new_v4().simple());
+        let mut entries = self.entries.lock().expect("postback token store poisoned");
+        entries.retain(|_, (_, expires)| *expires > now);
+        entries.insert(token.clone(), (payload.clone(), now + ttl));
+        token
+    }
+
+    fn resolve(&self, token: &str) -> Option<Value> {
+        let entries = self.entries.lock().expect("postback token store poisoned");
+        let (payload, expires) = entries.get(token)?;
+        if *expires <= Instant::now() {
+            return None;
+        }
+        Some(payload.clone())
+    }
+}
+
+/// Token store that keeps one file per token in a directory. Egress issues tokens while
+/// rendering and the gateway resolves them on ingress, usually in another process; pointing
+/// both at the same directory (a shared volume across replicas) lets the token round-trip.
+#[derive(Debug, Clone)]
+pub struct DirPostbackTokenStore {
+    dir: PathBuf,
+}
+
+impl DirPostbackTokenStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Path for `token`, or `None` when it is not a token this store could have issued.
+    fn path_for(&self, token: &str) -> Option<PathBuf> {
+        let id = token.strip_prefix(POSTBACK_TOKEN_PREFIX)?;
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some(self.dir.join(format!("{id}.json")))
+    }
+}
+
+fn unix_ms(at: SystemTime) -> u64 {
+    at.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+impl PostbackTokenStore for DirPostbackTokenStore {
+    fn issue(&self, payload: &Value, ttl: Duration) -> String {
+        let token = format!("{POSTBACK_TOKEN_PREFIX}{}", Uuid::new_v4().simple());
+        let entry = json!({
+            "payload": payload,
+            "expires_at_ms": unix_ms(SystemTime::now() + ttl),
+        });
+        let written = fs::create_dir_all(&self.dir).and_then(|_| {
+            let path = self.path_for(&token).expect("issued tokens are valid");
+            fs::write(path, entry.to_string())
+        });
+        if let Err(err) = written {
+            warn!(
+                target = "gsm.mcard.postback",
+                dir = %self.dir.display(),
+                error = %err,
+                "failed to persist postback token"
+            );
+        }
+        token
+    }
+
+    fn resolve(&self, token: &str) -> Option<Value> {
+        let path = self.path_for(token)?;
+        let raw = fs::read_to_string(&path).ok()?;
+        let mut entry: Value = serde_json::from_str(&raw).ok()?;
+        let expires_at = entry.get("expires_at_ms").and_then(Value::as_u64)?;
+        if expires_at <= unix_ms(SystemTime::now()) {
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+        entry.get_mut("payload").map(Value::take)
+    }
+}
+
+/// Turns inbound callback data back into the postback payload the card was rendered with.
+///
+/// Tokens are looked up in `store`; inline data is parsed as JSON and falls back to a string.
+/// Returns `None` when a token is unknown or has expired.
+pub fn resolve_callback_data(store: &dyn PostbackTokenStore, callback_data: &str) -> Option<Value> {
+    if callback_data.starts_with(POSTBACK_TOKEN_PREFIX) {
+        return store.resolve(callback_data);
+    }
+    Some(
+        serde_json::from_str(callback_data)
+            .unwrap_or_else(|_| Value::String(callback_data.to_string())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tokens_resolve_until_expiry() {
+        let store = InMemoryPostbackTokenStore::new();
+        let payload = json!({"action": "approve", "request": "x".repeat(100)});
+        let token = store.issue(&payload, Duration::from_millis(20));
+        assert!(token.len() <= 64);
+        assert_eq!(store.resolve(&token), Some(payload));
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(store.resolve(&token), None);
+    }
+
+    #[test]
+    fn expired_tokens_are_evicted_on_issue() {
+        let store = InMemoryPostbackTokenStore::new();
+        store.issue(&json!(1), Duration::from_millis(1));
+        std::thread::sleep(Duration::from_millis(5));
+        store.issue(&json!(2), Duration::from_secs(60));
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn dir_store_tokens_resolve_across_instances() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let payload = json!({"action": "approve", "request": "x".repeat(100)});
+        let token = DirPostbackTokenStore::new(dir.path()).issue(&payload, Duration::from_secs(60));
+        assert!(token.len() <= 64);
+
+        let other = DirPostbackTokenStore::new(dir.path());
+        assert_eq!(other.resolve(&token), Some(payload));
+        assert_eq!(other.resolve("pb:../../etc/passwd"), None);
+
+        let expired = other.issue(&json!(1), Duration::ZERO);
+        assert_eq!(other.resolve(&expired), None);
+    }
+
+    #[test]
+    fn inline_callback_data_is_parsed() {
+        let store = InMemoryPostbackTokenStore::new();
+        assert_eq!(
+            resolve_callback_data(&store, "{\"ok\":true}"),
+            Some(json!({"ok": true}))
+        );
+        assert_eq!(resolve_callback_data(&store, "yes"), Some(json!("yes")));
+        assert_eq!(resolve_callback_data(&store, "pb:unknown"), None);
+    }
+}
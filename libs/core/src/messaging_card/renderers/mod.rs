@@ -14,22 +14,29 @@ use sha2::Sha256;
 use tracing::warn;
 use urlencoding::encode;
 
+use crate::messaging_card::adaptive::{AdaptiveCardHost, validate_for_host};
 use crate::messaging_card::ir::{
     AppLink, AppLinkJwt, Element, InputChoice, InputKind, IrAction, MessageCardIr, Meta,
 };
+use crate::messaging_card::markdown::{self, MarkdownDialect};
 use crate::messaging_card::spec::AuthRenderSpec;
 use crate::messaging_card::tier::Tier;
+use crate::render_planner::url_matches;
 
 mod slack;
 mod teams;
 mod telegram;
+#[cfg(feature = "component-host")]
+mod wasm;
 mod webchat;
 mod webex;
 mod whatsapp;
 
-pub use slack::SlackRenderer;
-pub use teams::TeamsRenderer;
+pub use slack::{DIALOG_ACTION_PREFIX, SlackRenderer, SlackViewTarget};
+pub use teams::{TaskModuleSize, TeamsRenderer, task_module_message};
 pub use telegram::TelegramRenderer;
+#[cfg(feature = "component-host")]
+pub use wasm::{WasmRenderer, register_pack_renderers};
 pub use webchat::WebChatRenderer;
 pub use webex::WebexRenderer;
 pub use whatsapp::WhatsAppRenderer;
@@ -43,36 +50,78 @@ const WHATSAPP_TEXT_LIMIT: usize = 4000;
 const MAX_STATE_BYTES: usize = 2048;
 
 pub trait PlatformRenderer: Send + Sync {
-    fn platform(&self) -> &'static str;
+    fn platform(&self) -> &str;
     fn target_tier(&self) -> Tier;
     fn render(&self, ir: &MessageCardIr) -> RenderOutput;
 
+    /// Like [`render`](Self::render), but reports a renderer that could not produce a payload
+    /// so the engine can record the failure and fall back. Built-in renderers always succeed.
+    fn try_render(&self, ir: &MessageCardIr) -> anyhow::Result<RenderOutput> {
+        Ok(self.render(ir))
+    }
+
     fn render_auth(&self, _auth: &AuthRenderSpec) -> Option<RenderOutput> {
         None
     }
+
+    /// Rewrites the first payload of `rendered` into an in-place edit of the message
+    /// `message_id`. Returns `None` when the platform cannot edit this output, in which case
+    /// the card is sent as a new message.
+    fn render_update(&self, _message_id: &str, _rendered: &RenderOutput) -> Option<Value> {
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct RenderOutput {
     pub payload: Value,
+    /// Follow-up payloads sent after `payload`, in order, when content was split across
+    /// several messages to stay within platform limits.
+    pub continuations: Vec<Value>,
     pub used_modal: bool,
     pub warnings: Vec<String>,
+    /// Set when content exceeded a platform limit, whether it was truncated or split.
     pub limit_exceeded: bool,
     pub sanitized_count: usize,
     pub url_blocked_count: usize,
+    /// Provider message id that `payload` edits in place. Continuations are always sent as
+    /// new messages.
+    pub edit_message_id: Option<String>,
 }
 
 impl RenderOutput {
     pub fn new(payload: Value) -> Self {
         Self {
             payload,
+            continuations: Vec::new(),
             used_modal: false,
             warnings: Vec::new(),
             limit_exceeded: false,
             sanitized_count: 0,
             url_blocked_count: 0,
+            edit_message_id: None,
         }
     }
+
+    /// Builds an output from payloads in send order. Panics if `payloads` is empty.
+    pub fn from_payloads(payloads: Vec<Value>) -> Self {
+        let mut payloads = payloads.into_iter();
+        let mut output = Self::new(payloads.next().expect("at least one payload"));
+        output.continuations = payloads.collect();
+        output
+    }
+
+    /// All payloads in the order egress must send them.
+    pub fn payloads(&self) -> impl Iterator<Item = &Value> {
+        std::iter::once(&self.payload).chain(&self.continuations)
+    }
+
+    pub fn into_payloads(self) -> Vec<Value> {
+        let mut payloads = Vec::with_capacity(1 + self.continuations.len());
+        payloads.push(self.payload);
+        payloads.extend(self.continuations);
+        payloads
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -85,6 +134,8 @@ pub struct RenderMetrics {
 #[derive(Default)]
 pub struct RendererRegistry {
     renderers: BTreeMap<String, Arc<dyn PlatformRenderer>>,
+    /// Renderers displaced by [`RendererRegistry::replace`], used when the replacement fails.
+    fallbacks: BTreeMap<String, Arc<dyn PlatformRenderer>>,
 }
 
 impl RendererRegistry {
@@ -96,10 +147,30 @@ impl RendererRegistry {
             .insert(renderer.platform().to_string(), Arc::new(renderer));
     }
 
+    /// Registers `renderer` in place of the platform's current one, which is kept as the
+    /// fallback for renders the new renderer fails.
+    pub fn replace<R>(&mut self, renderer: R)
+    where
+        R: PlatformRenderer + 'static,
+    {
+        let platform = renderer.platform().to_string();
+        if let Some(previous) = self.renderers.insert(platform.clone(), Arc::new(renderer)) {
+            self.fallbacks.entry(platform).or_insert(previous);
+        }
+    }
+
     pub fn get(&self, platform: &str) -> Option<Arc<dyn PlatformRenderer>> {
         self.renderers.get(platform).cloned()
     }
 
+    pub fn fallback(&self, platform: &str) -> Option<Arc<dyn PlatformRenderer>> {
+        self.fallbacks.get(platform).cloned()
+    }
+
+    pub fn contains(&self, platform: &str) -> bool {
+        self.renderers.contains_key(platform)
+    }
+
     pub fn render(&self, platform: &str, ir: &MessageCardIr) -> Option<RenderOutput> {
         self.get(platform).map(|renderer| renderer.render(ir))
     }
@@ -178,7 +249,13 @@ pub fn adaptive_from_ir(
                 let sanitized = sanitize_text_for_tier(text, ir.tier, metrics);
                 body.push(json!({
                     "type": "TextBlock",
-                    "text": sanitized,
+                    "text": format_text(
+                        &sanitized,
+                        *markdown,
+                        MarkdownDialect::AdaptiveCard,
+                        metrics,
+                        warnings,
+                    ),
                     "wrap": true,
                     "isSubtle": !markdown,
                 }));
@@ -353,6 +430,13 @@ fn render_actions(
                     "data": data,
                 }));
             }
+            IrAction::Dialog { title, data } => {
+                rendered.push(json!({
+                    "type": "Action.Submit",
+                    "title": title,
+                    "data": teams::task_fetch_data(data),
+                }));
+            }
         }
     }
     rendered
@@ -506,6 +590,61 @@ fn sanitize_text_for_tier(text: &str, tier: Tier, metrics: &mut RenderMetrics) -
     sanitized
 }
 
+/// Converts Markdown element text into the platform dialect; plain text passes through. Links
+/// outside the URL allow list keep only their text and are counted as blocked.
+fn format_text(
+    text: &str,
+    markdown: bool,
+    dialect: MarkdownDialect,
+    metrics: &mut RenderMetrics,
+    warnings: &mut Vec<String>,
+) -> String {
+    if !markdown {
+        return text.to_string();
+    }
+    markdown::convert_with_links(text, dialect, |url| link_allowed(url, metrics, warnings))
+}
+
+fn link_allowed(url: &str, metrics: &mut RenderMetrics, warnings: &mut Vec<String>) -> bool {
+    if is_url_allowed(url) {
+        return true;
+    }
+    metrics.url_blocked_count += 1;
+    warnings.push("url_blocked".into());
+    false
+}
+
+/// Fits an Adaptive Card to the host that displays it: the version is lowered to what the host
+/// renders, unsupported features are swapped for their fallbacks and the result is checked
+/// against that version's schema. Changed elements or actions add an `adaptive.host_downgraded`
+/// warning; a card the host cannot render is returned unchanged with `adaptive.host_invalid`.
+fn fit_to_host(card: Value, host: AdaptiveCardHost, warnings: &mut Vec<String>) -> Value {
+    match validate_for_host(&card, host) {
+        Ok(fitted) => {
+            if fitted.features_changed() {
+                warn!(
+                    target = "gsm.mcard.adaptive",
+                    host = host.as_str(),
+                    changes = ?fitted.changes,
+                    "adaptive card downgraded for host"
+                );
+                warnings.push("adaptive.host_downgraded".into());
+            }
+            fitted.content
+        }
+        Err(err) => {
+            warn!(
+                target = "gsm.mcard.adaptive",
+                host = host.as_str(),
+                error = %err,
+                "adaptive card is not valid for host"
+            );
+            warnings.push("adaptive.host_invalid".into());
+            card
+        }
+    }
+}
+
 fn enforce_text_limit(
     text: &str,
     limit: usize,
@@ -539,7 +678,7 @@ fn resolve_url_with_policy(
 fn is_url_allowed(url: &str) -> bool {
     let guard = URL_ALLOW_LIST.read().expect("url allow list poisoned");
     match &*guard {
-        Some(list) => list.iter().any(|prefix| url.starts_with(prefix)),
+        Some(list) => list.iter().any(|prefix| url_matches(url, prefix)),
         None => true,
     }
 }
@@ -1,16 +1,33 @@
 use serde_json::{Value, json};
 
 use crate::messaging_card::ir::{Element, InputChoice, InputKind, IrAction, MessageCardIr};
+use crate::messaging_card::markdown::MarkdownDialect;
+use crate::messaging_card::split::{SplitSyntax, split_text};
 use crate::messaging_card::tier::Tier;
 
 use super::{
     PlatformRenderer, RenderMetrics, RenderOutput, SLACK_TEXT_LIMIT, enforce_text_limit,
-    resolve_url_with_policy, sanitize_text_for_tier,
+    format_text, resolve_url_with_policy, sanitize_text_for_tier,
 };
 
 const HEADER_LIMIT: usize = 150;
 const MODAL_TITLE_LIMIT: usize = 24;
 const BUTTON_LIMIT: usize = 5;
+/// Slack rejects messages with more than 50 blocks.
+const MESSAGE_BLOCK_LIMIT: usize = 50;
+const PRIVATE_METADATA_LIMIT: usize = 3000;
+const DEFAULT_CALLBACK_ID: &str = "gsm_card_modal";
+/// `action_id` prefix of buttons rendered from `IrAction::Dialog`.
+pub const DIALOG_ACTION_PREFIX: &str = "dialog_";
+
+/// Slack surface a view payload is published to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlackViewTarget {
+    /// `views.open` modal, opened with the `trigger_id` of the interaction that requested it.
+    Modal { trigger_id: String },
+    /// `views.publish` App Home tab for a user.
+    Home { user_id: String },
+}
 
 #[derive(Default)]
 pub struct SlackRenderer;
@@ -32,12 +49,24 @@ impl PlatformRenderer for SlackRenderer {
             .iter()
             .any(|el| matches!(el, Element::Input { .. }));
 
-        let payload = if has_inputs {
-            render_modal(ir, &mut warnings, &mut metrics)
+        let payloads = if has_inputs {
+            vec![render_modal(ir, &mut warnings, &mut metrics)]
         } else {
-            json!({ "blocks": render_blocks(ir, &mut warnings, false, &mut metrics) })
+            let blocks = render_blocks(ir, &mut warnings, false, &mut metrics);
+            if blocks.len() > MESSAGE_BLOCK_LIMIT {
+                metrics.limit_exceeded = true;
+                warnings.push("slack.message_split".into());
+            }
+            if blocks.is_empty() {
+                vec![json!({ "blocks": blocks })]
+            } else {
+                blocks
+                    .chunks(MESSAGE_BLOCK_LIMIT)
+                    .map(|chunk| json!({ "blocks": chunk }))
+                    .collect()
+            }
         };
-        let mut output = RenderOutput::new(payload);
+        let mut output = RenderOutput::from_payloads(payloads);
         output.used_modal = has_inputs;
         output.warnings = warnings;
         output.limit_exceeded = metrics.limit_exceeded;
@@ -45,12 +74,101 @@ impl PlatformRenderer for SlackRenderer {
         output.url_blocked_count = metrics.url_blocked_count;
         output
     }
+
+    fn render_update(&self, message_id: &str, rendered: &RenderOutput) -> Option<Value> {
+        // Cards with inputs open a modal, so there is no posted message to edit.
+        if rendered.used_modal {
+            return None;
+        }
+        let mut payload = rendered.payload.clone();
+        payload["method"] = json!("chat.update");
+        payload["ts"] = json!(message_id);
+        Some(payload)
+    }
+}
+
+impl SlackRenderer {
+    /// Renders the card as a `views.open` or `views.publish` request. `state` is serialized into
+    /// `private_metadata` so it comes back with the `view_submission` payload.
+    pub fn render_view(
+        &self,
+        ir: &MessageCardIr,
+        target: &SlackViewTarget,
+        callback_id: &str,
+        state: Option<&Value>,
+    ) -> RenderOutput {
+        let mut warnings = Vec::new();
+        let mut metrics = RenderMetrics::default();
+        let mut view = match target {
+            SlackViewTarget::Modal { .. } => {
+                render_modal_view(ir, callback_id, &mut warnings, &mut metrics)
+            }
+            SlackViewTarget::Home { .. } => json!({
+                "type": "home",
+                "callback_id": callback_id,
+                "blocks": render_blocks(ir, &mut warnings, false, &mut metrics),
+            }),
+        };
+        if let Some(metadata) = private_metadata(state, &mut warnings, &mut metrics) {
+            view["private_metadata"] = json!(metadata);
+        }
+
+        let payload = match target {
+            SlackViewTarget::Modal { trigger_id } => json!({
+                "method": "views.open",
+                "trigger_id": trigger_id,
+                "view": view,
+            }),
+            SlackViewTarget::Home { user_id } => json!({
+                "method": "views.publish",
+                "user_id": user_id,
+                "view": view,
+            }),
+        };
+        let mut output = RenderOutput::new(payload);
+        output.used_modal = matches!(target, SlackViewTarget::Modal { .. });
+        output.warnings = warnings;
+        output.limit_exceeded = metrics.limit_exceeded;
+        output.sanitized_count = metrics.sanitized_count;
+        output.url_blocked_count = metrics.url_blocked_count;
+        output
+    }
+}
+
+fn private_metadata(
+    state: Option<&Value>,
+    warnings: &mut Vec<String>,
+    metrics: &mut RenderMetrics,
+) -> Option<String> {
+    let state = state.filter(|value| !value.is_null())?;
+    let serialized = match serde_json::to_string(state) {
+        Ok(serialized) => serialized,
+        Err(_) => {
+            warnings.push("slack.private_metadata_unserializable".into());
+            return None;
+        }
+    };
+    if serialized.len() > PRIVATE_METADATA_LIMIT {
+        metrics.limit_exceeded = true;
+        warnings.push("slack.private_metadata_dropped".into());
+        return None;
+    }
+    Some(serialized)
 }
 
 fn render_modal(
     ir: &MessageCardIr,
     warnings: &mut Vec<String>,
     metrics: &mut RenderMetrics,
+) -> Value {
+    render_modal_view(ir, DEFAULT_CALLBACK_ID, warnings, metrics)
+}
+
+fn render_modal_view(
+    ir: &MessageCardIr,
+    callback_id: &str,
+    warnings: &mut Vec<String>,
+    metrics: &mut RenderMetrics,
 ) -> Value {
     let title_raw = ir
         .head
@@ -66,7 +184,7 @@ fn render_modal(
         "title": plain_text(&title),
         "submit": plain_text("Submit"),
         "close": plain_text("Close"),
-        "callback_id": "gsm_card_modal",
+        "callback_id": callback_id,
         "blocks": render_blocks(ir, warnings, true, metrics),
     })
 }
@@ -101,14 +219,14 @@ fn render_blocks(
             Element::Text { text, markdown } => {
                 saw_text_element = true;
                 let sanitized = sanitize_text_for_tier(text, ir.tier, metrics);
-                let limited = enforce_text_limit(
+                let formatted = format_text(
                     &sanitized,
-                    SLACK_TEXT_LIMIT,
-                    "slack.text_truncated",
+                    *markdown,
+                    MarkdownDialect::SlackMrkdwn,
                     metrics,
                     warnings,
                 );
-                blocks.push(section_block(&limited, *markdown));
+                blocks.extend(text_sections(&formatted, *markdown, warnings, metrics));
             }
             Element::Image { url, alt } => {
                 let alt_text = alt
@@ -181,14 +299,7 @@ fn render_blocks(
 
     if !saw_text_element && let Some(text) = &ir.head.text {
         let sanitized = sanitize_text_for_tier(text, ir.tier, metrics);
-        let limited = enforce_text_limit(
-            &sanitized,
-            SLACK_TEXT_LIMIT,
-            "slack.text_truncated",
-            metrics,
-            warnings,
-        );
-        blocks.push(section_block(&limited, true));
+        blocks.extend(text_sections(&sanitized, true, warnings, metrics));
     }
 
     if let Some(footer) = &ir.head.footer {
@@ -299,18 +410,27 @@ fn actions_block(
                     }));
                 }
             }
-            IrAction::Postback { title, data } => match serde_json::to_string(data) {
-                Ok(value) => {
-                    let button_text = sanitize_text_for_tier(title, ir.tier, metrics);
-                    elements.push(json!({
-                        "type": "button",
-                        "text": plain_text(&button_text),
-                        "value": value,
-                        "action_id": format!("postback_{}", elements.len()),
-                    }));
+            IrAction::Postback { title, data } | IrAction::Dialog { title, data } => {
+                match serde_json::to_string(data) {
+                    Ok(value) => {
+                        // Dialog buttons carry a distinct action id so the app knows to answer
+                        // the interaction with `views.open`.
+                        let prefix = if matches!(action, IrAction::Dialog { .. }) {
+                            DIALOG_ACTION_PREFIX
+                        } else {
+                            "postback_"
+                        };
+                        let button_text = sanitize_text_for_tier(title, ir.tier, metrics);
+                        elements.push(json!({
+                            "type": "button",
+                            "text": plain_text(&button_text),
+                            "value": value,
+                            "action_id": format!("{prefix}{}", elements.len()),
+                        }));
+                    }
+                    Err(_) => warnings.push("slack.postback_unserializable".into()),
                 }
-                Err(_) => warnings.push("slack.postback_unserializable".into()),
-            },
+            }
         }
     }
 
@@ -324,6 +444,29 @@ fn actions_block(
     }
 }
 
+/// Section blocks for `text`, split across several sections when it exceeds the section limit.
+fn text_sections(
+    text: &str,
+    markdown: bool,
+    warnings: &mut Vec<String>,
+    metrics: &mut RenderMetrics,
+) -> Vec<Value> {
+    let syntax = if markdown {
+        SplitSyntax::Markdown
+    } else {
+        SplitSyntax::Plain
+    };
+    let chunks = split_text(text, SLACK_TEXT_LIMIT, syntax);
+    if chunks.len() > 1 {
+        metrics.limit_exceeded = true;
+        warnings.push("slack.text_split".into());
+    }
+    chunks
+        .iter()
+        .map(|chunk| section_block(chunk, markdown))
+        .collect()
+}
+
 fn section_block(text: &str, markdown: bool) -> Value {
     if markdown {
         json!({
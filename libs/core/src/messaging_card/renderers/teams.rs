@@ -2,8 +2,34 @@ use crate::messaging_card::ir::MessageCardIr;
 use crate::messaging_card::spec::AuthRenderSpec;
 use crate::messaging_card::tier::Tier;
 
-use super::{PlatformRenderer, RenderMetrics, RenderOutput, adaptive_from_ir};
-use serde_json::json;
+use crate::messaging_card::adaptive::AdaptiveCardHost;
+
+use super::{PlatformRenderer, RenderMetrics, RenderOutput, adaptive_from_ir, fit_to_host};
+use serde_json::{Map, Value, json};
+
+const ADAPTIVE_CONTENT_TYPE: &str = "application/vnd.microsoft.card.adaptive";
+const THUMBNAIL_CONTENT_TYPE: &str = "application/vnd.microsoft.card.thumbnail";
+
+/// Width or height of a Teams task module (dialog).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TaskModuleSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+    Pixels(u32),
+}
+
+impl TaskModuleSize {
+    fn to_value(self) -> Value {
+        match self {
+            TaskModuleSize::Small => json!("small"),
+            TaskModuleSize::Medium => json!("medium"),
+            TaskModuleSize::Large => json!("large"),
+            TaskModuleSize::Pixels(px) => json!(px),
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct TeamsRenderer;
@@ -20,13 +46,8 @@ impl PlatformRenderer for TeamsRenderer {
     fn render(&self, ir: &MessageCardIr) -> RenderOutput {
         let mut warnings = Vec::new();
         let mut metrics = RenderMetrics::default();
-        let payload = adaptive_from_ir(ir, &mut metrics, &mut warnings);
-        let mut output = RenderOutput::new(payload);
-        output.warnings = warnings;
-        output.limit_exceeded = metrics.limit_exceeded;
-        output.sanitized_count = metrics.sanitized_count;
-        output.url_blocked_count = metrics.url_blocked_count;
-        output
+        let payload = teams_card(ir, &mut metrics, &mut warnings);
+        finish(payload, warnings, metrics)
     }
 
     fn render_auth(&self, auth: &AuthRenderSpec) -> Option<RenderOutput> {
@@ -55,4 +76,210 @@ impl PlatformRenderer for TeamsRenderer {
         }
         Some(RenderOutput::new(payload))
     }
+
+    fn render_update(&self, message_id: &str, rendered: &RenderOutput) -> Option<Value> {
+        Some(json!({
+            "method": "updateActivity",
+            "activity_id": message_id,
+            "activity": {
+                "type": "message",
+                "id": message_id,
+                "attachments": [{
+                    "contentType": ADAPTIVE_CONTENT_TYPE,
+                    "content": rendered.payload,
+                }],
+            },
+        }))
+    }
+}
+
+impl TeamsRenderer {
+    /// Renders the card as the `continue` response to a `task/fetch` invoke, which Teams shows
+    /// as a dialog.
+    pub fn render_task_module(
+        &self,
+        ir: &MessageCardIr,
+        width: TaskModuleSize,
+        height: TaskModuleSize,
+    ) -> RenderOutput {
+        let mut warnings = Vec::new();
+        let mut metrics = RenderMetrics::default();
+        let card = teams_card(ir, &mut metrics, &mut warnings);
+        let mut value = json!({
+            "width": width.to_value(),
+            "height": height.to_value(),
+            "card": {
+                "contentType": ADAPTIVE_CONTENT_TYPE,
+                "content": card,
+            }
+        });
+        if let Some(title) = &ir.head.title {
+            value["title"] = json!(title);
+        }
+        let payload = json!({
+            "task": {
+                "type": "continue",
+                "value": value,
+            }
+        });
+        let mut output = finish(payload, warnings, metrics);
+        output.used_modal = true;
+        output
+    }
+
+    /// Renders `composeExtension/query` results, one Adaptive Card attachment per card with a
+    /// thumbnail preview for the result list.
+    pub fn render_message_extension(&self, cards: &[MessageCardIr]) -> RenderOutput {
+        let mut warnings = Vec::new();
+        let mut metrics = RenderMetrics::default();
+        let attachments: Vec<Value> = cards
+            .iter()
+            .map(|ir| {
+                let content = teams_card(ir, &mut metrics, &mut warnings);
+                let mut preview = Map::new();
+                if let Some(title) = &ir.head.title {
+                    preview.insert("title".into(), json!(title));
+                }
+                if let Some(text) = &ir.head.text {
+                    preview.insert("text".into(), json!(text));
+                }
+                json!({
+                    "contentType": ADAPTIVE_CONTENT_TYPE,
+                    "content": content,
+                    "preview": {
+                        "contentType": THUMBNAIL_CONTENT_TYPE,
+                        "content": preview,
+                    }
+                })
+            })
+            .collect();
+        let payload = json!({
+            "composeExtension": {
+                "type": "result",
+                "attachmentLayout": "list",
+                "attachments": attachments,
+            }
+        });
+        finish(payload, warnings, metrics)
+    }
+}
+
+fn teams_card(
+    ir: &MessageCardIr,
+    metrics: &mut RenderMetrics,
+    warnings: &mut Vec<String>,
+) -> Value {
+    let card = adaptive_from_ir(ir, metrics, warnings);
+    fit_to_host(card, AdaptiveCardHost::TeamsDesktop, warnings)
+}
+
+/// Response to `task/submit` that closes the dialog and shows `message` to the user.
+pub fn task_module_message(message: &str) -> Value {
+    json!({
+        "task": {
+            "type": "message",
+            "value": message,
+        }
+    })
+}
+
+/// Marks `Action.Submit` data so Teams issues a `task/fetch` invoke instead of a message.
+/// Non-object data is nested under `data` because Teams requires an object; the marker then
+/// carries `wrapped: true` so ingress can restore the original value.
+pub(crate) fn task_fetch_data(data: &Value) -> Value {
+    let (mut obj, marker) = match data {
+        Value::Object(map) => (map.clone(), json!({ "type": "task/fetch" })),
+        Value::Null => (Map::new(), json!({ "type": "task/fetch" })),
+        other => {
+            let mut map = Map::new();
+            map.insert("data".into(), other.clone());
+            (map, json!({ "type": "task/fetch", "wrapped": true }))
+        }
+    };
+    obj.insert("msteams".into(), marker);
+    Value::Object(obj)
+}
+
+fn finish(payload: Value, warnings: Vec<String>, metrics: RenderMetrics) -> RenderOutput {
+    let mut output = RenderOutput::new(payload);
+    output.warnings = warnings;
+    output.limit_exceeded = metrics.limit_exceeded;
+    output.sanitized_count = metrics.sanitized_count;
+    output.url_blocked_count = metrics.url_blocked_count;
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging_card::ir::MessageCardIrBuilder;
+
+    #[test]
+    fn dialog_action_renders_task_fetch_submit() {
+        let ir = MessageCardIrBuilder::default()
+            .tier(Tier::Advanced)
+            .title("Expenses")
+            .dialog("Open form", json!({"form": "expense"}))
+            .build();
+        let rendered = TeamsRenderer.render(&ir);
+        let action = &rendered.payload["actions"][0];
+        assert_eq!(action["type"], "Action.Submit");
+        assert_eq!(action["data"]["msteams"]["type"], "task/fetch");
+        assert_eq!(action["data"]["form"], "expense");
+    }
+
+    #[test]
+    fn task_module_wraps_card_in_continue_response() {
+        let ir = MessageCardIrBuilder::default()
+            .title("Expense form")
+            .primary_text("Fill in the details", false)
+            .build();
+        let rendered = TeamsRenderer.render_task_module(
+            &ir,
+            TaskModuleSize::Large,
+            TaskModuleSize::Pixels(400),
+        );
+        let task = &rendered.payload["task"];
+        assert_eq!(task["type"], "continue");
+        assert_eq!(task["value"]["title"], "Expense form");
+        assert_eq!(task["value"]["width"], "large");
+        assert_eq!(task["value"]["height"], 400);
+        assert_eq!(task["value"]["card"]["contentType"], ADAPTIVE_CONTENT_TYPE);
+        assert_eq!(task["value"]["card"]["content"]["type"], "AdaptiveCard");
+        assert!(rendered.used_modal);
+    }
+
+    #[test]
+    fn message_extension_lists_cards_with_previews() {
+        let cards = vec![
+            MessageCardIrBuilder::default()
+                .title("Ticket 1")
+                .primary_text("Printer on fire", false)
+                .build(),
+            MessageCardIrBuilder::default().title("Ticket 2").build(),
+        ];
+        let rendered = TeamsRenderer.render_message_extension(&cards);
+        let result = &rendered.payload["composeExtension"];
+        assert_eq!(result["type"], "result");
+        let attachments = result["attachments"].as_array().expect("attachments");
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0]["preview"]["content"]["title"], "Ticket 1");
+        assert_eq!(
+            attachments[0]["preview"]["content"]["text"],
+            "Printer on fire"
+        );
+        assert!(attachments[1]["preview"]["content"].get("text").is_none());
+    }
+
+    #[test]
+    fn task_fetch_data_wraps_scalars() {
+        assert_eq!(
+            task_fetch_data(&json!("expense")),
+            json!({"data": "expense", "msteams": {"type": "task/fetch", "wrapped": true}})
+        );
+        assert_eq!(
+            task_fetch_data(&Value::Null),
+            json!({"msteams": {"type": "task/fetch"}})
+        );
+    }
 }
@@ -1,19 +1,54 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use serde_json::{Value, json};
 use tracing::warn;
 
-use crate::messaging_card::ir::{Element, InputKind, MessageCardIr};
+use crate::messaging_card::ir::{Element, InputKind, IrAction, KeyboardKind, MessageCardIr};
+use crate::messaging_card::markdown::MarkdownDialect;
+use crate::messaging_card::postback_tokens::PostbackTokenStore;
+use crate::messaging_card::split::{SplitSyntax, split_message};
 use crate::messaging_card::tier::Tier;
 
 use super::{
-    PlatformRenderer, RenderMetrics, RenderOutput, TELEGRAM_TEXT_LIMIT, enforce_text_limit,
+    PlatformRenderer, RenderMetrics, RenderOutput, TELEGRAM_TEXT_LIMIT, format_text,
     resolve_url_with_policy, sanitize_text_for_tier,
 };
 
 const MAX_BUTTONS: usize = 10;
 const MAX_PER_ROW: usize = 3;
+/// Telegram rejects `callback_data` longer than 64 bytes.
+const CALLBACK_DATA_LIMIT: usize = 64;
+const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone)]
+pub struct TelegramRenderer {
+    token_store: Option<Arc<dyn PostbackTokenStore>>,
+    token_ttl: Duration,
+}
+
+impl Default for TelegramRenderer {
+    fn default() -> Self {
+        Self {
+            token_store: None,
+            token_ttl: DEFAULT_TOKEN_TTL,
+        }
+    }
+}
 
-#[derive(Default)]
-pub struct TelegramRenderer;
+impl TelegramRenderer {
+    /// Postbacks larger than Telegram's `callback_data` limit are stored in `store` and
+    /// replaced by a token; without a store such buttons are dropped.
+    pub fn with_token_store(mut self, store: Arc<dyn PostbackTokenStore>) -> Self {
+        self.token_store = Some(store);
+        self
+    }
+
+    pub fn with_token_ttl(mut self, ttl: Duration) -> Self {
+        self.token_ttl = ttl;
+        self
+    }
+}
 
 impl PlatformRenderer for TelegramRenderer {
     fn platform(&self) -> &'static str {
@@ -36,28 +71,43 @@ impl PlatformRenderer for TelegramRenderer {
             }
         }
         if let Some(text) = &ir.head.text {
-            let escaped = sanitized_html(text, ir.tier, &mut metrics);
+            let markdown = ir.elements.iter().any(
+                |element| matches!(element, Element::Text { text: t, markdown: true } if t == text),
+            );
+            let escaped = sanitized_text_html(text, markdown, ir.tier, &mut metrics, &mut warnings);
             if !escaped.is_empty() {
                 lines.push(escaped);
             }
         }
 
         let mut primary_consumed = ir.head.text.is_none();
+        let mut photos: Vec<Value> = Vec::new();
 
         for element in &ir.elements {
             match element {
-                Element::Text { text, .. } => {
+                Element::Text { text, markdown } => {
                     if !primary_consumed && ir.head.text.as_deref() == Some(text.as_str()) {
                         primary_consumed = true;
                         continue;
                     }
                     primary_consumed = true;
-                    let escaped = sanitized_html(text, ir.tier, &mut metrics);
+                    let escaped =
+                        sanitized_text_html(text, *markdown, ir.tier, &mut metrics, &mut warnings);
                     if !escaped.is_empty() {
                         lines.push(escaped);
                     }
                 }
-                Element::Image { url, .. } => lines.push(url.clone()),
+                Element::Image { url, alt } => match ir.meta.media.get(url) {
+                    Some(file_id) => {
+                        let mut photo = json!({ "method": "sendPhoto", "photo": file_id });
+                        if let Some(alt) = alt {
+                            let caption = sanitize_text_for_tier(alt, ir.tier, &mut metrics);
+                            photo["caption"] = Value::String(caption.trim().to_string());
+                        }
+                        photos.push(photo);
+                    }
+                    None => lines.push(url.clone()),
+                },
                 Element::FactSet { facts } => {
                     for fact in facts {
                         let label = sanitized_html(&fact.label, ir.tier, &mut metrics);
@@ -115,22 +165,32 @@ impl PlatformRenderer for TelegramRenderer {
         }
 
         let text = lines.join("\n");
-        let limited = enforce_text_limit(
-            &text,
-            TELEGRAM_TEXT_LIMIT,
-            "telegram.body_truncated",
-            &mut metrics,
-            &mut warnings,
-        );
-        let mut payload = RenderOutput::new(json!({
-            "method": "sendMessage",
-            "parse_mode": "HTML",
-            "text": limited,
-        }));
-
-        if let Some(keyboard) = build_keyboard(ir, &mut warnings, &mut metrics) {
-            payload.payload["reply_markup"] = json!({ "inline_keyboard": keyboard });
+        let chunks = split_message(&text, TELEGRAM_TEXT_LIMIT, SplitSyntax::Html);
+        if chunks.len() > 1 {
+            metrics.limit_exceeded = true;
+            warnings.push("telegram.body_split".into());
         }
+        // Uploaded images go out first as photos; a card with nothing else to say needs no
+        // text message.
+        let skip_text = text.trim().is_empty() && !photos.is_empty();
+        let mut messages = photos;
+        if !skip_text {
+            messages.extend(chunks.into_iter().map(|chunk| {
+                json!({
+                    "method": "sendMessage",
+                    "parse_mode": "HTML",
+                    "text": chunk,
+                })
+            }));
+        }
+
+        // Buttons belong under the final part so they follow the full text.
+        if let Some(markup) = self.reply_markup(ir, &mut warnings, &mut metrics)
+            && let Some(last) = messages.last_mut()
+        {
+            last["reply_markup"] = markup;
+        }
+        let mut payload = RenderOutput::from_payloads(messages);
 
         payload.warnings = warnings;
         payload.limit_exceeded = metrics.limit_exceeded;
@@ -138,55 +198,150 @@ impl PlatformRenderer for TelegramRenderer {
         payload.url_blocked_count = metrics.url_blocked_count;
         payload
     }
-}
 
-fn build_keyboard(
-    ir: &MessageCardIr,
-    warnings: &mut Vec<String>,
-    metrics: &mut RenderMetrics,
-) -> Option<Vec<Vec<Value>>> {
-    if ir.actions.is_empty() {
-        return None;
+    fn render_update(&self, message_id: &str, rendered: &RenderOutput) -> Option<Value> {
+        let payload = &rendered.payload;
+        // Photos cannot be edited into text, and edits only accept inline keyboards.
+        if payload["method"] != "sendMessage" || payload["reply_markup"].get("keyboard").is_some() {
+            return None;
+        }
+        // Telegram message ids are integers; keep the raw id if it is not one.
+        let message_id = message_id
+            .parse::<i64>()
+            .map(Value::from)
+            .unwrap_or_else(|_| json!(message_id));
+        let text = payload["text"].as_str().unwrap_or_default();
+        let mut edit = if text.trim().is_empty() {
+            json!({ "method": "editMessageReplyMarkup" })
+        } else {
+            json!({
+                "method": "editMessageText",
+                "parse_mode": "HTML",
+                "text": text,
+            })
+        };
+        edit["message_id"] = message_id;
+        edit["reply_markup"] = payload
+            .get("reply_markup")
+            .cloned()
+            .unwrap_or_else(|| json!({ "inline_keyboard": [] }));
+        Some(edit)
     }
+}
 
-    let mut buttons = Vec::new();
-    for action in &ir.actions {
-        if buttons.len() == MAX_BUTTONS {
-            warnings.push("telegram.actions_truncated".into());
-            warn!(
-                target = "gsm.mcard.telegram",
-                "actions truncated at Telegram limit"
-            );
-            break;
+impl TelegramRenderer {
+    fn reply_markup(
+        &self,
+        ir: &MessageCardIr,
+        warnings: &mut Vec<String>,
+        metrics: &mut RenderMetrics,
+    ) -> Option<Value> {
+        let layout = ir.meta.keyboard.clone().unwrap_or_default();
+        let buttons = match layout.kind {
+            KeyboardKind::Inline => self.inline_buttons(ir, warnings, metrics),
+            KeyboardKind::Reply => reply_buttons(ir, warnings, metrics),
+        };
+        if buttons.is_empty() {
+            return None;
         }
-        match action {
-            super::IrAction::OpenUrl { title, url } => {
-                if let Some(resolved) = resolve_url_with_policy(&ir.meta, url, metrics, warnings) {
-                    buttons.push(json!({
-                        "text": sanitized_html(title, ir.tier, metrics),
-                        "url": resolved,
-                    }));
+
+        let per_row = layout.buttons_per_row.unwrap_or(MAX_PER_ROW).max(1);
+        let rows: Vec<Vec<Value>> = buttons.chunks(per_row).map(|c| c.to_vec()).collect();
+        Some(match layout.kind {
+            KeyboardKind::Inline => json!({ "inline_keyboard": rows }),
+            KeyboardKind::Reply => json!({
+                "keyboard": rows,
+                "one_time_keyboard": layout.one_time,
+                "resize_keyboard": layout.resize,
+            }),
+        })
+    }
+
+    fn inline_buttons(
+        &self,
+        ir: &MessageCardIr,
+        warnings: &mut Vec<String>,
+        metrics: &mut RenderMetrics,
+    ) -> Vec<Value> {
+        let mut buttons = Vec::new();
+        for action in &ir.actions {
+            if buttons.len() == MAX_BUTTONS {
+                warnings.push("telegram.actions_truncated".into());
+                warn!(
+                    target = "gsm.mcard.telegram",
+                    "actions truncated at Telegram limit"
+                );
+                break;
+            }
+            match action {
+                IrAction::OpenUrl { title, url } => {
+                    if let Some(resolved) =
+                        resolve_url_with_policy(&ir.meta, url, metrics, warnings)
+                    {
+                        buttons.push(json!({
+                            "text": sanitized_html(title, ir.tier, metrics),
+                            "url": resolved,
+                        }));
+                    }
+                }
+                IrAction::Postback { title, data } | IrAction::Dialog { title, data } => {
+                    if let Some(callback_data) = self.callback_data(data, warnings, metrics) {
+                        buttons.push(json!({
+                            "text": sanitized_html(title, ir.tier, metrics),
+                            "callback_data": callback_data,
+                        }));
+                    }
                 }
             }
-            super::IrAction::Postback { title, data } => {
-                let data_str = serde_json::to_string(data).unwrap_or_else(|_| "{}".into());
-                buttons.push(json!({
-                    "text": sanitized_html(title, ir.tier, metrics),
-                    "callback_data": data_str,
-                }));
+        }
+        buttons
+    }
+
+    fn callback_data(
+        &self,
+        data: &Value,
+        warnings: &mut Vec<String>,
+        metrics: &mut RenderMetrics,
+    ) -> Option<String> {
+        let data_str = serde_json::to_string(data).unwrap_or_else(|_| "{}".into());
+        if data_str.len() <= CALLBACK_DATA_LIMIT {
+            return Some(data_str);
+        }
+        match &self.token_store {
+            Some(store) => Some(store.issue(data, self.token_ttl)),
+            None => {
+                metrics.limit_exceeded = true;
+                warnings.push("telegram.callback_data_dropped".into());
+                warn!(
+                    target = "gsm.mcard.telegram",
+                    "postback exceeds callback_data limit and no token store is configured"
+                );
+                None
             }
         }
     }
+}
 
-    if buttons.is_empty() {
-        None
-    } else {
-        let mut rows = Vec::new();
-        for chunk in buttons.chunks(MAX_PER_ROW) {
-            rows.push(chunk.to_vec());
+fn reply_buttons(
+    ir: &MessageCardIr,
+    warnings: &mut Vec<String>,
+    metrics: &mut RenderMetrics,
+) -> Vec<Value> {
+    let mut buttons = Vec::new();
+    for action in &ir.actions {
+        if let IrAction::OpenUrl { .. } = action {
+            warnings.push("telegram.reply_keyboard_url_dropped".into());
+            continue;
+        }
+        if buttons.len() == MAX_BUTTONS {
+            warnings.push("telegram.actions_truncated".into());
+            break;
         }
-        Some(rows)
+        // Reply keyboard taps arrive as plain text, so only the title survives the round trip.
+        let title = sanitize_text_for_tier(action.title(), ir.tier, metrics);
+        buttons.push(json!({ "text": title.trim() }));
     }
+    buttons
 }
 
 fn html_escape(text: &str) -> String {
@@ -199,3 +354,24 @@ fn sanitized_html(text: &str, tier: Tier, metrics: &mut RenderMetrics) -> String
     let sanitized = sanitize_text_for_tier(text, tier, metrics);
     html_escape(sanitized.trim())
 }
+
+/// Like [`sanitized_html`], but converts Markdown text into Telegram's HTML parse mode.
+fn sanitized_text_html(
+    text: &str,
+    markdown: bool,
+    tier: Tier,
+    metrics: &mut RenderMetrics,
+    warnings: &mut Vec<String>,
+) -> String {
+    if !markdown {
+        return sanitized_html(text, tier, metrics);
+    }
+    let sanitized = sanitize_text_for_tier(text, tier, metrics);
+    format_text(
+        sanitized.trim(),
+        true,
+        MarkdownDialect::TelegramHtml,
+        metrics,
+        warnings,
+    )
+}
@@ -0,0 +1,444 @@
+//! Card renderers shipped by packs as WASM components.
+//!
+//! A renderer component exports `render: func(ir: string) -> result<string, string>`. The
+//! argument is the `MessageCardIr` as JSON; the result is a JSON object
+//! `{"payload": ..., "continuations": [...], "warnings": [...]}` where only `payload` is
+//! required. Each render runs in a fresh instance with a fuel budget and memory and table
+//! limits, so a misbehaving plugin cannot keep state between cards, stall the caller or
+//! exhaust its memory.
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use greentic_interfaces_host::runner_host_v1;
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::warn;
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Config, Engine, Store, StoreLimits, StoreLimitsBuilder};
+
+use super::{PlatformRenderer, RenderOutput, RendererRegistry};
+use crate::component_host::add_host_imports;
+use crate::messaging_card::ir::MessageCardIr;
+use crate::messaging_card::tier::Tier;
+use crate::pack_extensions::{ProviderExtensionsRegistry, RendererProviderDecl};
+use crate::path_safety::normalize_under_root;
+
+const DEFAULT_EXPORT: &str = "render";
+const DEFAULT_FUEL: u64 = 50_000_000;
+const DEFAULT_MEMORY_LIMIT: usize = 64 << 20;
+const DEFAULT_TABLE_ELEMENTS: usize = 10_000;
+
+/// Host imports offered to renderer components. Rendering must be pure, so HTTP is refused
+/// and the key-value store is empty and write-only.
+struct RendererHost {
+    limits: StoreLimits,
+}
+
+impl runner_host_v1::RunnerHost for RendererHost {
+    fn http_request(
+        &mut self,
+        _method: String,
+        _url: String,
+        _headers: Vec<String>,
+        _body: Option<Vec<u8>>,
+    ) -> wasmtime::Result<Result<Vec<u8>, String>> {
+        Ok(Err("http_request is not available to renderers".into()))
+    }
+
+    fn kv_get(&mut self, _ns: String, _key: String) -> wasmtime::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn kv_put(&mut self, _ns: String, _key: String, _val: String) -> wasmtime::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GuestOutput {
+    payload: Value,
+    #[serde(default)]
+    continuations: Vec<Value>,
+    #[serde(default)]
+    warnings: Vec<String>,
+}
+
+/// `PlatformRenderer` backed by a WASM component.
+pub struct WasmRenderer {
+    platform: String,
+    target_tier: Tier,
+    export: String,
+    fuel: u64,
+    memory_limit: usize,
+    engine: Engine,
+    component: Component,
+    linker: Linker<RendererHost>,
+}
+
+impl WasmRenderer {
+    /// Compiles a component from its binary (or, for tests, WAT text) form.
+    pub fn new(platform: impl Into<String>, target_tier: Tier, bytes: &[u8]) -> Result<Self> {
+        let mut config = Config::new();
+        config.wasm_component_model(true);
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+        let component = Component::new(&engine, bytes).context("invalid renderer component")?;
+        let mut linker = Linker::new(&engine);
+        add_host_imports(&mut linker)?;
+        Ok(Self {
+            platform: platform.into(),
+            target_tier,
+            export: DEFAULT_EXPORT.into(),
+            fuel: DEFAULT_FUEL,
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+            engine,
+            component,
+            linker,
+        })
+    }
+
+    pub fn from_file(platform: impl Into<String>, target_tier: Tier, path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("failed to read renderer component {}", path.display()))?;
+        Self::new(platform, target_tier, &bytes)
+    }
+
+    /// Name of the exported render function; defaults to `render`.
+    pub fn with_export(mut self, export: impl Into<String>) -> Self {
+        self.export = export.into();
+        self
+    }
+
+    /// Fuel available to a single render call.
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    /// Bytes of linear memory a single render call may grow to; defaults to 64 MiB.
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = bytes;
+        self
+    }
+
+    fn call(&self, ir: &MessageCardIr) -> Result<RenderOutput> {
+        let input = serde_json::to_string(ir)?;
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.memory_limit)
+            .table_elements(DEFAULT_TABLE_ELEMENTS)
+            .trap_on_grow_failure(true)
+            .build();
+        let mut store = Store::new(&self.engine, RendererHost { limits });
+        store.limiter(|host| &mut host.limits);
+        store.set_fuel(self.fuel)?;
+        let instance = self.linker.instantiate(&mut store, &self.component)?;
+        let render = instance
+            .get_typed_func::<(String,), (Result<String, String>,)>(
+                &mut store,
+                self.export.as_str(),
+            )
+            .with_context(|| format!("component does not export `{}`", self.export))?;
+        let (result,) = render.call(&mut store, (input,))?;
+        let raw = result.map_err(|err| anyhow!("renderer returned an error: {err}"))?;
+        let guest: GuestOutput =
+            serde_json::from_str(&raw).context("renderer returned an invalid output")?;
+        let mut output = RenderOutput::new(guest.payload);
+        output.continuations = guest.continuations;
+        output.warnings = guest.warnings;
+        Ok(output)
+    }
+}
+
+impl PlatformRenderer for WasmRenderer {
+    fn platform(&self) -> &str {
+        &self.platform
+    }
+
+    fn target_tier(&self) -> Tier {
+        self.target_tier
+    }
+
+    fn try_render(&self, ir: &MessageCardIr) -> Result<RenderOutput> {
+        self.call(ir)
+            .with_context(|| format!("wasm renderer for {} failed", self.platform))
+    }
+
+    fn render(&self, ir: &MessageCardIr) -> RenderOutput {
+        match self.call(ir) {
+            Ok(output) => output,
+            Err(err) => {
+                warn!(platform = %self.platform, error = %format!("{err:#}"), "wasm renderer failed");
+                let mut output = RenderOutput::new(Value::Null);
+                output
+                    .warnings
+                    .push(format!("{}.render_failed", self.platform));
+                output
+            }
+        }
+    }
+}
+
+/// Compiles and registers every renderer declared through the `messaging.renderer.v1` pack
+/// extension and returns the platforms that were registered. Component references are resolved
+/// under `components_root`. A renderer that fails to load is logged and skipped. A platform
+/// that already has a renderer keeps it unless the declaration sets `replace_builtin`, in which
+/// case the existing renderer becomes the fallback for failed renders.
+pub fn register_pack_renderers(
+    registry: &mut RendererRegistry,
+    extensions: &ProviderExtensionsRegistry,
+    components_root: &Path,
+) -> Vec<String> {
+    let mut registered = Vec::new();
+    for (platform, decl) in &extensions.renderers {
+        let replaces = registry.contains(platform);
+        if replaces && !decl.replace_builtin {
+            warn!(
+                platform = %platform,
+                "pack renderer ignored: platform has a built-in renderer and replace_builtin is not set"
+            );
+            continue;
+        }
+        let renderer = match load_pack_renderer(platform, decl, components_root) {
+            Ok(renderer) => renderer,
+            Err(err) => {
+                warn!(platform = %platform, error = %format!("{err:#}"), "pack renderer skipped");
+                continue;
+            }
+        };
+        if replaces {
+            registry.replace(renderer);
+        } else {
+            registry.register(renderer);
+        }
+        registered.push(platform.clone());
+    }
+    registered
+}
+
+fn load_pack_renderer(
+    platform: &str,
+    decl: &RendererProviderDecl,
+    components_root: &Path,
+) -> Result<WasmRenderer> {
+    let path = normalize_under_root(components_root, Path::new(&decl.runtime.component_ref))?;
+    let mut renderer = WasmRenderer::from_file(platform, decl.target_tier, &path)
+        .with_context(|| format!("failed to load renderer for {platform}"))?;
+    if !decl.runtime.export.is_empty() {
+        renderer = renderer.with_export(decl.runtime.export.as_str());
+    }
+    Ok(renderer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging_card::ir::MessageCardIrBuilder;
+    use crate::messaging_card::telemetry::{TelemetryEvent, TelemetryHook};
+    use crate::messaging_card::{MessageCardEngine, SlackRenderer};
+    use crate::pack_extensions::RuntimeRef;
+    use std::sync::{Arc, Mutex};
+
+    const OUTPUT: &str = r#"{"payload":{"text":"hi"},"warnings":["chat.plain"]}"#;
+
+    /// Component whose `render` returns `OUTPUT`, or echoes its input when `echo` is set.
+    /// `grow` returns the same output after growing memory by 100 pages (6.4 MB).
+    fn component(echo: bool) -> String {
+        let body = if echo {
+            "(i32.store (i32.const 20) (local.get 0))\n(i32.store (i32.const 24) (local.get 1))"
+                .to_string()
+        } else {
+            format!(
+                "(i32.store (i32.const 20) (i32.const 64))\n(i32.store (i32.const 24) (i32.const {}))",
+                OUTPUT.len()
+            )
+        };
+        format!(
+            r#"(component
+  (core module $m
+    (memory (export "memory") 1)
+    (data (i32.const 64) "{data}")
+    (global $bump (mut i32) (i32.const 1024))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr (global.get $bump))
+      (global.set $bump (i32.add (global.get $bump) (local.get 3)))
+      (local.get $ptr))
+    (func (export "render") (param i32 i32) (result i32)
+      (i32.store (i32.const 16) (i32.const 0))
+      {body}
+      (i32.const 16))
+    (func (export "spin") (param i32 i32) (result i32)
+      (loop $l (br $l))
+      (i32.const 16))
+    (func (export "grow") (param i32 i32) (result i32)
+      (drop (memory.grow (i32.const 100)))
+      (i32.store (i32.const 16) (i32.const 0))
+      {body}
+      (i32.const 16)))
+  (core instance $i (instantiate $m))
+  (func (export "render") (param "ir" string) (result (result string (error string)))
+    (canon lift (core func $i "render") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func (export "spin") (param "ir" string) (result (result string (error string)))
+    (canon lift (core func $i "spin") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func (export "grow") (param "ir" string) (result (result string (error string)))
+    (canon lift (core func $i "grow") (memory $i "memory") (realloc (func $i "realloc")))))"#,
+            data = OUTPUT.replace('"', "\\\""),
+        )
+    }
+
+    fn sample_ir() -> MessageCardIr {
+        MessageCardIrBuilder::default().title("Hello").build()
+    }
+
+    #[test]
+    fn renders_guest_output() {
+        let renderer = WasmRenderer::new("chat", Tier::Basic, component(false).as_bytes()).unwrap();
+        assert_eq!(renderer.platform(), "chat");
+        let output = renderer.render(&sample_ir());
+        assert_eq!(output.payload, serde_json::json!({"text": "hi"}));
+        assert_eq!(output.warnings, vec!["chat.plain".to_string()]);
+
+        let mut registry = RendererRegistry::default();
+        registry.register(renderer);
+        assert!(registry.render("chat", &sample_ir()).is_some());
+    }
+
+    #[test]
+    fn invalid_output_is_reported_as_warning() {
+        // Echoing the IR back is valid JSON but lacks the required `payload`.
+        let renderer = WasmRenderer::new("chat", Tier::Basic, component(true).as_bytes()).unwrap();
+        let output = renderer.render(&sample_ir());
+        assert!(output.payload.is_null());
+        assert_eq!(output.warnings, vec!["chat.render_failed".to_string()]);
+    }
+
+    fn decl(export: &str, replace_builtin: bool) -> RendererProviderDecl {
+        RendererProviderDecl {
+            runtime: RuntimeRef {
+                component_ref: "chat.wat".into(),
+                export: export.into(),
+                world: "greentic:messaging/renderer@1.0.0".into(),
+            },
+            target_tier: Tier::Advanced,
+            replace_builtin,
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct RecordingTelemetry(Arc<Mutex<Vec<TelemetryEvent>>>);
+
+    impl TelemetryHook for RecordingTelemetry {
+        fn emit(&self, event: TelemetryEvent) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    #[test]
+    fn registers_renderers_declared_by_packs() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("chat.wat"), component(false)).unwrap();
+        let mut extensions = ProviderExtensionsRegistry::default();
+        extensions
+            .renderers
+            .insert("chat".into(), decl("render", false));
+        let mut missing = decl("render", false);
+        missing.runtime.component_ref = "missing.wat".into();
+        extensions.renderers.insert("other".into(), missing);
+
+        let mut registry = RendererRegistry::default();
+        let registered = register_pack_renderers(&mut registry, &extensions, root.path());
+        assert_eq!(registered, vec!["chat".to_string()]);
+        let renderer = registry.get("chat").expect("registered");
+        assert_eq!(renderer.target_tier(), Tier::Advanced);
+        assert!(registry.get("other").is_none());
+    }
+
+    #[test]
+    fn pack_renderers_keep_builtins_unless_replacement_is_declared() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("chat.wat"), component(false)).unwrap();
+        let mut extensions = ProviderExtensionsRegistry::default();
+        let mut declared = decl("render", false);
+        declared.target_tier = Tier::Basic;
+        extensions
+            .renderers
+            .insert("slack".into(), declared.clone());
+
+        let mut registry = RendererRegistry::default();
+        registry.register(SlackRenderer);
+        assert!(register_pack_renderers(&mut registry, &extensions, root.path()).is_empty());
+        assert_eq!(registry.get("slack").unwrap().target_tier(), Tier::Advanced);
+
+        declared.replace_builtin = true;
+        extensions.renderers.insert("slack".into(), declared);
+        let registered = register_pack_renderers(&mut registry, &extensions, root.path());
+        assert_eq!(registered, vec!["slack".to_string()]);
+        assert_eq!(registry.get("slack").unwrap().target_tier(), Tier::Basic);
+        assert!(registry.fallback("slack").is_some());
+    }
+
+    #[test]
+    fn engine_falls_back_to_builtin_when_pack_renderer_fails() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("chat.wat"), component(false)).unwrap();
+        let mut extensions = ProviderExtensionsRegistry::default();
+        extensions
+            .renderers
+            .insert("slack".into(), decl("missing", true));
+        let telemetry = RecordingTelemetry::default();
+        let engine = MessageCardEngine::bootstrap()
+            .with_telemetry(telemetry.clone())
+            .with_pack_renderers(&extensions, root.path());
+
+        let snapshot = engine
+            .render_card_snapshot("slack", &sample_ir())
+            .expect("fallback renders");
+        assert!(snapshot.output.payload.get("blocks").is_some());
+        assert!(
+            snapshot
+                .output
+                .warnings
+                .contains(&"slack.render_failed".to_string())
+        );
+        let events = telemetry.0.lock().unwrap();
+        assert!(events.iter().any(|event| matches!(
+            event,
+            TelemetryEvent::RenderFailed { platform, reason, .. }
+                if platform == "slack" && reason.contains("missing")
+        )));
+    }
+
+    #[test]
+    fn failed_render_without_fallback_yields_nothing() {
+        let renderer = WasmRenderer::new("chat", Tier::Basic, component(true).as_bytes()).unwrap();
+        assert!(renderer.try_render(&sample_ir()).is_err());
+        let mut registry = RendererRegistry::default();
+        registry.register(renderer);
+        let telemetry = RecordingTelemetry::default();
+        let engine = MessageCardEngine::new(registry).with_telemetry(telemetry.clone());
+        assert!(engine.render_card_snapshot("chat", &sample_ir()).is_none());
+        assert_eq!(telemetry.0.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn memory_growth_is_limited() {
+        let renderer = WasmRenderer::new("chat", Tier::Basic, component(false).as_bytes())
+            .unwrap()
+            .with_export("grow");
+        assert!(renderer.try_render(&sample_ir()).is_ok());
+
+        let renderer = renderer.with_memory_limit(1 << 20);
+        let err = renderer.try_render(&sample_ir()).unwrap_err();
+        assert!(format!("{err:#}").contains("chat"));
+    }
+
+    #[test]
+    fn runaway_guest_runs_out_of_fuel() {
+        let renderer = WasmRenderer::new("chat", Tier::Basic, component(false).as_bytes())
+            .unwrap()
+            .with_export("spin")
+            .with_fuel(10_000);
+        let output = renderer.render(&sample_ir());
+        assert_eq!(output.warnings, vec!["chat.render_failed".to_string()]);
+    }
+}
@@ -2,7 +2,9 @@ use crate::messaging_card::ir::MessageCardIr;
 use crate::messaging_card::spec::AuthRenderSpec;
 use crate::messaging_card::tier::Tier;
 
-use super::{PlatformRenderer, RenderMetrics, RenderOutput, adaptive_from_ir};
+use crate::messaging_card::adaptive::AdaptiveCardHost;
+
+use super::{PlatformRenderer, RenderMetrics, RenderOutput, adaptive_from_ir, fit_to_host};
 use serde_json::json;
 
 #[derive(Default)]
@@ -20,7 +22,8 @@ impl PlatformRenderer for WebChatRenderer {
     fn render(&self, ir: &MessageCardIr) -> RenderOutput {
         let mut warnings = Vec::new();
         let mut metrics = RenderMetrics::default();
-        let payload = adaptive_from_ir(ir, &mut metrics, &mut warnings);
+        let card = adaptive_from_ir(ir, &mut metrics, &mut warnings);
+        let payload = fit_to_host(card, AdaptiveCardHost::WebChat, &mut warnings);
         let mut output = RenderOutput::new(payload);
         output.warnings = warnings;
         output.limit_exceeded = metrics.limit_exceeded;
@@ -1,12 +1,14 @@
 use serde_json::{Value, json};
 use tracing::warn;
 
+use crate::messaging_card::adaptive::AdaptiveCardHost;
 use crate::messaging_card::ir::{Element, IrAction, MessageCardIr};
+use crate::messaging_card::markdown::MarkdownDialect;
 use crate::messaging_card::tier::Tier;
 
 use super::{
     PlatformRenderer, RenderMetrics, RenderOutput, WEBEX_TEXT_LIMIT, enforce_text_limit,
-    resolve_url_with_policy, sanitize_text_for_tier,
+    fit_to_host, format_text, resolve_url_with_policy, sanitize_text_for_tier,
 };
 
 const FACTSET_WARNING: &str = "webex.factset_downgraded";
@@ -53,10 +55,17 @@ impl PlatformRenderer for WebexRenderer {
 
         for element in &ir.elements {
             match element {
-                Element::Text { text, .. } => {
+                Element::Text { text, markdown } => {
                     let sanitized = sanitize_text_for_tier(text, ir.tier, &mut metrics);
-                    body.push(text_block(&enforce_text_limit(
+                    let formatted = format_text(
                         &sanitized,
+                        *markdown,
+                        MarkdownDialect::AdaptiveCard,
+                        &mut metrics,
+                        &mut warnings,
+                    );
+                    body.push(text_block(&enforce_text_limit(
+                        &formatted,
                         WEBEX_TEXT_LIMIT,
                         "webex.text_truncated",
                         &mut metrics,
@@ -133,7 +142,7 @@ impl PlatformRenderer for WebexRenderer {
                         }));
                     }
                 }
-                IrAction::Postback { title, data } => {
+                IrAction::Postback { title, data } | IrAction::Dialog { title, data } => {
                     let sanitized = sanitize_text_for_tier(title, ir.tier, &mut metrics);
                     actions.push(json!({
                         "type": "Action.Submit",
@@ -144,12 +153,13 @@ impl PlatformRenderer for WebexRenderer {
             }
         }
 
-        let payload = json!({
+        let card = json!({
             "type": "AdaptiveCard",
-            "version": "1.4",
+            "version": "1.3",
             "body": body,
             "actions": actions,
         });
+        let payload = fit_to_host(card, AdaptiveCardHost::Webex, &mut warnings);
 
         let mut output = RenderOutput::new(payload);
         output.warnings = warnings;
@@ -158,6 +168,17 @@ impl PlatformRenderer for WebexRenderer {
         output.url_blocked_count = metrics.url_blocked_count;
         output
     }
+
+    fn render_update(&self, message_id: &str, rendered: &RenderOutput) -> Option<Value> {
+        Some(json!({
+            "method": "edit",
+            "messageId": message_id,
+            "attachments": [{
+                "contentType": "application/vnd.microsoft.card.adaptive",
+                "content": rendered.payload,
+            }],
+        }))
+    }
 }
 
 fn text_block(text: &str) -> Value {
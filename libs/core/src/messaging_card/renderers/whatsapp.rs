@@ -1,19 +1,41 @@
 use serde_json::{Map, Value, json};
 use tracing::warn;
 
-use crate::messaging_card::ir::{Element, InputKind, IrAction, MessageCardIr};
+use crate::messaging_card::ir::{Element, InputKind, IrAction, MessageCardIr, TemplateMessage};
+use crate::messaging_card::markdown::MarkdownDialect;
+use crate::messaging_card::split::{SplitSyntax, split_message};
 use crate::messaging_card::tier::Tier;
 
 use super::{
     PlatformRenderer, RenderMetrics, RenderOutput, WHATSAPP_TEXT_LIMIT, enforce_text_limit,
-    resolve_url_with_policy, sanitize_text_for_tier,
+    format_text, resolve_url_with_policy, sanitize_text_for_tier,
 };
 
 const MAX_BUTTONS: usize = 3;
+const MAX_LIST_ROWS: usize = 10;
+const INTERACTIVE_BODY_LIMIT: usize = 1024;
+const HEADER_LIMIT: usize = 60;
+const FOOTER_LIMIT: usize = 60;
+const BUTTON_TITLE_LIMIT: usize = 20;
+const ROW_TITLE_LIMIT: usize = 24;
+const SECTION_TITLE_LIMIT: usize = 24;
+const REPLY_ID_LIMIT: usize = 256;
+/// Warnings from an abandoned native rendering that explain the text fallback.
+const FALLBACK_REASONS: &[&str] = &["whatsapp.reply_id_too_long"];
+const LIST_BUTTON_TEXT: &str = "Choose";
 
 #[derive(Default)]
 pub struct WhatsAppRenderer;
 
+/// Message shape picked for a card, from most to least native.
+enum Mode {
+    Template(TemplateMessage),
+    ReplyButtons,
+    List,
+    CtaUrl,
+    Text,
+}
+
 impl PlatformRenderer for WhatsAppRenderer {
     fn platform(&self) -> &'static str {
         "whatsapp"
@@ -26,122 +48,547 @@ impl PlatformRenderer for WhatsAppRenderer {
     fn render(&self, ir: &MessageCardIr) -> RenderOutput {
         let mut warnings = Vec::new();
         let mut metrics = RenderMetrics::default();
-        let mut body_lines = Vec::new();
 
-        if let Some(title) = &ir.head.title {
-            let sanitized = sanitize_text_for_tier(title, ir.tier, &mut metrics);
-            body_lines.push(sanitized.trim().to_string());
+        let mode = select_mode(ir, &mut warnings);
+        let attempted_native = !matches!(mode, Mode::Text);
+        // Free-form media is not allowed when a template has to be used.
+        let send_media = !matches!(mode, Mode::Template(_));
+        let fallback_point = (warnings.len(), metrics.clone());
+        let native = match mode {
+            Mode::Template(template) => render_template(ir, &template, &mut warnings, &mut metrics),
+            Mode::Text => None,
+            interactive => render_interactive(ir, interactive, &mut warnings, &mut metrics),
+        };
+        let mut payloads = if send_media {
+            media_payloads(ir, &mut metrics)
+        } else {
+            Vec::new()
+        };
+        payloads.extend(match native {
+            Some(payload) => vec![payload],
+            None => {
+                // Discard partial native output so metrics are not counted twice, but keep
+                // the reason the native rendering was abandoned.
+                if attempted_native {
+                    let reasons: Vec<String> = warnings
+                        .drain(fallback_point.0..)
+                        .filter(|w| FALLBACK_REASONS.contains(&w.as_str()))
+                        .collect();
+                    metrics = fallback_point.1;
+                    warnings.extend(reasons);
+                    warnings.push("whatsapp.interactive_fallback".into());
+                }
+                render_text(ir, &mut warnings, &mut metrics)
+            }
+        });
+
+        let mut render_output = RenderOutput::from_payloads(payloads);
+        render_output.warnings = warnings;
+        render_output.limit_exceeded = metrics.limit_exceeded;
+        render_output.sanitized_count = metrics.sanitized_count;
+        render_output.url_blocked_count = metrics.url_blocked_count;
+        render_output
+    }
+}
+
+/// Image messages for card images that were uploaded ahead of rendering. They are sent before
+/// the card body so the text reads as a caption.
+fn media_payloads(ir: &MessageCardIr, metrics: &mut RenderMetrics) -> Vec<Value> {
+    ir.elements
+        .iter()
+        .filter_map(|element| match element {
+            Element::Image { url, alt } => {
+                let media_id = ir.meta.media.get(url)?;
+                let mut image = json!({ "id": media_id });
+                if let Some(alt) = alt {
+                    let caption = sanitize_text_for_tier(alt, ir.tier, metrics);
+                    image["caption"] = Value::String(caption.trim().to_string());
+                }
+                Some(json!({ "type": "image", "image": image }))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn select_mode(ir: &MessageCardIr, warnings: &mut Vec<String>) -> Mode {
+    if ir.meta.service_window_open == Some(false) {
+        match &ir.meta.template {
+            Some(template) => return Mode::Template(template.clone()),
+            None => warnings.push("whatsapp.service_window_closed".into()),
         }
-        if let Some(text) = &ir.head.text
-            && !text.trim().is_empty()
-        {
-            let sanitized = sanitize_text_for_tier(text, ir.tier, &mut metrics);
-            body_lines.push(sanitized.trim().to_string());
+    }
+
+    let url_count = ir
+        .actions
+        .iter()
+        .filter(|action| matches!(action, IrAction::OpenUrl { .. }))
+        .count();
+    let postback_count = ir.actions.len() - url_count;
+    let mut choice_inputs = 0;
+    let mut free_inputs = 0;
+    for element in &ir.elements {
+        if let Element::Input { kind, choices, .. } = element {
+            if matches!(kind, InputKind::Choice) && !choices.is_empty() {
+                choice_inputs += 1;
+            } else {
+                free_inputs += 1;
+            }
         }
+    }
+
+    // Interactive messages cannot mix URL and reply buttons, and free-text inputs can only be
+    // answered with a text reply, so those cards keep the text rendering.
+    if free_inputs > 0 {
+        return Mode::Text;
+    }
+    if url_count == 1 && postback_count == 0 && choice_inputs == 0 {
+        return Mode::CtaUrl;
+    }
+    if url_count > 0 {
+        return Mode::Text;
+    }
+    if choice_inputs == 0 && (1..=MAX_BUTTONS).contains(&postback_count) {
+        return Mode::ReplyButtons;
+    }
+    if choice_inputs > 0 || postback_count > MAX_BUTTONS {
+        return Mode::List;
+    }
+    Mode::Text
+}
+
+fn render_text(
+    ir: &MessageCardIr,
+    warnings: &mut Vec<String>,
+    metrics: &mut RenderMetrics,
+) -> Vec<Value> {
+    let mut body_lines = Vec::new();
+    if let Some(title) = &ir.head.title {
+        let sanitized = sanitize_text_for_tier(title, ir.tier, metrics);
+        body_lines.push(sanitized.trim().to_string());
+    }
+    body_lines.extend(content_lines(ir, true, warnings, metrics));
+    if let Some(footer) = &ir.head.footer {
+        let sanitized = sanitize_text_for_tier(footer, ir.tier, metrics);
+        body_lines.push(sanitized.trim().to_string());
+    }
+
+    let mut components = Vec::new();
+    let formatted_text = body_lines.join("\n");
+    let chunks = split_message(&formatted_text, WHATSAPP_TEXT_LIMIT, SplitSyntax::Markdown);
+    if chunks.len() > 1 {
+        metrics.limit_exceeded = true;
+        warnings.push("whatsapp.body_split".into());
+    }
+
+    let buttons = build_buttons(ir, warnings, metrics);
+    if !buttons.is_empty() {
+        components.push(json!({
+        "type": "BUTTONS",
+        "buttons": buttons,
+        }));
+    }
 
-        let primary_text = ir.head.text.as_deref().map(str::to_string);
-        let mut skipped_primary = false;
-
-        for element in &ir.elements {
-            match element {
-                Element::Text { text, .. } => {
-                    if !skipped_primary {
-                        if let Some(primary) = &primary_text
-                            && primary == text
-                        {
-                            skipped_primary = true;
-                            continue;
-                        }
+    let last = chunks.len() - 1;
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, text)| {
+            let mut payload = Map::new();
+            payload.insert("type".into(), Value::String("WhatsAppTemplate".into()));
+            payload.insert("body".into(), Value::String(text));
+            // Buttons go on the final part so they follow the full text.
+            if index == last && !components.is_empty() {
+                payload.insert("components".into(), Value::Array(components.clone()));
+            }
+            Value::Object(payload)
+        })
+        .collect()
+}
+
+/// Body lines below the title: primary text, elements and, when `prompt_inputs` is set,
+/// reply prompts for inputs.
+fn content_lines(
+    ir: &MessageCardIr,
+    prompt_inputs: bool,
+    warnings: &mut Vec<String>,
+    metrics: &mut RenderMetrics,
+) -> Vec<String> {
+    let mut body_lines = Vec::new();
+    if let Some(text) = &ir.head.text
+        && !text.trim().is_empty()
+    {
+        let markdown = ir.elements.iter().any(
+            |element| matches!(element, Element::Text { text: t, markdown: true } if t == text),
+        );
+        let sanitized = sanitize_text_for_tier(text, ir.tier, metrics);
+        body_lines.push(format_text(
+            sanitized.trim(),
+            markdown,
+            MarkdownDialect::WhatsApp,
+            metrics,
+            warnings,
+        ));
+    }
+
+    let primary_text = ir.head.text.as_deref().map(str::to_string);
+    let mut skipped_primary = false;
+
+    for element in &ir.elements {
+        match element {
+            Element::Text { text, markdown } => {
+                if !skipped_primary {
+                    if let Some(primary) = &primary_text
+                        && primary == text
+                    {
                         skipped_primary = true;
+                        continue;
                     }
-                    let sanitized = sanitize_text_for_tier(text, ir.tier, &mut metrics);
-                    body_lines.push(sanitized.trim().to_string());
+                    skipped_primary = true;
                 }
-                Element::Image { url, .. } => body_lines.push(url.to_string()),
-                Element::FactSet { facts } => {
-                    for fact in facts {
-                        let label = sanitize_text_for_tier(&fact.label, ir.tier, &mut metrics);
-                        let value = sanitize_text_for_tier(&fact.value, ir.tier, &mut metrics);
-                        body_lines.push(format!("• {label}: {value}"));
-                    }
+                let sanitized = sanitize_text_for_tier(text, ir.tier, metrics);
+                body_lines.push(format_text(
+                    sanitized.trim(),
+                    *markdown,
+                    MarkdownDialect::WhatsApp,
+                    metrics,
+                    warnings,
+                ));
+            }
+            Element::Image { url, .. } => {
+                if !ir.meta.media.contains_key(url) {
+                    body_lines.push(url.to_string());
                 }
-                Element::Input {
-                    label,
-                    kind,
-                    choices,
-                    ..
-                } => {
-                    warnings.push("whatsapp.inputs_not_supported".into());
-                    warn!(
-                        target = "gsm.mcard.whatsapp",
-                        "downgrading inputs to prompt text"
-                    );
-                    let field = label
-                        .as_deref()
-                        .map(|value| sanitize_text_for_tier(value, ir.tier, &mut metrics))
-                        .unwrap_or_else(|| "Input".into());
-                    let field = field.trim().to_string();
-                    let prompt = match kind {
-                        InputKind::Text => format!("{field}: reply with your answer."),
-                        InputKind::Choice => {
-                            let opts = if choices.is_empty() {
-                                "(choose any option)".to_string()
-                            } else {
-                                choices
-                                    .iter()
-                                    .map(|c| {
-                                        sanitize_text_for_tier(&c.title, ir.tier, &mut metrics)
-                                            .trim()
-                                            .to_string()
-                                    })
-                                    .collect::<Vec<_>>()
-                                    .join(", ")
-                            };
-                            format!("{field}: reply with [{opts}].")
-                        }
-                    };
-                    body_lines.push(prompt);
+            }
+            Element::FactSet { facts } => {
+                for fact in facts {
+                    let label = sanitize_text_for_tier(&fact.label, ir.tier, metrics);
+                    let value = sanitize_text_for_tier(&fact.value, ir.tier, metrics);
+                    body_lines.push(format!("• {label}: {value}"));
+                }
+            }
+            Element::Input {
+                label,
+                kind,
+                choices,
+                ..
+            } => {
+                if !prompt_inputs {
+                    continue;
                 }
+                warnings.push("whatsapp.inputs_not_supported".into());
+                warn!(
+                    target = "gsm.mcard.whatsapp",
+                    "downgrading inputs to prompt text"
+                );
+                let field = label
+                    .as_deref()
+                    .map(|value| sanitize_text_for_tier(value, ir.tier, metrics))
+                    .unwrap_or_else(|| "Input".into());
+                let field = field.trim().to_string();
+                let prompt = match kind {
+                    InputKind::Text => format!("{field}: reply with your answer."),
+                    InputKind::Choice => {
+                        let opts = if choices.is_empty() {
+                            "(choose any option)".to_string()
+                        } else {
+                            choices
+                                .iter()
+                                .map(|c| {
+                                    sanitize_text_for_tier(&c.title, ir.tier, metrics)
+                                        .trim()
+                                        .to_string()
+                                })
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        };
+                        format!("{field}: reply with [{opts}].")
+                    }
+                };
+                body_lines.push(prompt);
             }
         }
+    }
+    body_lines
+}
 
-        if let Some(footer) = &ir.head.footer {
-            let sanitized = sanitize_text_for_tier(footer, ir.tier, &mut metrics);
-            body_lines.push(sanitized.trim().to_string());
+fn render_interactive(
+    ir: &MessageCardIr,
+    mode: Mode,
+    warnings: &mut Vec<String>,
+    metrics: &mut RenderMetrics,
+) -> Option<Value> {
+    let action = match mode {
+        Mode::ReplyButtons => reply_buttons_action(ir, warnings, metrics)?,
+        Mode::List => list_action(ir, warnings, metrics)?,
+        Mode::CtaUrl => cta_url_action(ir, warnings, metrics)?,
+        Mode::Template(_) | Mode::Text => return None,
+    };
+    let kind = match mode {
+        Mode::ReplyButtons => "button",
+        Mode::List => "list",
+        _ => "cta_url",
+    };
+
+    let lines = content_lines(ir, false, warnings, metrics);
+    let mut body = lines.join("\n");
+    if body.trim().is_empty() {
+        body = ir
+            .head
+            .title
+            .as_deref()
+            .map(|title| sanitize_text_for_tier(title, ir.tier, metrics))
+            .unwrap_or_else(|| LIST_BUTTON_TEXT.into());
+    }
+    let body = enforce_text_limit(
+        &body,
+        INTERACTIVE_BODY_LIMIT,
+        "whatsapp.body_truncated",
+        metrics,
+        warnings,
+    );
+
+    let mut interactive = json!({
+        "type": kind,
+        "body": { "text": body },
+        "action": action,
+    });
+    if let Some(title) = &ir.head.title {
+        let sanitized = sanitize_text_for_tier(title, ir.tier, metrics);
+        interactive["header"] = json!({
+            "type": "text",
+            "text": limit(sanitized.trim(), HEADER_LIMIT, metrics, warnings),
+        });
+    }
+    if let Some(footer) = &ir.head.footer {
+        let sanitized = sanitize_text_for_tier(footer, ir.tier, metrics);
+        interactive["footer"] = json!({
+            "text": limit(sanitized.trim(), FOOTER_LIMIT, metrics, warnings),
+        });
+    }
+
+    Some(json!({
+        "type": "interactive",
+        "interactive": interactive,
+    }))
+}
+
+fn reply_buttons_action(
+    ir: &MessageCardIr,
+    warnings: &mut Vec<String>,
+    metrics: &mut RenderMetrics,
+) -> Option<Value> {
+    let mut buttons = Vec::new();
+    for action in &ir.actions {
+        if let IrAction::Postback { title, data } | IrAction::Dialog { title, data } = action {
+            let id = reply_id(data, warnings)?;
+            let title = sanitize_text_for_tier(title, ir.tier, metrics);
+            buttons.push(json!({
+                "type": "reply",
+                "reply": {
+                    "id": id,
+                    "title": limit(title.trim(), BUTTON_TITLE_LIMIT, metrics, warnings),
+                }
+            }));
         }
+    }
+    Some(json!({ "buttons": buttons }))
+}
 
-        let mut components = Vec::new();
-        let formatted_text = body_lines.join("\n");
-        let text = enforce_text_limit(
-            &formatted_text,
-            WHATSAPP_TEXT_LIMIT,
-            "whatsapp.body_truncated",
-            &mut metrics,
-            &mut warnings,
-        );
+fn list_action(
+    ir: &MessageCardIr,
+    warnings: &mut Vec<String>,
+    metrics: &mut RenderMetrics,
+) -> Option<Value> {
+    let mut sections = Vec::new();
+    let mut remaining = MAX_LIST_ROWS;
+    let mut truncated = false;
 
-        let buttons = build_buttons(ir, &mut warnings, &mut metrics);
-        if !buttons.is_empty() {
-            components.push(json!({
-            "type": "BUTTONS",
-            "buttons": buttons,
+    for element in &ir.elements {
+        let Element::Input {
+            label,
+            id,
+            choices,
+            kind: InputKind::Choice,
+            ..
+        } = element
+        else {
+            continue;
+        };
+        let input_id = id.as_deref().unwrap_or("choice");
+        let mut rows = Vec::new();
+        for choice in choices {
+            if remaining == 0 {
+                truncated = true;
+                break;
+            }
+            let data = json!({ input_id: choice.value });
+            let title = sanitize_text_for_tier(&choice.title, ir.tier, metrics);
+            rows.push(json!({
+                "id": reply_id(&data, warnings)?,
+                "title": limit(title.trim(), ROW_TITLE_LIMIT, metrics, warnings),
             }));
+            remaining -= 1;
         }
+        if !rows.is_empty() {
+            let title = label
+                .as_deref()
+                .map(|value| sanitize_text_for_tier(value, ir.tier, metrics))
+                .unwrap_or_else(|| "Options".into());
+            sections.push(json!({
+                "title": limit(title.trim(), SECTION_TITLE_LIMIT, metrics, warnings),
+                "rows": rows,
+            }));
+        }
+    }
 
-        let mut payload = Map::new();
-        payload.insert("type".into(), Value::String("WhatsAppTemplate".into()));
-        payload.insert("body".into(), Value::String(text));
-        if !components.is_empty() {
-            payload.insert("components".into(), Value::Array(components));
+    let mut rows = Vec::new();
+    for action in &ir.actions {
+        if let IrAction::Postback { title, data } | IrAction::Dialog { title, data } = action {
+            if remaining == 0 {
+                truncated = true;
+                break;
+            }
+            let title = sanitize_text_for_tier(title, ir.tier, metrics);
+            rows.push(json!({
+                "id": reply_id(data, warnings)?,
+                "title": limit(title.trim(), ROW_TITLE_LIMIT, metrics, warnings),
+            }));
+            remaining -= 1;
         }
+    }
+    if !rows.is_empty() {
+        sections.push(json!({
+            "title": "Actions",
+            "rows": rows,
+        }));
+    }
 
-        let mut render_output = RenderOutput::new(Value::Object(payload));
-        render_output.warnings = warnings;
-        render_output.limit_exceeded = metrics.limit_exceeded;
-        render_output.sanitized_count = metrics.sanitized_count;
-        render_output.url_blocked_count = metrics.url_blocked_count;
-        render_output
+    if truncated {
+        warnings.push("whatsapp.list_rows_truncated".into());
+        warn!(
+            target = "gsm.mcard.whatsapp",
+            "list rows truncated at WhatsApp limit"
+        );
+    }
+    // Section titles are only required when there is more than one section.
+    if sections.len() == 1
+        && let Some(section) = sections[0].as_object_mut()
+    {
+        section.remove("title");
+    }
+
+    Some(json!({
+        "button": LIST_BUTTON_TEXT,
+        "sections": sections,
+    }))
+}
+
+fn cta_url_action(
+    ir: &MessageCardIr,
+    warnings: &mut Vec<String>,
+    metrics: &mut RenderMetrics,
+) -> Option<Value> {
+    let (title, url) = ir.actions.iter().find_map(|action| match action {
+        IrAction::OpenUrl { title, url } => Some((title, url)),
+        _ => None,
+    })?;
+    let resolved = resolve_url_with_policy(&ir.meta, url, metrics, warnings)?;
+    let display = sanitize_text_for_tier(title, ir.tier, metrics);
+    Some(json!({
+        "name": "cta_url",
+        "parameters": {
+            "display_text": limit(display.trim(), BUTTON_TITLE_LIMIT, metrics, warnings),
+            "url": resolved,
+        }
+    }))
+}
+
+fn render_template(
+    ir: &MessageCardIr,
+    template: &TemplateMessage,
+    warnings: &mut Vec<String>,
+    metrics: &mut RenderMetrics,
+) -> Option<Value> {
+    let parameters: Vec<Value> = template
+        .parameters
+        .iter()
+        .map(|param| {
+            let bound = bind_template_param(ir, param, warnings);
+            let sanitized = sanitize_text_for_tier(&bound, ir.tier, metrics);
+            json!({ "type": "text", "text": sanitized })
+        })
+        .collect();
+
+    let mut body = json!({
+        "name": template.name,
+        "language": { "code": template.language },
+    });
+    if !parameters.is_empty() {
+        body["components"] = json!([{ "type": "body", "parameters": parameters }]);
+    }
+    Some(json!({
+        "type": "template",
+        "template": body,
+    }))
+}
+
+fn bind_template_param(ir: &MessageCardIr, param: &str, warnings: &mut Vec<String>) -> String {
+    let Some(binding) = param
+        .trim()
+        .strip_prefix("{{")
+        .and_then(|rest| rest.strip_suffix("}}"))
+        .map(str::trim)
+    else {
+        return param.to_string();
+    };
+
+    let resolved = match binding {
+        "title" => ir.head.title.clone(),
+        "text" => ir.head.text.clone(),
+        "footer" => ir.head.footer.clone(),
+        other => other.strip_prefix("fact:").and_then(|label| {
+            ir.elements.iter().find_map(|element| match element {
+                Element::FactSet { facts } => facts
+                    .iter()
+                    .find(|fact| fact.label == label.trim())
+                    .map(|fact| fact.value.clone()),
+                _ => None,
+            })
+        }),
+    };
+    resolved.unwrap_or_else(|| {
+        warnings.push("whatsapp.template_param_unresolved".into());
+        String::new()
+    })
+}
+
+/// Serialized postback used as the reply id; `None` when it exceeds WhatsApp's id limit so
+/// the caller falls back to the text rendering.
+fn reply_id(data: &Value, warnings: &mut Vec<String>) -> Option<String> {
+    let id = serde_json::to_string(data).unwrap_or_else(|_| "{}".into());
+    if id.len() > REPLY_ID_LIMIT {
+        warnings.push("whatsapp.reply_id_too_long".into());
+        return None;
+    }
+    Some(id)
+}
+
+/// Shortens interactive labels to WhatsApp's per-field limits, ending them with an ellipsis.
+fn limit(
+    text: &str,
+    max: usize,
+    metrics: &mut RenderMetrics,
+    warnings: &mut Vec<String>,
+) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    metrics.limit_exceeded = true;
+    if !warnings.iter().any(|w| w == "whatsapp.label_truncated") {
+        warnings.push("whatsapp.label_truncated".into());
     }
+    text.chars().take(max - 1).collect::<String>() + "…"
 }
 
 fn build_buttons(
@@ -171,7 +618,7 @@ fn build_buttons(
                     }));
                 }
             }
-            IrAction::Postback { title, data } => {
+            IrAction::Postback { title, data } | IrAction::Dialog { title, data } => {
                 let payload = serde_json::to_string(data).unwrap_or_else(|_| "{}".into());
                 let button_text = sanitize_text_for_tier(title, ir.tier, metrics);
                 buttons.push(json!({
@@ -3,6 +3,7 @@ use serde_json::Value;
 
 use crate::messaging_card::ir::MessageCardIr;
 use crate::messaging_card::types::{MessageCard, OauthCard, OauthPrompt, OauthProvider};
+use crate::provider_ops::SendOutput;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -40,6 +41,30 @@ impl RenderSpec {
     }
 }
 
+/// Replaces a previously sent card with `card`, keyed by the provider's id for that message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardUpdate {
+    pub provider_message_id: String,
+    pub card: MessageCardIr,
+}
+
+impl CardUpdate {
+    pub fn new(provider_message_id: impl Into<String>, card: MessageCardIr) -> Self {
+        Self {
+            provider_message_id: provider_message_id.into(),
+            card,
+        }
+    }
+
+    /// Returns `None` when the provider did not report an id for the sent message.
+    pub fn from_send_output(output: &SendOutput, card: MessageCardIr) -> Option<Self> {
+        output
+            .provider_message_id
+            .as_deref()
+            .map(|id| Self::new(id, card))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AuthRenderSpec {
     pub provider: OauthProvider,
@@ -54,9 +79,13 @@ pub struct AuthRenderSpec {
 
 impl AuthRenderSpec {
     pub fn from_card(card: &MessageCard, oauth: &OauthCard) -> Self {
-        let fallback_title = card
-            .title
-            .clone()
+        Self::with_title(card.title.as_deref(), oauth)
+    }
+
+    /// Builds the spec for an OAuth prompt whose fallback button is labelled `title`.
+    pub fn with_title(title: Option<&str>, oauth: &OauthCard) -> Self {
+        let fallback_title = title
+            .map(str::to_string)
             .unwrap_or_else(|| format!("Sign in with {}", oauth.provider.display_name()));
         let fallback_button = FallbackButton {
             title: fallback_title,
@@ -0,0 +1,311 @@
+//! Splits text that exceeds a platform limit into several messages.
+//!
+//! Chunks break on paragraph, line, sentence and word boundaries, in that order of preference.
+//! Cuts never land inside a link, inline code span, HTML tag or entity; fenced code blocks and
+//! open HTML tags are closed at the end of a chunk and reopened at the start of the next one.
+
+/// Markup the text is written in, which decides where a cut is safe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitSyntax {
+    Plain,
+    /// Markdown-like dialects (Slack mrkdwn, WhatsApp, CommonMark).
+    Markdown,
+    /// Telegram-style HTML.
+    Html,
+}
+
+/// Characters kept free in every chunk for the `(n/m)` continuation marker.
+pub const CONTINUATION_MARKER_RESERVE: usize = 10;
+
+const SEPARATORS: [&str; 6] = ["\n\n", "\n", ". ", "! ", "? ", " "];
+
+/// Splits `text` into chunks of at most `limit` characters without continuation markers.
+pub fn split_text(text: &str, limit: usize, syntax: SplitSyntax) -> Vec<String> {
+    if text.chars().count() <= limit || limit == 0 {
+        return vec![text.to_string()];
+    }
+    let mut chunks = Vec::new();
+    let mut rest = text.to_string();
+    while rest.chars().count() > limit {
+        let (chunk, next) = take_chunk(&rest, limit, syntax);
+        chunks.push(chunk);
+        rest = next;
+    }
+    if !rest.trim().is_empty() {
+        chunks.push(rest);
+    }
+    chunks
+}
+
+/// Splits `text` into separate messages of at most `limit` characters, each ending with a
+/// `(n/m)` continuation marker. Text within the limit is returned unchanged.
+pub fn split_message(text: &str, limit: usize, syntax: SplitSyntax) -> Vec<String> {
+    if text.chars().count() <= limit {
+        return vec![text.to_string()];
+    }
+    let mut chunks = split_text(
+        text,
+        limit.saturating_sub(CONTINUATION_MARKER_RESERVE),
+        syntax,
+    );
+    let total = chunks.len();
+    if total > 1 {
+        for (index, chunk) in chunks.iter_mut().enumerate() {
+            chunk.push_str(&format!("\n({}/{total})", index + 1));
+        }
+    }
+    chunks
+}
+
+/// Cuts the first chunk off `text`, returning it and the remainder (with reopened markup).
+fn take_chunk(text: &str, limit: usize, syntax: SplitSyntax) -> (String, String) {
+    let mut budget = limit;
+    loop {
+        let window_end = byte_index(text, budget);
+        let scan = scan(text, window_end, syntax);
+        let cut = best_cut(&text[..window_end], &scan.safe).unwrap_or(window_end);
+        let state = &scan.open_at[cut];
+        let head = text[..cut].trim_end();
+        let chunk = format!("{head}{}", state.closers());
+        let fits = chunk.chars().count() <= limit;
+        // Retry with a smaller window when reclosed markup pushes the chunk over the limit.
+        if fits || budget <= limit / 2 {
+            let tail = text[cut..].trim_start();
+            return (chunk, format!("{}{tail}", state.openers()));
+        }
+        budget -= chunk.chars().count() - limit;
+    }
+}
+
+/// Picks the latest safe cut for the most preferred separator, ignoring separators in the first
+/// quarter of the window so chunks are not needlessly short.
+fn best_cut(window: &str, safe: &[bool]) -> Option<usize> {
+    let floor = window.len() / 4;
+    for separator in SEPARATORS {
+        let found = window
+            .match_indices(separator)
+            .map(|(index, _)| index + separator.trim_end().len().max(1))
+            .filter(|&cut| cut > floor && safe[cut])
+            .last();
+        if found.is_some() {
+            return found;
+        }
+    }
+    (1..=window.len())
+        .rev()
+        .find(|&cut| window.is_char_boundary(cut) && safe[cut])
+}
+
+fn byte_index(text: &str, chars: usize) -> usize {
+    text.char_indices()
+        .nth(chars)
+        .map(|(index, _)| index)
+        .unwrap_or(text.len())
+}
+
+#[derive(Clone, Default)]
+struct OpenState {
+    /// Language of an open fenced code block, if any.
+    fence: Option<String>,
+    /// Open HTML tags as `(name, opening tag)`.
+    tags: Vec<(String, String)>,
+}
+
+impl OpenState {
+    fn closers(&self) -> String {
+        let mut out = String::new();
+        if self.fence.is_some() {
+            out.push_str("\n```");
+        }
+        for (name, _) in self.tags.iter().rev() {
+            out.push_str(&format!("</{name}>"));
+        }
+        out
+    }
+
+    fn openers(&self) -> String {
+        let mut out = String::new();
+        if let Some(lang) = &self.fence {
+            out.push_str(&format!("```{lang}\n"));
+        }
+        for (_, open) in &self.tags {
+            out.push_str(open);
+        }
+        out
+    }
+}
+
+struct Scan {
+    /// Whether a cut at each byte offset keeps inline markup intact.
+    safe: Vec<bool>,
+    /// Markup that must be closed and reopened when cutting at each byte offset.
+    open_at: Vec<OpenState>,
+}
+
+/// Scans `text` up to byte offset `end`, looking past `end` only to match closing markup.
+fn scan(text: &str, end: usize, syntax: SplitSyntax) -> Scan {
+    let mut safe = vec![true; end + 1];
+    let mut open_at = vec![OpenState::default(); end + 1];
+    let mut state = OpenState::default();
+    let bytes = text.as_bytes();
+
+    let mut in_code = false;
+    let mut in_angle = false;
+    let mut bracket_depth = 0usize;
+    let mut in_url = false;
+    let mut in_entity = false;
+    let mut index = 0;
+
+    while index < end {
+        let inline_open = in_code || in_angle || bracket_depth > 0 || in_url || in_entity;
+        safe[index] = text.is_char_boundary(index) && !inline_open;
+        open_at[index] = state.clone();
+
+        let rest = &text[index..];
+        let mut step = rest.chars().next().map(char::len_utf8).unwrap_or(1);
+        match syntax {
+            SplitSyntax::Plain => {}
+            SplitSyntax::Markdown => {
+                if bytes[index] == b'\\' {
+                    step += rest[1..].chars().next().map(char::len_utf8).unwrap_or(0);
+                } else if let Some(after) = rest.strip_prefix("```") {
+                    if state.fence.is_some() {
+                        state.fence = None;
+                    } else {
+                        let lang = after.lines().next().unwrap_or_default().trim();
+                        state.fence = Some(lang.to_string());
+                    }
+                    step = 3;
+                } else if state.fence.is_none() {
+                    match bytes[index] {
+                        b'`' if in_code || closes_on_line(rest, '`') => in_code = !in_code,
+                        _ if in_code => {}
+                        b'<' if closes_on_line(rest, '>') => in_angle = true,
+                        b'>' => in_angle = false,
+                        b'[' if closes_on_line(rest, ']') => bracket_depth += 1,
+                        b']' if bracket_depth > 0 => {
+                            bracket_depth -= 1;
+                            if bracket_depth == 0 && rest[1..].starts_with('(') {
+                                in_url = true;
+                            }
+                        }
+                        b')' if in_url => in_url = false,
+                        _ => {}
+                    }
+                }
+            }
+            SplitSyntax::Html => match bytes[index] {
+                b'<' => {
+                    let tag_end = rest.find('>').map(|close| close + 1).unwrap_or(rest.len());
+                    update_tags(&mut state.tags, &rest[..tag_end]);
+                    for offset in 1..tag_end.min(end - index) {
+                        safe[index + offset] = false;
+                        open_at[index + offset] = state.clone();
+                    }
+                    step = tag_end;
+                }
+                b'&' => {
+                    in_entity = rest
+                        .chars()
+                        .skip(1)
+                        .take(10)
+                        .take_while(|c| !c.is_whitespace())
+                        .any(|c| c == ';');
+                }
+                b';' => in_entity = false,
+                _ => {}
+            },
+        }
+        index += step.max(1);
+    }
+    // A token that straddles `end` (an HTML tag or escape) makes the window edge unsafe.
+    safe[end] = index == end && !(in_code || in_angle || bracket_depth > 0 || in_url || in_entity);
+    open_at[end] = state;
+    Scan { safe, open_at }
+}
+
+/// Whether `rest` (starting at an opening delimiter) closes with `close` on the same line.
+fn closes_on_line(rest: &str, close: char) -> bool {
+    rest[1..]
+        .split('\n')
+        .next()
+        .is_some_and(|line| line.contains(close))
+}
+
+fn update_tags(tags: &mut Vec<(String, String)>, tag: &str) {
+    let inner = tag.trim_start_matches('<').trim_end_matches('>');
+    if let Some(closing) = inner.strip_prefix('/') {
+        let name = closing.trim().to_ascii_lowercase();
+        if let Some(position) = tags.iter().rposition(|(open, _)| *open == name) {
+            tags.truncate(position);
+        }
+    } else if !inner.ends_with('/') {
+        let name = inner
+            .split(|c: char| c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !name.is_empty() {
+            tags.push((name, tag.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_paragraph_then_sentence_boundaries() {
+        let text = "First paragraph here.\n\nSecond one. It has two sentences.";
+        assert_eq!(
+            split_text(text, 30, SplitSyntax::Plain),
+            vec![
+                "First paragraph here.",
+                "Second one.",
+                "It has two sentences."
+            ]
+        );
+    }
+
+    #[test]
+    fn html_tags_are_closed_and_reopened() {
+        let text = format!("<b>{}</b>", "word ".repeat(20).trim_end());
+        let chunks = split_text(&text, 40, SplitSyntax::Html);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 40, "{chunk}");
+            assert!(
+                chunk.starts_with("<b>") && chunk.ends_with("</b>"),
+                "{chunk}"
+            );
+        }
+    }
+
+    #[test]
+    fn markdown_links_and_fences_stay_intact() {
+        let text = "Please read the [notes](https://ex.com/n) today";
+        assert_eq!(
+            split_text(text, 30, SplitSyntax::Markdown),
+            vec!["Please read the", "[notes](https://ex.com/n)", "today"]
+        );
+
+        let code = format!("```rust\n{}\n```", "let x = 1;\n".repeat(8).trim_end());
+        for chunk in split_text(&code, 50, SplitSyntax::Markdown) {
+            assert!(chunk.starts_with("```"), "{chunk}");
+            assert!(chunk.ends_with("```"), "{chunk}");
+        }
+    }
+
+    #[test]
+    fn messages_carry_continuation_markers() {
+        let text = "alpha beta gamma delta ".repeat(10);
+        let chunks = split_message(&text, 60, SplitSyntax::Plain);
+        let total = chunks.len();
+        assert!(total > 1);
+        for (index, chunk) in chunks.iter().enumerate() {
+            assert!(chunk.chars().count() <= 60);
+            assert!(chunk.ends_with(&format!("({}/{total})", index + 1)));
+        }
+    }
+}
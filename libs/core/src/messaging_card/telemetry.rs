@@ -1,5 +1,14 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::messaging_card::ir::{Element, IrAction, MessageCardIr};
 use crate::messaging_card::tier::Tier;
 
+/// Key under which renderers stamp the [`Correlation`] into object postback payloads.
+pub const CORRELATION_KEY: &str = "_gsm";
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TelemetryEvent {
     Rendered {
@@ -17,6 +26,152 @@ pub enum TelemetryEvent {
         from: Tier,
         to: Tier,
     },
+    /// A button on a rendered card was clicked.
+    ActionInvoked {
+        card_id: String,
+        action_id: String,
+        platform: String,
+        latency_ms: u64,
+    },
+    /// A card with inputs was submitted.
+    InputSubmitted {
+        card_id: String,
+        action_id: String,
+        platform: String,
+        latency_ms: u64,
+    },
+    /// An interaction arrived after the card's time to live.
+    CardExpired {
+        card_id: String,
+        platform: String,
+        age_ms: u64,
+    },
+    RenderFailed {
+        card_id: Option<String>,
+        platform: String,
+        reason: String,
+    },
+}
+
+/// Identifies the card and action a postback came from, and when the card was rendered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Correlation {
+    #[serde(rename = "c")]
+    pub card_id: String,
+    #[serde(rename = "a")]
+    pub action_id: String,
+    /// Render time in milliseconds since the Unix epoch.
+    #[serde(rename = "t")]
+    pub rendered_at_ms: u64,
+    /// Set when the card has inputs, so the postback carries submitted values.
+    #[serde(rename = "i", default, skip_serializing_if = "std::ops::Not::not")]
+    pub submits_inputs: bool,
+}
+
+impl Correlation {
+    /// Milliseconds elapsed between rendering and `now_ms`.
+    pub fn age_ms(&self, now_ms: u64) -> u64 {
+        now_ms.saturating_sub(self.rendered_at_ms)
+    }
+
+    /// Compact string form stamped into postbacks: `card~action~time[~i]`, with the render time
+    /// in base 36. Kept short because some platforms cap callback data (Telegram at 64 bytes).
+    pub fn to_stamp(&self) -> String {
+        let mut stamp = format!(
+            "{}~{}~{}",
+            self.card_id,
+            self.action_id,
+            to_base36(self.rendered_at_ms)
+        );
+        if self.submits_inputs {
+            stamp.push_str("~i");
+        }
+        stamp
+    }
+
+    /// Parses [`Correlation::to_stamp`] output. Card ids may contain `~`, so fields are read
+    /// from the end.
+    pub fn from_stamp(stamp: &str) -> Option<Self> {
+        let (rest, submits_inputs) = match stamp.strip_suffix("~i") {
+            Some(rest) => (rest, true),
+            None => (stamp, false),
+        };
+        let mut fields = rest.rsplitn(3, '~');
+        let rendered_at_ms = u64::from_str_radix(fields.next()?, 36).ok()?;
+        let action_id = fields.next()?.to_string();
+        let card_id = fields.next()?.to_string();
+        Some(Self {
+            card_id,
+            action_id,
+            rendered_at_ms,
+            submits_inputs,
+        })
+    }
+}
+
+fn to_base36(mut value: u64) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut digits = Vec::new();
+    loop {
+        digits.push(DIGITS[(value % 36) as usize]);
+        value /= 36;
+        if value == 0 {
+            break;
+        }
+    }
+    digits
+        .iter()
+        .rev()
+        .map(|&digit| char::from(digit))
+        .collect()
+}
+
+/// Stamps a [`Correlation`] into every object postback payload of `ir`. Cards without
+/// `meta.card_id` are left untouched so anonymous renders stay deterministic.
+///
+/// Action ids are the action's position on the card.
+pub fn stamp_correlation(ir: &mut MessageCardIr, rendered_at_ms: u64) {
+    let Some(card_id) = ir.meta.card_id.clone() else {
+        return;
+    };
+    let submits_inputs = ir
+        .elements
+        .iter()
+        .any(|element| matches!(element, Element::Input { .. }));
+    for (index, action) in ir.actions.iter_mut().enumerate() {
+        let (IrAction::Postback { data, .. } | IrAction::Dialog { data, .. }) = action else {
+            continue;
+        };
+        let Some(map) = data.as_object_mut() else {
+            continue;
+        };
+        let correlation = Correlation {
+            card_id: card_id.clone(),
+            action_id: index.to_string(),
+            rendered_at_ms,
+            submits_inputs,
+        };
+        map.insert(
+            CORRELATION_KEY.into(),
+            Value::String(correlation.to_stamp()),
+        );
+    }
+}
+
+/// Removes the correlation stamp from an inbound postback payload and returns it. Stamps in the
+/// older object form are still accepted.
+pub fn take_correlation(data: &mut Value) -> Option<Correlation> {
+    match data.as_object_mut()?.remove(CORRELATION_KEY)? {
+        Value::String(stamp) => Correlation::from_stamp(&stamp),
+        stamp => serde_json::from_value(stamp).ok(),
+    }
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
 }
 
 pub trait TelemetryHook: Send + Sync {
@@ -69,6 +224,45 @@ impl<'a> CardTelemetry<'a> {
             native_count,
         });
     }
+
+    /// Emits `ActionInvoked` or `InputSubmitted` for a postback from a correlated card.
+    pub fn interaction(&self, platform: &str, correlation: &Correlation, now_ms: u64) {
+        let card_id = correlation.card_id.clone();
+        let action_id = correlation.action_id.clone();
+        let platform = platform.to_string();
+        let latency_ms = correlation.age_ms(now_ms);
+        self.hook.emit(if correlation.submits_inputs {
+            TelemetryEvent::InputSubmitted {
+                card_id,
+                action_id,
+                platform,
+                latency_ms,
+            }
+        } else {
+            TelemetryEvent::ActionInvoked {
+                card_id,
+                action_id,
+                platform,
+                latency_ms,
+            }
+        });
+    }
+
+    pub fn expired(&self, platform: &str, card_id: &str, age_ms: u64) {
+        self.hook.emit(TelemetryEvent::CardExpired {
+            card_id: card_id.to_string(),
+            platform: platform.to_string(),
+            age_ms,
+        });
+    }
+
+    pub fn render_failed(&self, platform: &str, card_id: Option<&str>, reason: impl Into<String>) {
+        self.hook.emit(TelemetryEvent::RenderFailed {
+            card_id: card_id.map(str::to_string),
+            platform: platform.to_string(),
+            reason: reason.into(),
+        });
+    }
 }
 
 #[cfg(test)]
@@ -102,4 +296,79 @@ mod tests {
         let events = hook.events.lock().unwrap();
         assert_eq!(events.len(), 2);
     }
+
+    #[test]
+    fn correlation_stamp_round_trips_through_postbacks() {
+        use crate::messaging_card::ir::{InputKind, MessageCardIrBuilder};
+        use serde_json::json;
+
+        let mut ir = MessageCardIrBuilder::default()
+            .input(None, InputKind::Text, Some("comment".into()), Vec::new())
+            .open_url("Docs", "https://example.com")
+            .postback("Approve", json!({"decision": "approve"}))
+            .postback("Plain", json!("plain"))
+            .build();
+        stamp_correlation(&mut ir, 1_000);
+        let IrAction::Postback { data, .. } = &ir.actions[1] else {
+            panic!("expected postback");
+        };
+        assert_eq!(
+            data,
+            &json!({"decision": "approve"}),
+            "no card id, no stamp"
+        );
+
+        ir.meta.card_id = Some("expense-42".into());
+        stamp_correlation(&mut ir, 1_000);
+        assert_eq!(
+            ir.actions[2],
+            IrAction::Postback {
+                title: "Plain".into(),
+                data: json!("plain"),
+            },
+            "only object payloads are stamped"
+        );
+        let IrAction::Postback { data, .. } = &ir.actions[1] else {
+            panic!("expected postback");
+        };
+        let mut inbound = data.clone();
+        let correlation = take_correlation(&mut inbound).expect("stamp");
+        assert_eq!(inbound, json!({"decision": "approve"}));
+        assert_eq!(correlation.card_id, "expense-42");
+        assert_eq!(correlation.action_id, "1");
+        assert!(correlation.submits_inputs);
+
+        assert_eq!(data[CORRELATION_KEY], json!("expense-42~1~rs~i"));
+
+        let hook = TestTelemetry::new();
+        CardTelemetry::new(&hook).interaction("slack", &correlation, 1_250);
+        assert_eq!(
+            hook.events.lock().unwrap()[0],
+            TelemetryEvent::InputSubmitted {
+                card_id: "expense-42".into(),
+                action_id: "1".into(),
+                platform: "slack".into(),
+                latency_ms: 250,
+            }
+        );
+    }
+
+    #[test]
+    fn stamps_parse_card_ids_with_separators_and_object_form() {
+        let correlation = Correlation {
+            card_id: "a~b".into(),
+            action_id: "3".into(),
+            rendered_at_ms: 1_760_000_000_000,
+            submits_inputs: false,
+        };
+        assert_eq!(
+            Correlation::from_stamp(&correlation.to_stamp()),
+            Some(correlation)
+        );
+
+        let mut legacy = serde_json::json!({"ok": true, "_gsm": {"c": "x", "a": "0", "t": 5}});
+        let parsed = take_correlation(&mut legacy).expect("object stamp");
+        assert_eq!(parsed.card_id, "x");
+        assert_eq!(parsed.rendered_at_ms, 5);
+    }
 }
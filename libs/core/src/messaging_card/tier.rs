@@ -1,28 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-/// Represents how expressive a rendered message card can be on a target platform.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
-#[serde(rename_all = "lowercase")]
-pub enum Tier {
-    #[default]
-    Basic,
-    Advanced,
-    Premium,
-}
-
-impl Tier {
-    pub fn as_str(self) -> &'static str {
-        match self {
-            Tier::Basic => "basic",
-            Tier::Advanced => "advanced",
-            Tier::Premium => "premium",
-        }
-    }
-
-    pub fn clamp(self, target: Tier) -> Tier {
-        self.min(target)
-    }
-}
+pub use crate::render_plan::Tier;
 
 /// Configuration describing how the engine should pick a tier for a platform.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -1,10 +1,14 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+pub use crate::types::{MessageCardKind, OauthCard, OauthPrompt, OauthProvider};
+
 fn default_true() -> bool {
     true
 }
 
+/// Flat card shape consumed by `MessageCardEngine`. It converts losslessly into the canonical
+/// [`crate::types::MessageCard`], which also accepts this shape when deserializing.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MessageCard {
     #[serde(default)]
@@ -59,78 +63,6 @@ pub enum Action {
     PostBack { title: String, data: Value },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
-#[serde(rename_all = "snake_case")]
-pub enum MessageCardKind {
-    #[default]
-    Standard,
-    Oauth,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum OauthProvider {
-    Microsoft,
-    Google,
-    Github,
-    Custom,
-}
-
-impl OauthProvider {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            OauthProvider::Microsoft => "microsoft",
-            OauthProvider::Google => "google",
-            OauthProvider::Github => "github",
-            OauthProvider::Custom => "custom",
-        }
-    }
-
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            OauthProvider::Microsoft => "Microsoft",
-            OauthProvider::Google => "Google",
-            OauthProvider::Github => "GitHub",
-            OauthProvider::Custom => "External Provider",
-        }
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum OauthPrompt {
-    None,
-    Consent,
-    Login,
-}
-
-impl OauthPrompt {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            OauthPrompt::None => "none",
-            OauthPrompt::Consent => "consent",
-            OauthPrompt::Login => "login",
-        }
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct OauthCard {
-    pub provider: OauthProvider,
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub scopes: Vec<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub resource: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub prompt: Option<OauthPrompt>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub start_url: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub connection_name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<Value>,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -6,12 +6,18 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 use greentic_types::pack_manifest::{ExtensionInline, ExtensionRef, PackManifest};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
 
+use crate::messaging_card::tier::Tier;
 use crate::path_safety::normalize_under_root;
+use crate::provider_capabilities::ProviderCapabilitiesV2;
 
 pub const INGRESS_EXTENSION_ID: &str = "messaging.provider_ingress.v1";
 pub const OAUTH_EXTENSION_ID: &str = "messaging.oauth.v1";
 pub const SUBSCRIPTIONS_EXTENSION_ID: &str = "messaging.subscriptions.v1";
+pub const RENDERER_EXTENSION_ID: &str = "messaging.renderer.v1";
+pub const CAPABILITIES_EXTENSION_ID: &str = "messaging.capabilities.v1";
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RuntimeRef {
@@ -57,16 +63,36 @@ pub struct SubscriptionsProviderDecl {
     pub renewal_window_hours: Option<u32>,
 }
 
+/// A card renderer shipped as a WASM component, keyed by the platform it renders for.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RendererProviderDecl {
+    pub runtime: RuntimeRef,
+    #[serde(default)]
+    pub target_tier: Tier,
+    /// Allows the renderer to take over a platform that has a built-in renderer. The built-in
+    /// one is still used when the pack renderer fails.
+    #[serde(default)]
+    pub replace_builtin: bool,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct ProviderExtensionsRegistry {
     pub ingress: BTreeMap<String, IngressProviderDecl>,
     pub oauth: BTreeMap<String, OAuthProviderDecl>,
     pub subscriptions: BTreeMap<String, SubscriptionsProviderDecl>,
+    pub renderers: BTreeMap<String, RendererProviderDecl>,
+    /// Full capability declarations keyed by provider (adapter) name. Declarations may be v1 or
+    /// v2 documents; v1 ones are upgraded.
+    pub capabilities: BTreeMap<String, ProviderCapabilitiesV2>,
 }
 
 impl ProviderExtensionsRegistry {
     pub fn is_empty(&self) -> bool {
-        self.ingress.is_empty() && self.oauth.is_empty() && self.subscriptions.is_empty()
+        self.ingress.is_empty()
+            && self.oauth.is_empty()
+            && self.subscriptions.is_empty()
+            && self.renderers.is_empty()
+            && self.capabilities.is_empty()
     }
 }
 
@@ -98,6 +124,12 @@ struct SubscriptionsPayload {
     providers: BTreeMap<String, SubscriptionsProviderDecl>,
 }
 
+#[derive(Debug, Deserialize)]
+struct RendererPayload {
+    #[serde(flatten)]
+    providers: BTreeMap<String, RendererProviderDecl>,
+}
+
 pub fn load_provider_extensions_from_pack_files(
     root: &Path,
     paths: &[PathBuf],
@@ -118,6 +150,8 @@ fn merge_registry(target: &mut ProviderExtensionsRegistry, incoming: ProviderExt
     target.ingress.extend(incoming.ingress);
     target.oauth.extend(incoming.oauth);
     target.subscriptions.extend(incoming.subscriptions);
+    target.renderers.extend(incoming.renderers);
+    target.capabilities.extend(incoming.capabilities);
 }
 
 fn extensions_from_pack_file(root: &Path, path: &Path) -> Result<ProviderExtensionsRegistry> {
@@ -183,6 +217,12 @@ fn apply_extensions(
     if let Some(payload) = extract_subscriptions(extensions) {
         registry.subscriptions.extend(payload);
     }
+    if let Some(payload) = extract_renderers(extensions) {
+        registry.renderers.extend(payload);
+    }
+    if let Some(payload) = extract_capabilities(extensions) {
+        registry.capabilities.extend(payload);
+    }
 }
 
 fn extract_ingress(
@@ -220,3 +260,40 @@ fn extract_subscriptions(
     let payload: SubscriptionsPayload = serde_json::from_value(value.clone()).ok()?;
     Some(payload.providers)
 }
+
+fn extract_renderers(
+    extensions: &BTreeMap<String, ExtensionRef>,
+) -> Option<BTreeMap<String, RendererProviderDecl>> {
+    let entry = extensions.get(RENDERER_EXTENSION_ID)?;
+    let inline = entry.inline.as_ref()?;
+    let ExtensionInline::Other(value) = inline else {
+        return None;
+    };
+    let payload: RendererPayload = serde_json::from_value(value.clone()).ok()?;
+    Some(payload.providers)
+}
+
+/// Parses each provider's declaration on its own, so one malformed entry is logged and skipped
+/// without losing the rest of the pack's declarations.
+fn extract_capabilities(
+    extensions: &BTreeMap<String, ExtensionRef>,
+) -> Option<BTreeMap<String, ProviderCapabilitiesV2>> {
+    let entry = extensions.get(CAPABILITIES_EXTENSION_ID)?;
+    let inline = entry.inline.as_ref()?;
+    let ExtensionInline::Other(Value::Object(providers)) = inline else {
+        return None;
+    };
+    let capabilities = providers
+        .iter()
+        .filter_map(
+            |(provider, value)| match ProviderCapabilitiesV2::from_json(value) {
+                Ok(caps) => Some((provider.clone(), caps)),
+                Err(err) => {
+                    warn!(provider = %provider, error = %err, "ignoring invalid pack capabilities");
+                    None
+                }
+            },
+        )
+        .collect();
+    Some(capabilities)
+}
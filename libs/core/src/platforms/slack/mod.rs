@@ -1,2 +1,3 @@
 pub mod sender;
+pub mod views;
 pub mod workspace;
@@ -0,0 +1,167 @@
+use serde_json::{Map, Value};
+
+/// Parsed Slack `view_submission` interaction payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlackViewSubmission {
+    pub callback_id: String,
+    pub view_id: Option<String>,
+    pub user_id: Option<String>,
+    /// State round-tripped through the view's `private_metadata`.
+    pub state: Option<Value>,
+    /// Input values keyed by input `block_id`.
+    pub inputs: Map<String, Value>,
+}
+
+impl SlackViewSubmission {
+    /// Parses an interaction payload; returns `None` unless it is a `view_submission`.
+    pub fn from_payload(payload: &Value) -> Option<Self> {
+        if payload.get("type").and_then(Value::as_str) != Some("view_submission") {
+            return None;
+        }
+        let view = payload.get("view")?;
+        let callback_id = view
+            .get("callback_id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let state = view
+            .get("private_metadata")
+            .and_then(Value::as_str)
+            .filter(|raw| !raw.is_empty())
+            .map(|raw| serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.into())));
+
+        let mut inputs = Map::new();
+        if let Some(blocks) = view
+            .get("state")
+            .and_then(|state| state.get("values"))
+            .and_then(Value::as_object)
+        {
+            for (block_id, actions) in blocks {
+                let Some(actions) = actions.as_object() else {
+                    continue;
+                };
+                // Each input block holds exactly one element; take the first value present.
+                if let Some(value) = actions.values().find_map(element_value) {
+                    inputs.insert(block_id.clone(), value);
+                }
+            }
+        }
+
+        Some(Self {
+            callback_id,
+            view_id: view.get("id").and_then(Value::as_str).map(str::to_string),
+            user_id: payload
+                .get("user")
+                .and_then(|user| user.get("id"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            state,
+            inputs,
+        })
+    }
+
+    /// Merges the round-tripped state with the submitted inputs, mirroring how Adaptive Card
+    /// `Action.Submit` merges action data with input values.
+    pub fn postback_data(&self) -> Value {
+        let mut data = match &self.state {
+            Some(Value::Object(map)) => map.clone(),
+            Some(other) => {
+                let mut map = Map::new();
+                map.insert("state".into(), other.clone());
+                map
+            }
+            None => Map::new(),
+        };
+        for (key, value) in &self.inputs {
+            data.insert(key.clone(), value.clone());
+        }
+        Value::Object(data)
+    }
+}
+
+fn element_value(element: &Value) -> Option<Value> {
+    let kind = element.get("type").and_then(Value::as_str)?;
+    match kind {
+        "plain_text_input" | "number_input" | "email_text_input" | "url_text_input" => {
+            Some(element.get("value").cloned().unwrap_or(Value::Null))
+        }
+        "static_select" | "external_select" | "radio_buttons" => Some(
+            element
+                .get("selected_option")
+                .and_then(|opt| opt.get("value"))
+                .cloned()
+                .unwrap_or(Value::Null),
+        ),
+        "multi_static_select" | "multi_external_select" | "checkboxes" => Some(Value::Array(
+            element
+                .get("selected_options")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|opt| opt.get("value").cloned())
+                .collect(),
+        )),
+        "users_select" => element.get("selected_user").cloned(),
+        "conversations_select" => element.get("selected_conversation").cloned(),
+        "channels_select" => element.get("selected_channel").cloned(),
+        "datepicker" => element.get("selected_date").cloned(),
+        "timepicker" => element.get("selected_time").cloned(),
+        _ => element.get("value").cloned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_submission_into_postback_data() {
+        let payload = json!({
+            "type": "view_submission",
+            "user": { "id": "U123" },
+            "view": {
+                "id": "V1",
+                "callback_id": "expense_form",
+                "private_metadata": "{\"flow\":\"expenses\",\"step\":2}",
+                "state": {
+                    "values": {
+                        "amount": {
+                            "amount_action": { "type": "plain_text_input", "value": "42" }
+                        },
+                        "choice": {
+                            "choice_select": {
+                                "type": "static_select",
+                                "selected_option": { "value": "yes" }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let submission = SlackViewSubmission::from_payload(&payload).expect("submission");
+        assert_eq!(submission.callback_id, "expense_form");
+        assert_eq!(submission.user_id.as_deref(), Some("U123"));
+        assert_eq!(
+            submission.postback_data(),
+            json!({"flow": "expenses", "step": 2, "amount": "42", "choice": "yes"})
+        );
+    }
+
+    #[test]
+    fn ignores_other_interactions() {
+        let payload = json!({ "type": "block_actions", "actions": [] });
+        assert!(SlackViewSubmission::from_payload(&payload).is_none());
+    }
+
+    #[test]
+    fn non_json_metadata_is_kept_as_string_state() {
+        let payload = json!({
+            "type": "view_submission",
+            "view": { "callback_id": "cb", "private_metadata": "opaque", "state": { "values": {} } }
+        });
+        let submission = SlackViewSubmission::from_payload(&payload).expect("submission");
+        assert_eq!(submission.postback_data(), json!({"state": "opaque"}));
+    }
+}
@@ -0,0 +1,244 @@
+use serde_json::{Map, Value, json};
+
+/// Bot Framework `invoke` activities Teams sends for dialogs, message extensions and
+/// universal actions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TeamsInvoke {
+    /// `task/fetch`: a dialog was requested; reply with a task module.
+    TaskFetch {
+        data: Value,
+    },
+    /// `task/submit`: the dialog form was submitted.
+    TaskSubmit {
+        data: Value,
+    },
+    /// `composeExtension/query`: search-based message extension.
+    MessageExtensionQuery {
+        command_id: String,
+        parameters: Vec<(String, String)>,
+    },
+    /// `composeExtension/fetchTask`: action-based message extension asking for a dialog.
+    MessageExtensionFetchTask {
+        command_id: String,
+    },
+    /// `composeExtension/submitAction`: action-based message extension dialog submitted.
+    MessageExtensionSubmit {
+        command_id: String,
+        data: Value,
+    },
+    /// `adaptiveCard/action`: `Action.Execute` universal action.
+    AdaptiveCardAction {
+        verb: Option<String>,
+        data: Value,
+    },
+    Other {
+        name: String,
+        value: Value,
+    },
+}
+
+impl TeamsInvoke {
+    /// Parses an invoke activity; returns `None` for other activity types.
+    pub fn from_activity(activity: &Value) -> Option<Self> {
+        let obj = activity.as_object()?;
+        if obj.get("type").and_then(Value::as_str) != Some("invoke") {
+            return None;
+        }
+        let name = obj.get("name").and_then(Value::as_str)?;
+        let value = obj.get("value").cloned().unwrap_or(Value::Null);
+
+        let parsed = match name {
+            "task/fetch" => TeamsInvoke::TaskFetch {
+                data: submit_data(&value),
+            },
+            "task/submit" => TeamsInvoke::TaskSubmit {
+                data: submit_data(&value),
+            },
+            "composeExtension/query" => TeamsInvoke::MessageExtensionQuery {
+                command_id: command_id(&value),
+                parameters: value
+                    .get("parameters")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|param| {
+                        let name = param.get("name")?.as_str()?.to_string();
+                        let value = match param.get("value")? {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        };
+                        Some((name, value))
+                    })
+                    .collect(),
+            },
+            "composeExtension/fetchTask" => TeamsInvoke::MessageExtensionFetchTask {
+                command_id: command_id(&value),
+            },
+            "composeExtension/submitAction" => TeamsInvoke::MessageExtensionSubmit {
+                command_id: command_id(&value),
+                data: submit_data(&value),
+            },
+            "adaptiveCard/action" => {
+                let action = value.get("action").cloned().unwrap_or(Value::Null);
+                TeamsInvoke::AdaptiveCardAction {
+                    verb: action
+                        .get("verb")
+                        .and_then(Value::as_str)
+                        .map(|s| s.to_string()),
+                    data: action.get("data").cloned().unwrap_or(Value::Null),
+                }
+            }
+            other => TeamsInvoke::Other {
+                name: other.to_string(),
+                value,
+            },
+        };
+        Some(parsed)
+    }
+
+    /// Invoke name as sent by Teams.
+    pub fn name(&self) -> &str {
+        match self {
+            TeamsInvoke::TaskFetch { .. } => "task/fetch",
+            TeamsInvoke::TaskSubmit { .. } => "task/submit",
+            TeamsInvoke::MessageExtensionQuery { .. } => "composeExtension/query",
+            TeamsInvoke::MessageExtensionFetchTask { .. } => "composeExtension/fetchTask",
+            TeamsInvoke::MessageExtensionSubmit { .. } => "composeExtension/submitAction",
+            TeamsInvoke::AdaptiveCardAction { .. } => "adaptiveCard/action",
+            TeamsInvoke::Other { name, .. } => name,
+        }
+    }
+
+    /// Postback data carried by the invoke, in the same shape the card action was rendered
+    /// with (the `msteams` routing marker removed).
+    pub fn postback_data(&self) -> Option<&Value> {
+        match self {
+            TeamsInvoke::TaskFetch { data }
+            | TeamsInvoke::TaskSubmit { data }
+            | TeamsInvoke::MessageExtensionSubmit { data, .. }
+            | TeamsInvoke::AdaptiveCardAction { data, .. } => Some(data),
+            _ => None,
+        }
+    }
+}
+
+/// Wraps an invoke response body in the `{status, body}` envelope Bot Framework expects.
+pub fn invoke_response(status: u16, body: Value) -> Value {
+    json!({
+        "status": status,
+        "body": body,
+    })
+}
+
+fn command_id(value: &Value) -> String {
+    value
+        .get("commandId")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn submit_data(value: &Value) -> Value {
+    match value.get("data") {
+        Some(Value::Object(map)) => strip_msteams(map),
+        Some(other) => other.clone(),
+        None => Value::Null,
+    }
+}
+
+/// Removes the routing marker; scalar data the renderer nested under `data` (flagged with
+/// `msteams.wrapped`) is returned as the original value.
+fn strip_msteams(map: &Map<String, Value>) -> Value {
+    let mut rest = map.clone();
+    let marker = rest.remove("msteams");
+    let wrapped = marker
+        .as_ref()
+        .and_then(|m| m.get("wrapped"))
+        .and_then(Value::as_bool)
+        == Some(true);
+    if wrapped {
+        return rest.remove("data").unwrap_or(Value::Null);
+    }
+    Value::Object(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_task_fetch_and_strips_marker() {
+        let activity = json!({
+            "type": "invoke",
+            "name": "task/fetch",
+            "value": {
+                "data": { "msteams": { "type": "task/fetch" }, "form": "expense" }
+            }
+        });
+        let invoke = TeamsInvoke::from_activity(&activity).expect("invoke");
+        assert_eq!(
+            invoke,
+            TeamsInvoke::TaskFetch {
+                data: json!({"form": "expense"})
+            }
+        );
+        assert_eq!(invoke.postback_data(), Some(&json!({"form": "expense"})));
+    }
+
+    #[test]
+    fn task_fetch_restores_wrapped_scalar_data() {
+        let activity = json!({
+            "type": "invoke",
+            "name": "task/fetch",
+            "value": {
+                "data": { "msteams": { "type": "task/fetch", "wrapped": true }, "data": "expense" }
+            }
+        });
+        let invoke = TeamsInvoke::from_activity(&activity).expect("invoke");
+        assert_eq!(invoke.postback_data(), Some(&json!("expense")));
+    }
+
+    #[test]
+    fn parses_message_extension_query_parameters() {
+        let activity = json!({
+            "type": "invoke",
+            "name": "composeExtension/query",
+            "value": {
+                "commandId": "searchTickets",
+                "parameters": [{ "name": "query", "value": "printer" }]
+            }
+        });
+        let invoke = TeamsInvoke::from_activity(&activity).expect("invoke");
+        assert_eq!(
+            invoke,
+            TeamsInvoke::MessageExtensionQuery {
+                command_id: "searchTickets".into(),
+                parameters: vec![("query".into(), "printer".into())],
+            }
+        );
+    }
+
+    #[test]
+    fn parses_universal_action() {
+        let activity = json!({
+            "type": "invoke",
+            "name": "adaptiveCard/action",
+            "value": { "action": { "type": "Action.Execute", "verb": "approve", "data": {"id": 7} } }
+        });
+        let invoke = TeamsInvoke::from_activity(&activity).expect("invoke");
+        assert_eq!(invoke.name(), "adaptiveCard/action");
+        assert_eq!(
+            invoke,
+            TeamsInvoke::AdaptiveCardAction {
+                verb: Some("approve".into()),
+                data: json!({"id": 7}),
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_non_invoke_activities() {
+        let activity = json!({ "type": "message", "text": "hi" });
+        assert!(TeamsInvoke::from_activity(&activity).is_none());
+    }
+}
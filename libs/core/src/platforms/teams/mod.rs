@@ -1,5 +1,7 @@
 pub mod conversations;
+pub mod invoke;
 pub mod sender;
 
 pub use conversations::*;
+pub use invoke::*;
 pub use sender::*;
@@ -1,5 +1,8 @@
+use std::collections::BTreeSet;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -56,6 +59,12 @@ pub enum CapabilitiesError {
     BadVersion,
     #[error("max_buttons_per_row cannot exceed max_total_buttons")]
     ButtonsRowExceedsTotal,
+    #[error("unsupported capabilities version {0:?}")]
+    UnsupportedVersion(String),
+    #[error("invalid MIME type {0:?}")]
+    InvalidMimeType(String),
+    #[error("invalid capabilities: {0}")]
+    Invalid(String),
 }
 
 impl ProviderCapabilitiesV1 {
@@ -75,3 +84,219 @@ impl ProviderCapabilitiesV1 {
         Ok(())
     }
 }
+
+/// Input kinds a provider can collect natively.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InputKindV2 {
+    Text,
+    Choice,
+    Number,
+    Date,
+    Time,
+    Toggle,
+}
+
+/// Size and type limits for files a provider accepts as uploads.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(default, rename_all = "snake_case")]
+pub struct FileUploadsV2 {
+    pub max_bytes: Option<u64>,
+    /// Accepted MIME types such as `image/*`; an empty list accepts any type.
+    pub mime_types: Vec<String>,
+}
+
+impl FileUploadsV2 {
+    pub fn accepts(&self, mime_type: &str, bytes: u64) -> bool {
+        if self.max_bytes.is_some_and(|max| bytes > max) {
+            return false;
+        }
+        self.mime_types.is_empty()
+            || self.mime_types.iter().any(|allowed| {
+                allowed == mime_type
+                    || allowed.strip_suffix("/*").is_some_and(|prefix| {
+                        mime_type
+                            .split_once('/')
+                            .is_some_and(|(kind, _)| kind == prefix)
+                    })
+            })
+    }
+}
+
+/// Outbound rate limits published by the provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(default, rename_all = "snake_case")]
+pub struct RateLimitsV2 {
+    pub messages_per_second: Option<u32>,
+    pub messages_per_minute: Option<u32>,
+    pub burst: Option<u32>,
+}
+
+/// Capabilities schema v2. Unlike v1, every limit lives in `limits` once, and every field has a
+/// conservative default so declarations only list what a provider supports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default, rename_all = "snake_case")]
+pub struct ProviderCapabilitiesV2 {
+    pub version: String,
+    pub supports_adaptive_cards: bool,
+    pub supports_markdown: bool,
+    pub supports_html: bool,
+    pub supports_images: bool,
+    pub supports_buttons: bool,
+    pub supports_threads: bool,
+    pub supports_reactions: bool,
+    pub supports_edits: bool,
+    pub supports_deletes: bool,
+    pub supports_typing: bool,
+    pub supports_ephemeral: bool,
+    pub supports_modals: bool,
+    pub supports_mentions: bool,
+    pub supports_direct_messages: bool,
+    /// `None` when the provider does not accept file uploads.
+    pub file_uploads: Option<FileUploadsV2>,
+    pub inputs: BTreeSet<InputKindV2>,
+    pub limits: ProviderLimitsV1,
+    pub rate_limits: RateLimitsV2,
+}
+
+impl Default for ProviderCapabilitiesV2 {
+    fn default() -> Self {
+        Self {
+            version: "v2".to_string(),
+            supports_adaptive_cards: false,
+            supports_markdown: false,
+            supports_html: false,
+            supports_images: false,
+            supports_buttons: false,
+            supports_threads: false,
+            supports_reactions: false,
+            supports_edits: false,
+            supports_deletes: false,
+            supports_typing: false,
+            supports_ephemeral: false,
+            supports_modals: false,
+            supports_mentions: false,
+            supports_direct_messages: false,
+            file_uploads: None,
+            inputs: BTreeSet::new(),
+            limits: ProviderLimitsV1::default(),
+            rate_limits: RateLimitsV2::default(),
+        }
+    }
+}
+
+impl From<ProviderCapabilitiesV1> for ProviderCapabilitiesV2 {
+    /// Keeps every v1 flag and folds the top-level limits into `limits`; when both are set the
+    /// `limits` value wins, as it does in `ProviderCapabilitiesV1::validate`. Adaptive cards
+    /// imply text and choice inputs.
+    fn from(v1: ProviderCapabilitiesV1) -> Self {
+        let inputs = if v1.supports_adaptive_cards {
+            BTreeSet::from([InputKindV2::Text, InputKindV2::Choice])
+        } else {
+            BTreeSet::new()
+        };
+        Self {
+            supports_adaptive_cards: v1.supports_adaptive_cards,
+            supports_markdown: v1.supports_markdown,
+            supports_html: v1.supports_html,
+            supports_images: v1.supports_images,
+            supports_buttons: v1.supports_buttons,
+            supports_threads: v1.supports_threads,
+            inputs,
+            limits: ProviderLimitsV1 {
+                max_text_len: v1.limits.max_text_len.or(v1.max_text_len),
+                max_payload_bytes: v1.limits.max_payload_bytes.or(v1.max_payload_bytes),
+                max_actions: v1.limits.max_actions.or(v1.max_actions),
+                max_buttons_per_row: v1.limits.max_buttons_per_row.or(v1.max_buttons_per_row),
+                max_total_buttons: v1.limits.max_total_buttons.or(v1.max_total_buttons),
+            },
+            ..Default::default()
+        }
+    }
+}
+
+impl ProviderCapabilitiesV2 {
+    /// Parses a v1 or v2 document, upgrading v1 and validating the result.
+    pub fn from_json(value: &Value) -> Result<Self, CapabilitiesError> {
+        let caps = match value.get("version").and_then(Value::as_str) {
+            Some("v1") => {
+                let v1: ProviderCapabilitiesV1 = serde_json::from_value(value.clone())
+                    .map_err(|err| CapabilitiesError::Invalid(err.to_string()))?;
+                v1.validate()?;
+                Self::from(v1)
+            }
+            Some("v2") => serde_json::from_value(value.clone())
+                .map_err(|err| CapabilitiesError::Invalid(err.to_string()))?,
+            other => {
+                return Err(CapabilitiesError::UnsupportedVersion(
+                    other.unwrap_or_default().to_string(),
+                ));
+            }
+        };
+        caps.validate()?;
+        Ok(caps)
+    }
+
+    /// JSON Schema for v2 documents.
+    pub fn json_schema() -> Value {
+        serde_json::to_value(schemars::schema_for!(ProviderCapabilitiesV2))
+            .expect("schema serializes")
+    }
+
+    pub fn validate(&self) -> Result<(), CapabilitiesError> {
+        if self.version != "v2" {
+            return Err(CapabilitiesError::UnsupportedVersion(self.version.clone()));
+        }
+        if let (Some(row), Some(total)) = (
+            self.limits.max_buttons_per_row,
+            self.limits.max_total_buttons,
+        ) && row > total
+        {
+            return Err(CapabilitiesError::ButtonsRowExceedsTotal);
+        }
+        if let Some(uploads) = &self.file_uploads
+            && let Some(bad) = uploads
+                .mime_types
+                .iter()
+                .find(|mime| !is_mime_pattern(mime))
+        {
+            return Err(CapabilitiesError::InvalidMimeType(bad.clone()));
+        }
+        Ok(())
+    }
+
+    pub fn supports_input(&self, kind: InputKindV2) -> bool {
+        self.inputs.contains(&kind)
+    }
+
+    /// The v1 view used by the render planner. Limits are written to both the top-level fields
+    /// and `limits`.
+    pub fn to_v1(&self) -> ProviderCapabilitiesV1 {
+        ProviderCapabilitiesV1 {
+            version: "v1".to_string(),
+            supports_adaptive_cards: self.supports_adaptive_cards,
+            supports_markdown: self.supports_markdown,
+            supports_html: self.supports_html,
+            supports_images: self.supports_images,
+            supports_buttons: self.supports_buttons,
+            supports_threads: self.supports_threads,
+            max_text_len: self.limits.max_text_len,
+            max_payload_bytes: self.limits.max_payload_bytes,
+            max_actions: self.limits.max_actions,
+            max_buttons_per_row: self.limits.max_buttons_per_row,
+            max_total_buttons: self.limits.max_total_buttons,
+            limits: self.limits.clone(),
+        }
+    }
+}
+
+fn is_mime_pattern(mime: &str) -> bool {
+    match mime.split_once('/') {
+        Some((kind, subtype)) => {
+            !kind.is_empty() && kind != "*" && !subtype.is_empty() && !subtype.contains('/')
+        }
+        None => false,
+    }
+}
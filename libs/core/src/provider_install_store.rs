@@ -70,7 +70,7 @@ impl ProviderInstallRouting {
     }
 }
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum ProviderInstallError {
     #[error("missing provider install {provider_id}/{install_id}")]
     MissingInstall {
@@ -79,6 +79,10 @@ pub enum ProviderInstallError {
     },
     #[error("invalid webhook signature (header {header})")]
     InvalidSignature { header: String },
+    #[error("webhook timestamp outside the allowed window (header {header})")]
+    StaleSignature { header: String },
+    #[error("webhook request was already delivered")]
+    ReplayedRequest,
     #[error("missing provider install secret {key}")]
     MissingSecret { key: String },
     #[error("missing provider install config {key}")]
@@ -1,8 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
+use anyhow::{Context, Result};
+use greentic_pack::messaging::MessagingAdapterCapabilities;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
-use crate::provider_capabilities::ProviderCapabilitiesV1;
+use crate::adapter_registry::{AdapterRegistry, load_adapters_from_pack_files};
+use crate::pack_extensions::{
+    ProviderExtensionsRegistry, load_provider_extensions_from_pack_files,
+};
+use crate::provider_capabilities::{FileUploadsV2, ProviderCapabilitiesV1, ProviderCapabilitiesV2};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -12,16 +20,68 @@ pub enum CapsSource {
     Override,
 }
 
+impl CapsSource {
+    /// Rank used to resolve conflicting registrations: overrides beat capabilities reported
+    /// by the provider at runtime, which beat what its pack declares.
+    pub fn precedence(self) -> u8 {
+        match self {
+            CapsSource::FromPackManifest => 0,
+            CapsSource::FromProviderCall => 1,
+            CapsSource::Override => 2,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProviderRecord {
     pub id: String,
     pub version: String,
     pub caps_source: CapsSource,
     pub capabilities: ProviderCapabilitiesV1,
+    /// Full v2 view of the same capabilities; records registered from v1 are upgraded.
+    #[serde(default)]
+    pub capabilities_v2: ProviderCapabilitiesV2,
     #[serde(default)]
     pub encoder_ref: Option<String>,
 }
 
+/// Operator-supplied capabilities that replace whatever packs declare for a provider.
+///
+/// ```
+/// use gsm_core::ProviderCapsOverrides;
+///
+/// let overrides: ProviderCapsOverrides = serde_json::from_str(
+///     r#"{"providers": {"slack-main": {"version": "v1", "supports_adaptive_cards": false,
+///         "supports_markdown": true, "supports_html": false, "supports_images": true,
+///         "supports_buttons": true, "supports_threads": true, "max_text_len": 3000,
+///         "max_payload_bytes": null, "max_actions": 5, "max_buttons_per_row": null,
+///         "max_total_buttons": null}}}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(overrides.providers["slack-main"].max_actions, Some(5));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderCapsOverrides {
+    #[serde(default)]
+    pub providers: BTreeMap<String, ProviderCapabilitiesV1>,
+}
+
+impl ProviderCapsOverrides {
+    /// Loads a JSON file such as `{"providers": {"slack-main": {...}}}`. Every entry must
+    /// pass `ProviderCapabilitiesV1::validate`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read provider caps from {}", path.display()))?;
+        let overrides: Self = serde_json::from_str(&raw)
+            .with_context(|| format!("invalid provider caps {}", path.display()))?;
+        for (id, caps) in &overrides.providers {
+            caps.validate()
+                .with_context(|| format!("invalid capabilities for {id} in {}", path.display()))?;
+        }
+        Ok(overrides)
+    }
+}
+
 #[derive(Default)]
 pub struct ProviderCapsRegistry {
     providers: HashMap<String, ProviderRecord>,
@@ -35,6 +95,23 @@ impl ProviderCapsRegistry {
         }
     }
 
+    /// Builds the registry for loaded packs: capabilities derived from adapter features, then
+    /// full declarations from the `messaging.capabilities.v1` extension, then `overrides`.
+    pub fn from_packs(
+        adapters: &AdapterRegistry,
+        extensions: &ProviderExtensionsRegistry,
+        overrides: &ProviderCapsOverrides,
+    ) -> Self {
+        let mut registry = Self::new();
+        registry.register_adapters(adapters);
+        registry.register_pack_extensions(extensions);
+        registry.apply_overrides(overrides);
+        registry
+    }
+
+    /// Registers a provider unless a record from a higher-precedence `CapsSource` already
+    /// exists. Returns whether the registration took effect; within the same source the
+    /// latest registration wins.
     pub fn register_provider(
         &mut self,
         id: impl Into<String>,
@@ -42,23 +119,192 @@ impl ProviderCapsRegistry {
         caps_source: CapsSource,
         capabilities: ProviderCapabilitiesV1,
         encoder_ref: Option<String>,
-    ) {
-        let id = id.into();
+    ) -> bool {
+        let upgraded = ProviderCapabilitiesV2::from(capabilities.clone());
+        self.insert(
+            id.into(),
+            version.into(),
+            caps_source,
+            capabilities,
+            upgraded,
+            encoder_ref,
+        )
+    }
+
+    /// Like [`register_provider`](Self::register_provider), for v2 capabilities.
+    pub fn register_provider_v2(
+        &mut self,
+        id: impl Into<String>,
+        version: impl Into<String>,
+        caps_source: CapsSource,
+        capabilities: ProviderCapabilitiesV2,
+        encoder_ref: Option<String>,
+    ) -> bool {
+        self.insert(
+            id.into(),
+            version.into(),
+            caps_source,
+            capabilities.to_v1(),
+            capabilities,
+            encoder_ref,
+        )
+    }
+
+    fn insert(
+        &mut self,
+        id: String,
+        version: String,
+        caps_source: CapsSource,
+        capabilities: ProviderCapabilitiesV1,
+        capabilities_v2: ProviderCapabilitiesV2,
+        encoder_ref: Option<String>,
+    ) -> bool {
+        if let Some(existing) = self.providers.get(&id)
+            && existing.caps_source.precedence() > caps_source.precedence()
+        {
+            return false;
+        }
         let record = ProviderRecord {
             id: id.clone(),
-            version: version.into(),
+            version,
             caps_source,
             capabilities,
+            capabilities_v2,
             encoder_ref,
         };
         self.providers.insert(id, record);
+        true
+    }
+
+    /// Registers every egress-capable adapter that declares capabilities in its pack.
+    pub fn register_adapters(&mut self, adapters: &AdapterRegistry) {
+        for adapter in adapters.all() {
+            if !adapter.allows_egress() {
+                continue;
+            }
+            let Some(declared) = adapter.capabilities.as_ref() else {
+                continue;
+            };
+            self.register_provider_v2(
+                adapter.name.as_str(),
+                adapter.pack_version.as_str(),
+                CapsSource::FromPackManifest,
+                caps_from_adapter_features(declared),
+                None,
+            );
+        }
+    }
+
+    /// Registers full capability declarations shipped through the `messaging.capabilities.v1`
+    /// extension. They replace feature-derived capabilities for the same provider; entries that
+    /// fail validation are skipped.
+    pub fn register_pack_extensions(&mut self, extensions: &ProviderExtensionsRegistry) {
+        for (id, caps) in &extensions.capabilities {
+            if let Err(err) = caps.validate() {
+                warn!(provider = %id, error = %err, "ignoring invalid pack capabilities");
+                continue;
+            }
+            let version = self.version_of(id);
+            self.register_provider_v2(
+                id.as_str(),
+                version,
+                CapsSource::FromPackManifest,
+                caps.clone(),
+                None,
+            );
+        }
+    }
+
+    pub fn apply_overrides(&mut self, overrides: &ProviderCapsOverrides) {
+        for (id, caps) in &overrides.providers {
+            let version = self.version_of(id);
+            self.register_provider(
+                id.as_str(),
+                version,
+                CapsSource::Override,
+                caps.clone(),
+                None,
+            );
+        }
+    }
+
+    fn version_of(&self, provider_id: &str) -> String {
+        self.providers
+            .get(provider_id)
+            .map(|record| record.version.clone())
+            .unwrap_or_else(|| "unknown".into())
     }
 
     pub fn get_caps(&self, provider_id: &str) -> Option<&ProviderCapabilitiesV1> {
         self.providers.get(provider_id).map(|p| &p.capabilities)
     }
 
+    pub fn get_caps_v2(&self, provider_id: &str) -> Option<&ProviderCapabilitiesV2> {
+        self.providers.get(provider_id).map(|p| &p.capabilities_v2)
+    }
+
     pub fn get(&self, provider_id: &str) -> Option<&ProviderRecord> {
         self.providers.get(provider_id)
     }
+
+    /// All records, sorted by provider id.
+    pub fn records(&self) -> Vec<&ProviderRecord> {
+        let mut records: Vec<_> = self.providers.values().collect();
+        records.sort_by(|a, b| a.id.cmp(&b.id));
+        records
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
+
+/// Maps the coarse `features` list of a pack adapter onto capability flags. Features without a
+/// matching flag (`text`, ...) are ignored.
+pub fn caps_from_adapter_features(
+    declared: &MessagingAdapterCapabilities,
+) -> ProviderCapabilitiesV2 {
+    let mut caps = ProviderCapabilitiesV2::default();
+    for feature in &declared.features {
+        match feature.to_ascii_lowercase().as_str() {
+            "cards" | "adaptive_cards" => {
+                caps.supports_adaptive_cards = true;
+                caps.supports_buttons = true;
+            }
+            "buttons" => caps.supports_buttons = true,
+            "markdown" => caps.supports_markdown = true,
+            "html" => caps.supports_html = true,
+            "images" | "media" => caps.supports_images = true,
+            "attachments" | "files" => {
+                caps.supports_images = true;
+                caps.file_uploads.get_or_insert_with(FileUploadsV2::default);
+            }
+            "threads" => caps.supports_threads = true,
+            "reactions" => caps.supports_reactions = true,
+            "edits" => caps.supports_edits = true,
+            "deletes" => caps.supports_deletes = true,
+            "typing" => caps.supports_typing = true,
+            "ephemeral" => caps.supports_ephemeral = true,
+            "modals" => caps.supports_modals = true,
+            "mentions" => caps.supports_mentions = true,
+            "direct_messages" => caps.supports_direct_messages = true,
+            _ => {}
+        }
+    }
+    caps
+}
+
+/// Loads adapters and provider extensions from `paths` and builds the effective registry.
+pub fn load_provider_caps_from_pack_files(
+    root: &Path,
+    paths: &[PathBuf],
+    overrides: &ProviderCapsOverrides,
+) -> Result<ProviderCapsRegistry> {
+    let adapters = load_adapters_from_pack_files(root, paths)?;
+    let extensions = load_provider_extensions_from_pack_files(root, paths)?;
+    Ok(ProviderCapsRegistry::from_packs(
+        &adapters,
+        &extensions,
+        overrides,
+    ))
 }
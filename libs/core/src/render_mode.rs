@@ -1,18 +1,60 @@
 //! Render mode abstraction to toggle between legacy rendering and the planner path.
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
 use crate::{
+    messaging_card::{MessageCardEngine, RenderSpec, ir::MessageCardIr},
     provider_capabilities::ProviderCapabilitiesV1,
     provider_registry::ProviderCapsRegistry,
-    render_plan::{RenderPlan, RenderTier, RenderWarning},
-    render_planner::{PlannerCard, plan_render, planner_policy},
+    render_plan::{RENDER_PLAN_META_KEY, RenderPlan, RenderWarning, Tier},
+    render_planner::{PlannerPolicy, plan_card, remove_blocked_urls},
+    types::OutMessage,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum RenderMode {
     #[default]
     Legacy,
     Planned,
 }
 
+/// Render mode per tenant, with a default for tenants that are not listed. Planning is opt-in:
+/// unless configured otherwise every tenant renders in legacy mode.
+///
+/// ```
+/// use gsm_core::{RenderMode, RenderModeConfig};
+///
+/// let config: RenderModeConfig =
+///     serde_json::from_str(r#"{"tenants": {"acme": "planned"}}"#).unwrap();
+/// assert_eq!(config.mode_for("acme"), RenderMode::Planned);
+/// assert_eq!(config.mode_for("globex"), RenderMode::Legacy);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenderModeConfig {
+    #[serde(default)]
+    pub default: RenderMode,
+    #[serde(default)]
+    pub tenants: BTreeMap<String, RenderMode>,
+}
+
+impl RenderModeConfig {
+    pub fn mode_for(&self, tenant: &str) -> RenderMode {
+        self.tenants.get(tenant).copied().unwrap_or(self.default)
+    }
+
+    /// Loads a JSON config file such as `{"default": "planned", "tenants": {"acme": "legacy"}}`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read render modes from {}", path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("invalid render mode config {}", path.display()))
+    }
+}
+
 /// Result of a render decision.
 #[derive(Debug, Clone, PartialEq)]
 pub struct RenderOutcome {
@@ -22,24 +64,24 @@ pub struct RenderOutcome {
 }
 
 impl RenderOutcome {
-    pub fn tier(&self) -> RenderTier {
-        self.plan
-            .as_ref()
-            .map(|p| p.tier)
-            .unwrap_or(RenderTier::TierD)
+    pub fn tier(&self) -> Tier {
+        self.plan.as_ref().map(|p| p.tier).unwrap_or(Tier::Basic)
     }
 }
 
 /// Decide how to render based on render mode and provider capabilities.
 ///
 /// Legacy mode bypasses the planner and returns no plan.
-/// Planned mode pulls capabilities from the registry (or a supplied fallback) and runs the planner.
+/// Planned mode pulls capabilities from the registry (or a supplied fallback) and runs the planner
+/// under `policy`. A provider whose capabilities are unknown gets no plan either, rather than
+/// one built against capabilities that support nothing.
 pub fn compute_render_outcome(
     mode: RenderMode,
     provider_id: &str,
-    card: &PlannerCard,
+    ir: &MessageCardIr,
     registry: &ProviderCapsRegistry,
     caps_fallback: Option<&ProviderCapabilitiesV1>,
+    policy: &PlannerPolicy,
 ) -> RenderOutcome {
     match mode {
         RenderMode::Legacy => RenderOutcome {
@@ -48,12 +90,14 @@ pub fn compute_render_outcome(
             warnings: Vec::new(),
         },
         RenderMode::Planned => {
-            let caps = registry
-                .get_caps(provider_id)
-                .or(caps_fallback)
-                .cloned()
-                .unwrap_or_default();
-            let plan = plan_render(card, &caps, &planner_policy());
+            let Some(caps) = registry.get_caps(provider_id).or(caps_fallback) else {
+                return RenderOutcome {
+                    mode,
+                    plan: None,
+                    warnings: Vec::new(),
+                };
+            };
+            let plan = plan_card(ir, caps, policy);
             let warnings = plan.warnings.clone();
             RenderOutcome {
                 mode,
@@ -63,3 +107,47 @@ pub fn compute_render_outcome(
         }
     }
 }
+
+/// Plans `out.card` for `provider_id` and stores the plan under `meta.render_plan`, where
+/// translators and adapters pick it up. URLs the policy does not allow are removed from the
+/// card first, in every mode and before anything can fail, so no path sends them. Legacy mode,
+/// messages without a card, OAuth prompts and providers with unknown capabilities get no plan
+/// and return `None`.
+pub fn attach_render_plan(
+    out: &mut OutMessage,
+    mode: RenderMode,
+    provider_id: &str,
+    engine: &MessageCardEngine,
+    registry: &ProviderCapsRegistry,
+    caps_fallback: Option<&ProviderCapabilitiesV1>,
+    policy: &PlannerPolicy,
+) -> Result<Option<RenderOutcome>> {
+    let Some(card) = out.card.as_mut() else {
+        return Ok(None);
+    };
+    let removed = remove_blocked_urls(card, policy);
+    if mode == RenderMode::Legacy || registry.get_caps(provider_id).or(caps_fallback).is_none() {
+        return Ok(None);
+    }
+    let RenderSpec::Card(ir) = engine.render_card_spec(card)? else {
+        return Ok(None);
+    };
+    let mut outcome =
+        compute_render_outcome(mode, provider_id, &ir, registry, caps_fallback, policy);
+    if removed > 0 {
+        let warning = RenderWarning {
+            code: "url_blocked".into(),
+            message: Some(format!("{removed} blocked URLs removed from the card")),
+            path: Some("/body".into()),
+        };
+        if let Some(plan) = &mut outcome.plan {
+            plan.warnings.push(warning.clone());
+        }
+        outcome.warnings.push(warning);
+    }
+    if let Some(plan) = &outcome.plan {
+        out.meta
+            .insert(RENDER_PLAN_META_KEY.into(), serde_json::to_value(plan)?);
+    }
+    Ok(Some(outcome))
+}
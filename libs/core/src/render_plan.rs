@@ -3,33 +3,124 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-/// Render tiers are coarse buckets for capability and downgrade decisions.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum RenderTier {
-    TierA,
-    TierB,
-    TierC,
-    TierD,
+/// `OutMessage.meta` key under which egress attaches the plan for the message's card.
+pub const RENDER_PLAN_META_KEY: &str = "render_plan";
+
+/// How expressive a rendered message can be. This is the one tier vocabulary shared by card
+/// IR, renderers, `DowngradeContext`, telemetry and `RenderPlan`:
+///
+/// - `Premium`: full cards, including inputs and dialogs.
+/// - `Advanced`: cards with images, fact sets and postback buttons, but no inputs.
+/// - `Basic`: text and links only.
+///
+/// Plans written before the tiers were unified used `tier_a`..`tier_d`; they still deserialize,
+/// with `tier_a` as `Premium`, `tier_b` and `tier_c` as `Advanced`, and `tier_d` as `Basic`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    #[default]
+    #[serde(alias = "tier_d")]
+    Basic,
+    #[serde(alias = "tier_b", alias = "tier_c")]
+    Advanced,
+    #[serde(alias = "tier_a")]
+    Premium,
+}
+
+impl Tier {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Tier::Basic => "basic",
+            Tier::Advanced => "advanced",
+            Tier::Premium => "premium",
+        }
+    }
+
+    pub fn clamp(self, target: Tier) -> Tier {
+        self.min(target)
+    }
 }
 
 /// A renderer-independent plan describing what to render and any warnings produced.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct RenderPlan {
     /// Target capability tier.
-    pub tier: RenderTier,
+    pub tier: Tier,
     /// Human-friendly summary text (optional).
     pub summary_text: Option<String>,
     /// Action identifiers/labels the host can map to platform-specific actions.
     pub actions: Vec<String>,
     /// Attachment references (URLs or opaque identifiers) to include in the render.
     pub attachments: Vec<String>,
+    /// Per-element decisions, in card order (elements first, then actions).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub elements: Vec<ElementDecision>,
     /// Deterministic warnings emitted during planning.
     pub warnings: Vec<RenderWarning>,
     /// Opaque debug payload for diagnostics.
     pub debug: Option<Value>,
 }
 
+impl RenderPlan {
+    /// Decisions for elements and actions that survive planning, possibly downgraded.
+    pub fn kept(&self) -> impl Iterator<Item = &ElementDecision> {
+        self.elements
+            .iter()
+            .filter(|decision| decision.disposition != ElementDisposition::Drop)
+    }
+
+    /// Decisions for elements and actions removed from the render.
+    pub fn dropped(&self) -> impl Iterator<Item = &ElementDecision> {
+        self.elements
+            .iter()
+            .filter(|decision| decision.disposition == ElementDisposition::Drop)
+    }
+
+    /// Decision recorded for a path such as `/elements/1` or `/actions/0`.
+    pub fn decision(&self, path: &str) -> Option<&ElementDecision> {
+        self.elements.iter().find(|decision| decision.path == path)
+    }
+}
+
+/// What the planner decided for a single card element or action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ElementDecision {
+    /// JSON pointer–like path into the card IR, e.g. `/elements/2` or `/actions/0`.
+    pub path: String,
+    /// Element or action type as named in the IR (`text`, `fact_set`, `open_url`, ...).
+    pub kind: String,
+    pub disposition: ElementDisposition,
+    /// Lowest tier that renders the element as authored.
+    pub tier: Tier,
+    /// Warning code explaining a downgrade or drop.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ElementDisposition {
+    /// Rendered as authored.
+    Keep,
+    /// Rendered in a simpler form (plain text, link, flattened facts).
+    Downgrade,
+    /// Left out of the render.
+    Drop,
+}
+
 /// Structured warning emitted during render planning.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct RenderWarning {
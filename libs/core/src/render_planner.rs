@@ -1,18 +1,143 @@
-//! Deterministic render planner prototype (capability-driven tiers).
+//! Deterministic, capability-driven render planner.
+//!
+//! `plan_card` walks a `MessageCardIr` and decides, per element and action, whether the provider
+//! renders it as authored, in a simpler form, or not at all. `apply_plan` turns those decisions
+//! into the IR handed to renderers.
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
 
 use crate::{
+    messaging_card::{
+        ir::{Element, Head, IrAction, MessageCardIr},
+        markdown,
+    },
     provider_capabilities::ProviderCapabilitiesV1,
-    render_plan::{RenderPlan, RenderTier, RenderWarning},
+    render_plan::{ElementDecision, ElementDisposition, RenderPlan, RenderWarning, Tier},
+    types::{CardAction, CardBlock, MessageCard},
 };
 
-/// Minimal policy placeholder for future use.
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
-pub struct PlannerPolicy;
+/// Planning rules for a tenant (and optionally a single provider). Every field has a default,
+/// so policy files only list what they change.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlannerPolicy {
+    /// Use native card features where the provider has them, or always plan plain text.
+    pub prefer: RenderPreference,
+    /// Upper bound on buttons, applied on top of the provider's own limits.
+    pub max_buttons: Option<u32>,
+    /// Show images the provider cannot display as links instead of dropping them.
+    pub images_as_links: bool,
+    /// URL prefixes allowed in actions and images. Empty allows every URL that is not denied.
+    /// Entries match on scheme, host and port, then on a path prefix; see [`url_matches`].
+    pub url_allow: Vec<String>,
+    /// URL prefixes that are always removed, even when they also match `url_allow`.
+    pub url_deny: Vec<String>,
+    /// How text over the provider's length limit is shortened.
+    pub truncation: TruncationStrategy,
+}
+
+impl PlannerPolicy {
+    pub fn url_allowed(&self, url: &str) -> bool {
+        if self.url_deny.iter().any(|prefix| url_matches(url, prefix)) {
+            return false;
+        }
+        self.url_allow.is_empty() || self.url_allow.iter().any(|prefix| url_matches(url, prefix))
+    }
+}
+
+/// Whether `url` falls under the policy entry `prefix`: the scheme, host and port must be equal
+/// and the path must start with the entry's path, so `https://example.com` matches
+/// `https://example.com/docs` but not `https://example.com.evil.net/`. A URL or entry that does
+/// not parse never matches.
+pub fn url_matches(url: &str, prefix: &str) -> bool {
+    let (Ok(url), Ok(prefix)) = (Url::parse(url), Url::parse(prefix)) else {
+        return false;
+    };
+    url.scheme() == prefix.scheme()
+        && url.host() == prefix.host()
+        && url.port_or_known_default() == prefix.port_or_known_default()
+        && url.path().starts_with(prefix.path())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderPreference {
+    #[default]
+    Native,
+    /// Plan as if the provider only supported plain text.
+    Text,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    /// Cut at the limit.
+    #[default]
+    Hard,
+    /// Cut one character early and end with `…`.
+    Ellipsis,
+    /// Cut at the last whitespace before the limit, falling back to a hard cut.
+    WordBoundary,
+}
 
 /// Convenience constructor for a default policy.
 pub fn planner_policy() -> PlannerPolicy {
-    PlannerPolicy
+    PlannerPolicy::default()
+}
+
+/// Planner policies per tenant and provider. The most specific match applies as a whole:
+/// tenant + provider, then tenant, then `default`.
+///
+/// ```
+/// use gsm_core::{PlannerPolicies, RenderPreference};
+///
+/// let policies: PlannerPolicies = serde_json::from_str(
+///     r#"{"tenants": {"acme": {"providers": {"sms": {"prefer": "text"}}}}}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(policies.policy_for("acme", "sms").prefer, RenderPreference::Text);
+/// assert_eq!(policies.policy_for("acme", "slack").prefer, RenderPreference::Native);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannerPolicies {
+    #[serde(default)]
+    pub default: PlannerPolicy,
+    #[serde(default)]
+    pub tenants: BTreeMap<String, TenantPlannerPolicies>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantPlannerPolicies {
+    #[serde(default)]
+    pub default: Option<PlannerPolicy>,
+    #[serde(default)]
+    pub providers: BTreeMap<String, PlannerPolicy>,
+}
+
+impl PlannerPolicies {
+    pub fn policy_for(&self, tenant: &str, provider: &str) -> &PlannerPolicy {
+        let Some(tenant) = self.tenants.get(tenant) else {
+            return &self.default;
+        };
+        tenant
+            .providers
+            .get(provider)
+            .or(tenant.default.as_ref())
+            .unwrap_or(&self.default)
+    }
+
+    /// Loads policies from a JSON file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read planner policies from {}", path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("invalid planner policies {}", path.display()))
+    }
 }
 
 /// Simplified card input for planning tests.
@@ -32,36 +157,116 @@ pub struct PlannerAction {
     pub url: Option<String>,
 }
 
-/// Pure deterministic planner that picks a tier based on capabilities.
+impl PlannerCard {
+    /// Card IR equivalent: text goes to the head, images become elements and actions without
+    /// a URL become postbacks.
+    pub fn to_ir(&self) -> MessageCardIr {
+        MessageCardIr {
+            head: Head {
+                title: self.title.clone(),
+                text: self.text.clone(),
+                footer: None,
+            },
+            elements: self
+                .images
+                .iter()
+                .map(|url| Element::Image {
+                    url: url.clone(),
+                    alt: None,
+                })
+                .collect(),
+            actions: self
+                .actions
+                .iter()
+                .map(|action| match &action.url {
+                    Some(url) => IrAction::OpenUrl {
+                        title: action.title.clone(),
+                        url: url.clone(),
+                    },
+                    None => IrAction::Postback {
+                        title: action.title.clone(),
+                        data: Value::Null,
+                    },
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Plans a `PlannerCard` by way of its IR; see [`plan_card`].
 pub fn plan_render(
     card: &PlannerCard,
     caps: &ProviderCapabilitiesV1,
-    _policy: &PlannerPolicy,
+    policy: &PlannerPolicy,
+) -> RenderPlan {
+    plan_card(&card.to_ir(), caps, policy)
+}
+
+/// Pure deterministic planner: decides every element and action against the provider's
+/// capabilities and `policy`, builds a plain-text summary and picks the overall tier. The
+/// policy is recorded in `debug.policy`.
+pub fn plan_card(
+    ir: &MessageCardIr,
+    caps: &ProviderCapabilitiesV1,
+    policy: &PlannerPolicy,
 ) -> RenderPlan {
+    let text_only;
+    let caps = match policy.prefer {
+        RenderPreference::Native => caps,
+        RenderPreference::Text => {
+            text_only = text_only_caps(caps);
+            &text_only
+        }
+    };
     let mut warnings = Vec::<RenderWarning>::new();
+    let decisions = decide_elements(ir, caps, policy);
+
     let mut summary_sanitized = false;
     let mut actions_sanitized = false;
     let mut lines = Vec::new();
-    if let Some(title) = card.title.as_deref() {
-        let (clean, stripped) = sanitize_text(title, caps);
-        summary_sanitized |= stripped;
-        lines.push(clean);
+    let mut push_line = |text: &str, lines: &mut Vec<String>| {
+        if !text.is_empty() {
+            let (clean, stripped) = sanitize_text(text, caps);
+            summary_sanitized |= stripped;
+            lines.push(clean);
+        }
+    };
+    if let Some(title) = ir.head.title.as_deref() {
+        push_line(title, &mut lines);
     }
-    if let Some(text) = card.text.as_deref()
-        && !text.is_empty()
-    {
-        let (clean, stripped) = sanitize_text(text, caps);
-        summary_sanitized |= stripped;
-        lines.push(clean);
+    if let Some(text) = ir.head.text.as_deref() {
+        push_line(text, &mut lines);
+    }
+    for (index, element) in ir.elements.iter().enumerate() {
+        if is_dropped(&decisions, &format!("/elements/{index}")) {
+            continue;
+        }
+        match element {
+            // Builders mirror the primary text into the head; list it once.
+            Element::Text { text, .. } if ir.head.text.as_deref() != Some(text.as_str()) => {
+                push_line(text, &mut lines)
+            }
+            Element::Text { .. } => {}
+            Element::FactSet { facts } => {
+                for fact in facts {
+                    push_line(&format!("{}: {}", fact.label, fact.value), &mut lines);
+                }
+            }
+            Element::Image { .. } | Element::Input { .. } => {}
+        }
     }
 
     let mut action_titles = Vec::new();
     let mut action_links = Vec::new();
-    for action in &card.actions {
-        let (title, stripped) = sanitize_text(&action.title, caps);
+    for (index, action) in ir.actions.iter().enumerate() {
+        if is_removed_action(&decisions, index) {
+            continue;
+        }
+        let (title, stripped) = sanitize_text(action.title(), caps);
         actions_sanitized |= stripped;
         action_titles.push(title.clone());
-        if let Some(url) = &action.url {
+        if let IrAction::OpenUrl { url, .. } = action {
             action_links.push(format!("{} ({})", title, url));
         } else {
             action_links.push(title);
@@ -100,7 +305,7 @@ pub fn plan_render(
     if let Some(max) = effective_max_text_len(caps)
         && let Some(text) = &summary
     {
-        let (truncated, did_truncate) = truncate_chars(text, max);
+        let (truncated, did_truncate) = truncate_chars(text, max, policy.truncation);
         if did_truncate {
             push_warning(
                 &mut warnings,
@@ -127,64 +332,393 @@ pub fn plan_render(
         summary = Some(trimmed);
     }
 
-    let tier = select_tier(card, caps, &mut warnings);
+    let truncated_actions = decisions
+        .iter()
+        .filter(|decision| decision.reason.as_deref() == Some("actions_truncated"))
+        .count();
+    if truncated_actions > 0 {
+        push_warning(
+            &mut warnings,
+            "actions_truncated",
+            Some(format!(
+                "dropped {truncated_actions} actions over the limit"
+            )),
+            Some("/actions".to_string()),
+        );
+    }
+    for decision in &decisions {
+        if decision.reason.as_deref() == Some("url_blocked") {
+            push_warning(
+                &mut warnings,
+                "url_blocked",
+                Some("url not allowed by policy".into()),
+                Some(decision.path.clone()),
+            );
+        }
+    }
+
+    let tier = select_tier(ir, caps, &decisions, &mut warnings);
+    let attachments = ir
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            Element::Image { url, .. } => Some(url.clone()),
+            _ => None,
+        })
+        .collect();
 
     RenderPlan {
         tier,
         summary_text: summary,
         actions: action_titles,
-        attachments: card.images.clone(),
+        attachments,
+        elements: decisions,
         warnings,
         debug: Some(serde_json::json!({
-            "planner_version": 1,
-            "tier": tier_label(tier),
+            "planner_version": 2,
+            "tier": tier.as_str(),
+            "policy": policy,
         })),
     }
 }
 
-fn tier_label(tier: RenderTier) -> &'static str {
-    match tier {
-        RenderTier::TierA => "a",
-        RenderTier::TierB => "b",
-        RenderTier::TierC => "c",
-        RenderTier::TierD => "d",
+/// Replaces Markdown links in `card` text that `policy` does not allow with their text, so
+/// text links follow the same rules as buttons and images. Returns the number of text blocks
+/// that changed.
+pub fn strip_blocked_text_links(card: &mut MessageCard, policy: &PlannerPolicy) -> usize {
+    let mut changed = 0;
+    for block in &mut card.body {
+        if let CardBlock::Text {
+            text,
+            markdown: true,
+        } = block
+        {
+            let stripped = markdown::strip_links(text, |url| policy.url_allowed(url));
+            if stripped != *text {
+                *text = stripped;
+                changed += 1;
+            }
+        }
+    }
+    changed
+}
+
+/// Removes every URL `policy` does not allow from `card`, whatever way it is later rendered:
+/// Markdown links in text keep their text, image blocks and open-URL actions are dropped, and
+/// a blocked OAuth `start_url` is cleared. In a raw Adaptive Card, `Image` and `Action.OpenUrl`
+/// entries and `selectAction`s are dropped and `TextBlock` links are stripped. Returns the number
+/// of removals.
+pub fn remove_blocked_urls(card: &mut MessageCard, policy: &PlannerPolicy) -> usize {
+    let mut removed = strip_blocked_text_links(card, policy);
+    let blocks = card.body.len();
+    card.body.retain(|block| match block {
+        CardBlock::Image { url, .. } => policy.url_allowed(url),
+        _ => true,
+    });
+    removed += blocks - card.body.len();
+    let actions = card.actions.len();
+    card.actions.retain(|action| match action {
+        CardAction::OpenUrl { url, .. } => policy.url_allowed(url),
+        CardAction::Postback { .. } => true,
+    });
+    removed += actions - card.actions.len();
+    if let Some(oauth) = &mut card.oauth
+        && oauth
+            .start_url
+            .as_deref()
+            .is_some_and(|url| !policy.url_allowed(url))
+    {
+        oauth.start_url = None;
+        removed += 1;
+    }
+    if let Some(adaptive) = &mut card.adaptive {
+        removed += remove_blocked_adaptive_urls(adaptive, policy);
+    }
+    removed
+}
+
+fn remove_blocked_adaptive_urls(value: &mut Value, policy: &PlannerPolicy) -> usize {
+    let mut removed = 0;
+    match value {
+        Value::Array(items) => {
+            let before = items.len();
+            items.retain(|item| !blocked_adaptive_item(item, policy));
+            removed += before - items.len();
+            for item in items {
+                removed += remove_blocked_adaptive_urls(item, policy);
+            }
+        }
+        Value::Object(map) => {
+            if map
+                .get("selectAction")
+                .is_some_and(|action| blocked_adaptive_item(action, policy))
+            {
+                map.remove("selectAction");
+                removed += 1;
+            }
+            if map.get("type").and_then(Value::as_str) == Some("TextBlock")
+                && let Some(Value::String(text)) = map.get_mut("text")
+            {
+                let stripped = markdown::strip_links(text, |url| policy.url_allowed(url));
+                if stripped != *text {
+                    *text = stripped;
+                    removed += 1;
+                }
+            }
+            for child in map.values_mut() {
+                removed += remove_blocked_adaptive_urls(child, policy);
+            }
+        }
+        _ => {}
+    }
+    removed
+}
+
+fn blocked_adaptive_item(item: &Value, policy: &PlannerPolicy) -> bool {
+    matches!(
+        item.get("type").and_then(Value::as_str),
+        Some("Image" | "Action.OpenUrl")
+    ) && item
+        .get("url")
+        .and_then(Value::as_str)
+        .is_some_and(|url| !policy.url_allowed(url))
+}
+
+/// Applies `plan` to `ir`: dropped elements and actions are removed, downgraded ones are
+/// rewritten in their simpler form, and each decision's reason is recorded as a
+/// `plan.<reason>` warning. Elements without a decision are kept unchanged.
+pub fn apply_plan(ir: &MessageCardIr, plan: &RenderPlan) -> MessageCardIr {
+    let mut planned = MessageCardIr {
+        elements: Vec::with_capacity(ir.elements.len()),
+        actions: Vec::with_capacity(ir.actions.len()),
+        ..ir.clone()
+    };
+    let mut links = Vec::new();
+    for (index, element) in ir.elements.iter().enumerate() {
+        let decision = plan.decision(&format!("/elements/{index}"));
+        match decision.map(|decision| decision.disposition) {
+            None | Some(ElementDisposition::Keep) => planned.elements.push(element.clone()),
+            Some(ElementDisposition::Downgrade) => {
+                planned.elements.push(downgrade_element(element))
+            }
+            Some(ElementDisposition::Drop) => {}
+        }
+        record_reason(&mut planned, decision);
+    }
+    for (index, action) in ir.actions.iter().enumerate() {
+        let decision = plan.decision(&format!("/actions/{index}"));
+        match decision.map(|decision| decision.disposition) {
+            None | Some(ElementDisposition::Keep) => planned.actions.push(action.clone()),
+            Some(ElementDisposition::Downgrade) => {
+                if let IrAction::OpenUrl { title, url } = action {
+                    links.push(format!("{title}: {url}"));
+                }
+            }
+            Some(ElementDisposition::Drop) => {}
+        }
+        record_reason(&mut planned, decision);
+    }
+    if !links.is_empty() {
+        planned.elements.push(Element::Text {
+            text: links.join("\n"),
+            markdown: false,
+        });
+    }
+    planned
+}
+
+fn record_reason(ir: &mut MessageCardIr, decision: Option<&ElementDecision>) {
+    if let Some(reason) = decision.and_then(|decision| decision.reason.as_deref()) {
+        let warning = format!("plan.{reason}");
+        if !ir.meta.warnings.contains(&warning) {
+            ir.meta.warn(warning);
+        }
+    }
+}
+
+fn downgrade_element(element: &Element) -> Element {
+    match element {
+        Element::Text { text, .. } => Element::Text {
+            text: text.clone(),
+            markdown: false,
+        },
+        Element::FactSet { facts } => Element::Text {
+            text: facts
+                .iter()
+                .map(|fact| format!("{}: {}", fact.label, fact.value))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            markdown: false,
+        },
+        Element::Image { url, alt } => Element::Text {
+            text: match alt {
+                Some(alt) => format!("{alt}: {url}"),
+                None => url.clone(),
+            },
+            markdown: false,
+        },
+        Element::Input { .. } => element.clone(),
+    }
+}
+
+fn decide_elements(
+    ir: &MessageCardIr,
+    caps: &ProviderCapabilitiesV1,
+    policy: &PlannerPolicy,
+) -> Vec<ElementDecision> {
+    let mut decisions = Vec::with_capacity(ir.elements.len() + ir.actions.len());
+    for (index, element) in ir.elements.iter().enumerate() {
+        let (kind, tier, outcome) = match element {
+            Element::Text { markdown, .. } => {
+                let tier = if *markdown {
+                    Tier::Advanced
+                } else {
+                    Tier::Basic
+                };
+                let outcome = (*markdown && !caps.supports_markdown)
+                    .then_some((ElementDisposition::Downgrade, "formatting_stripped"));
+                ("text", tier, outcome)
+            }
+            Element::Image { url, .. } => {
+                let outcome = if !policy.url_allowed(url) {
+                    Some((ElementDisposition::Drop, "url_blocked"))
+                } else if caps.supports_images {
+                    None
+                } else if policy.images_as_links {
+                    Some((ElementDisposition::Downgrade, "images_as_links"))
+                } else {
+                    Some((ElementDisposition::Drop, "images_not_supported"))
+                };
+                ("image", Tier::Advanced, outcome)
+            }
+            Element::FactSet { .. } => (
+                "fact_set",
+                Tier::Advanced,
+                (!caps.supports_markdown && !caps.supports_adaptive_cards)
+                    .then_some((ElementDisposition::Downgrade, "fact_set_flattened")),
+            ),
+            Element::Input { .. } => (
+                "input",
+                Tier::Premium,
+                (!caps.supports_adaptive_cards)
+                    .then_some((ElementDisposition::Drop, "inputs_not_supported")),
+            ),
+        };
+        decisions.push(decision(format!("/elements/{index}"), kind, tier, outcome));
+    }
+
+    let action_limit = effective_max_actions(caps, policy).unwrap_or(usize::MAX);
+    let mut listed = 0;
+    for (index, action) in ir.actions.iter().enumerate() {
+        let (kind, outcome) = match action {
+            IrAction::OpenUrl { .. } => (
+                "open_url",
+                (!caps.supports_buttons)
+                    .then_some((ElementDisposition::Downgrade, "actions_as_links")),
+            ),
+            IrAction::Postback { .. } => (
+                "postback",
+                (!caps.supports_buttons)
+                    .then_some((ElementDisposition::Drop, "unsupported_element")),
+            ),
+            IrAction::Dialog { .. } => (
+                "dialog",
+                (!caps.supports_buttons)
+                    .then_some((ElementDisposition::Drop, "unsupported_element")),
+            ),
+        };
+        let outcome = match action {
+            IrAction::OpenUrl { url, .. } if !policy.url_allowed(url) => {
+                Some((ElementDisposition::Drop, "url_blocked"))
+            }
+            _ if listed >= action_limit => Some((ElementDisposition::Drop, "actions_truncated")),
+            _ => {
+                listed += 1;
+                outcome
+            }
+        };
+        decisions.push(decision(
+            format!("/actions/{index}"),
+            kind,
+            Tier::Advanced,
+            outcome,
+        ));
+    }
+    decisions
+}
+
+fn decision(
+    path: String,
+    kind: &str,
+    tier: Tier,
+    outcome: Option<(ElementDisposition, &str)>,
+) -> ElementDecision {
+    let (disposition, reason) = match outcome {
+        Some((disposition, reason)) => (disposition, Some(reason.to_string())),
+        None => (ElementDisposition::Keep, None),
+    };
+    ElementDecision {
+        path,
+        kind: kind.to_string(),
+        disposition,
+        tier,
+        reason,
     }
 }
 
+/// Actions left out of the summary: blocked by policy or over the action limit. Actions the
+/// provider merely cannot show as buttons are still listed.
+fn is_removed_action(decisions: &[ElementDecision], index: usize) -> bool {
+    let path = format!("/actions/{index}");
+    decisions.iter().any(|decision| {
+        decision.path == path
+            && matches!(
+                decision.reason.as_deref(),
+                Some("url_blocked" | "actions_truncated")
+            )
+    })
+}
+
+fn is_dropped(decisions: &[ElementDecision], path: &str) -> bool {
+    decisions
+        .iter()
+        .any(|decision| decision.path == path && decision.disposition == ElementDisposition::Drop)
+}
+
 fn select_tier(
-    card: &PlannerCard,
+    ir: &MessageCardIr,
     caps: &ProviderCapabilitiesV1,
+    decisions: &[ElementDecision],
     warnings: &mut Vec<RenderWarning>,
-) -> RenderTier {
-    // Tier A path: adaptive cards supported and no unsupported elements.
+) -> Tier {
+    push_unsupported_warnings(ir, caps, warnings);
+    // Premium: adaptive cards supported and every element rendered as authored.
     if caps.supports_adaptive_cards {
-        let unsupported = has_unsupported_elements(card, caps, warnings);
-        if !unsupported {
-            return RenderTier::TierA;
+        if decisions
+            .iter()
+            .all(|decision| decision.disposition == ElementDisposition::Keep)
+        {
+            return Tier::Premium;
         }
-        // Adaptive is supported but we need to drop/alter elements.
-        return RenderTier::TierB;
+        // Advanced: adaptive is supported but we need to drop/alter elements.
+        return Tier::Advanced;
     }
 
-    // Default to Tier D for everything else.
-    let _ = has_unsupported_elements(card, caps, warnings);
+    // Basic (text and links) for everything else.
     warnings.push(RenderWarning {
         code: "adaptive_cards_not_supported".into(),
         message: None,
         path: None,
     });
-    RenderTier::TierD
+    Tier::Basic
 }
 
-fn has_unsupported_elements(
-    card: &PlannerCard,
+fn push_unsupported_warnings(
+    ir: &MessageCardIr,
     caps: &ProviderCapabilitiesV1,
     warnings: &mut Vec<RenderWarning>,
-) -> bool {
-    let mut unsupported = false;
-
-    if !caps.supports_buttons && !card.actions.is_empty() {
-        unsupported = true;
+) {
+    if !caps.supports_buttons && !ir.actions.is_empty() {
         warnings.push(RenderWarning {
             code: "unsupported_element".into(),
             message: Some("buttons/actions not supported".into()),
@@ -192,8 +726,8 @@ fn has_unsupported_elements(
         });
     }
 
-    if !caps.supports_images && !card.images.is_empty() {
-        unsupported = true;
+    let has = |pred: fn(&Element) -> bool| ir.elements.iter().any(pred);
+    if !caps.supports_images && has(|element| matches!(element, Element::Image { .. })) {
         warnings.push(RenderWarning {
             code: "images_not_supported".into(),
             message: Some("images not supported".into()),
@@ -201,18 +735,49 @@ fn has_unsupported_elements(
         });
     }
 
-    unsupported
+    if !caps.supports_adaptive_cards && has(|element| matches!(element, Element::Input { .. })) {
+        warnings.push(RenderWarning {
+            code: "inputs_not_supported".into(),
+            message: Some("inputs need adaptive cards".into()),
+            path: Some("/elements".into()),
+        });
+    }
 }
 
-fn truncate_chars(text: &str, max: usize) -> (String, bool) {
-    let mut out = String::new();
-    for (idx, ch) in text.chars().enumerate() {
-        if idx == max {
-            return (out, true);
+fn truncate_chars(text: &str, max: usize, strategy: TruncationStrategy) -> (String, bool) {
+    if text.chars().count() <= max {
+        return (text.to_string(), false);
+    }
+    let out = match strategy {
+        TruncationStrategy::Hard => text.chars().take(max).collect(),
+        TruncationStrategy::Ellipsis if max > 0 => {
+            let mut out: String = text.chars().take(max - 1).collect();
+            out.push('…');
+            out
         }
-        out.push(ch);
+        TruncationStrategy::Ellipsis => String::new(),
+        TruncationStrategy::WordBoundary => {
+            let cut: String = text.chars().take(max).collect();
+            let at_boundary = text.chars().nth(max).is_some_and(char::is_whitespace);
+            match cut.rfind(char::is_whitespace) {
+                Some(end) if end > 0 && !at_boundary => cut[..end].trim_end().to_string(),
+                _ => cut,
+            }
+        }
+    };
+    (out, true)
+}
+
+/// Capabilities of a plain-text provider with the same limits as `caps`.
+fn text_only_caps(caps: &ProviderCapabilitiesV1) -> ProviderCapabilitiesV1 {
+    ProviderCapabilitiesV1 {
+        supports_adaptive_cards: false,
+        supports_markdown: false,
+        supports_html: false,
+        supports_images: false,
+        supports_buttons: false,
+        ..caps.clone()
     }
-    (out, false)
 }
 
 fn truncate_bytes(text: &str, max: usize) -> (String, bool) {
@@ -295,6 +860,18 @@ fn effective_max_text_len(caps: &ProviderCapabilitiesV1) -> Option<usize> {
         .map(|v| v as usize)
 }
 
+fn effective_max_actions(caps: &ProviderCapabilitiesV1, policy: &PlannerPolicy) -> Option<usize> {
+    [
+        caps.limits.max_actions.or(caps.max_actions),
+        caps.limits.max_total_buttons.or(caps.max_total_buttons),
+        policy.max_buttons,
+    ]
+    .into_iter()
+    .flatten()
+    .min()
+    .map(|v| v as usize)
+}
+
 fn effective_max_payload_bytes(caps: &ProviderCapabilitiesV1) -> Option<usize> {
     caps.limits
         .max_payload_bytes
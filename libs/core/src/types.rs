@@ -214,15 +214,14 @@ impl TryFrom<InvocationEnvelope> for MessageEnvelope {
 ///     thread_id: None,
 ///     kind: OutKind::Text,
 ///     text: Some("Hello".into()),
-///     message_card: None,
-///     #[cfg(feature = "adaptive-cards")]
-///     adaptive_card: None,
+///     card: None,
 ///     meta: Default::default(),
 /// };
 ///
 /// assert_eq!(out.kind, OutKind::Text);
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "OutMessageWire")]
 pub struct OutMessage {
     pub ctx: TenantCtx,
     pub tenant: String,
@@ -231,14 +230,46 @@ pub struct OutMessage {
     pub thread_id: Option<String>,
     pub kind: OutKind,
     pub text: Option<String>,
-    pub message_card: Option<MessageCard>,
-    #[cfg(feature = "adaptive-cards")]
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub adaptive_card: Option<AdaptiveMessageCard>,
-    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card: Option<MessageCard>,
     pub meta: BTreeMap<String, Value>,
 }
 
+/// Accepts the current `card` field as well as the older `message_card`/`adaptive_card` pair.
+/// When both legacy fields are set, `adaptive_card` wins, as it did for rendering.
+#[derive(Deserialize)]
+struct OutMessageWire {
+    ctx: TenantCtx,
+    tenant: String,
+    platform: Platform,
+    chat_id: String,
+    thread_id: Option<String>,
+    kind: OutKind,
+    text: Option<String>,
+    #[serde(default, alias = "message_card")]
+    card: Option<MessageCard>,
+    #[serde(default)]
+    adaptive_card: Option<MessageCard>,
+    #[serde(default)]
+    meta: BTreeMap<String, Value>,
+}
+
+impl From<OutMessageWire> for OutMessage {
+    fn from(wire: OutMessageWire) -> Self {
+        Self {
+            ctx: wire.ctx,
+            tenant: wire.tenant,
+            platform: wire.platform,
+            chat_id: wire.chat_id,
+            thread_id: wire.thread_id,
+            kind: wire.kind,
+            text: wire.text,
+            card: wire.adaptive_card.or(wire.card),
+            meta: wire.meta,
+        }
+    }
+}
+
 impl OutMessage {
     /// Returns a stable identifier for tracing/logging, falling back to chat scope.
     pub fn message_id(&self) -> String {
@@ -264,7 +295,10 @@ pub enum OutKind {
     Card,
 }
 
-/// Minimal, canonical MessageCard v1.
+/// Canonical message card used by flows, `OutMessage` and the translators.
+///
+/// Deserialization also accepts the flat shape of `messaging_card::MessageCard` (`text`,
+/// `images`, `allow_markdown`), so payloads written against either model keep working.
 ///
 /// ```
 /// use gsm_core::{CardAction, CardBlock, MessageCard};
@@ -280,17 +314,167 @@ pub enum OutKind {
 ///         CardAction::OpenUrl { title: "Detail".into(), url: "https://example.com".into(), jwt: false },
 ///         CardAction::Postback { title: "Ack".into(), data: json!({"ok": true}) },
 ///     ],
+///     ..Default::default()
 /// };
 /// assert_eq!(card.body.len(), 2);
+///
+/// let flat: MessageCard = serde_json::from_value(json!({"title": "Hi", "text": "Hello"})).unwrap();
+/// assert_eq!(flat.body, vec![CardBlock::Text { text: "Hello".into(), markdown: true }]);
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(from = "MessageCardWire")]
 pub struct MessageCard {
+    #[serde(skip_serializing_if = "MessageCardKind::is_standard")]
+    pub kind: MessageCardKind,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
-    #[serde(default)]
     pub body: Vec<CardBlock>,
-    #[serde(default)]
     pub actions: Vec<CardAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer: Option<String>,
+    /// Raw Adaptive Card; when present it takes precedence over `body` for rendering.
+    #[cfg(feature = "adaptive-cards")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<OauthCard>,
+}
+
+impl MessageCard {
+    /// Whether the card needs the card engine: an OAuth prompt or a raw Adaptive Card.
+    pub fn is_rich(&self) -> bool {
+        #[cfg(feature = "adaptive-cards")]
+        if self.adaptive.is_some() {
+            return true;
+        }
+        matches!(self.kind, MessageCardKind::Oauth) || self.oauth.is_some()
+    }
+}
+
+/// Deserializes an optional flow card template. Flow authors write Markdown, so text blocks
+/// without an explicit `markdown` flag default to Markdown here, unlike in `MessageCard`.
+pub fn deserialize_flow_card<'de, D>(deserializer: D) -> Result<Option<MessageCard>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let Some(mut value) = Option::<Value>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    if let Some(blocks) = value.get_mut("body").and_then(Value::as_array_mut) {
+        for block in blocks {
+            if block.get("type").and_then(Value::as_str) == Some("text")
+                && let Some(map) = block.as_object_mut()
+            {
+                map.entry("markdown").or_insert(Value::Bool(true));
+            }
+        }
+    }
+    serde_json::from_value(value)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Union of the canonical and flat card shapes.
+#[derive(Deserialize)]
+struct MessageCardWire {
+    #[serde(default)]
+    kind: MessageCardKind,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default = "default_true")]
+    allow_markdown: bool,
+    #[serde(default)]
+    body: Vec<CardBlock>,
+    #[serde(default)]
+    images: Vec<WireImage>,
+    #[serde(default)]
+    actions: Vec<CardAction>,
+    #[serde(default)]
+    footer: Option<String>,
+    #[cfg(feature = "adaptive-cards")]
+    #[serde(default)]
+    adaptive: Option<Value>,
+    #[serde(default)]
+    oauth: Option<OauthCard>,
+}
+
+#[derive(Deserialize)]
+struct WireImage {
+    url: String,
+    #[serde(default)]
+    alt: Option<String>,
+}
+
+impl From<MessageCardWire> for MessageCard {
+    fn from(wire: MessageCardWire) -> Self {
+        let mut body = Vec::new();
+        if let Some(text) = wire.text {
+            body.push(CardBlock::Text {
+                text,
+                markdown: wire.allow_markdown,
+            });
+        }
+        body.extend(wire.body);
+        body.extend(wire.images.into_iter().map(|image| CardBlock::Image {
+            url: image.url,
+            alt: image.alt,
+        }));
+        Self {
+            kind: wire.kind,
+            title: wire.title,
+            body,
+            actions: wire.actions,
+            footer: wire.footer,
+            #[cfg(feature = "adaptive-cards")]
+            adaptive: wire.adaptive,
+            oauth: wire.oauth,
+        }
+    }
+}
+
+#[cfg(feature = "adaptive-cards")]
+impl From<AdaptiveMessageCard> for MessageCard {
+    fn from(card: AdaptiveMessageCard) -> Self {
+        use crate::messaging_card::types::Action;
+
+        MessageCardWire {
+            kind: card.kind,
+            title: card.title,
+            text: card.text,
+            allow_markdown: card.allow_markdown,
+            body: Vec::new(),
+            images: card
+                .images
+                .into_iter()
+                .map(|image| WireImage {
+                    url: image.url,
+                    alt: image.alt,
+                })
+                .collect(),
+            actions: card
+                .actions
+                .into_iter()
+                .map(|action| match action {
+                    Action::OpenUrl { title, url } => CardAction::OpenUrl {
+                        title,
+                        url,
+                        jwt: false,
+                    },
+                    Action::PostBack { title, data } => CardAction::Postback { title, data },
+                })
+                .collect(),
+            footer: card.footer,
+            adaptive: card.adaptive,
+            oauth: card.oauth,
+        }
+        .into()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -308,14 +492,18 @@ pub enum CardBlock {
     Fact { label: String, value: String },
     /// Image block referenced by URL.
     #[serde(rename = "image")]
-    Image { url: String },
+    Image {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        alt: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type")]
 pub enum CardAction {
     /// Opens an URL when invoked (optionally signed with JWT).
-    #[serde(rename = "openUrl")]
+    #[serde(rename = "openUrl", alias = "open_url")]
     OpenUrl {
         title: String,
         url: String,
@@ -323,6 +511,84 @@ pub enum CardAction {
         jwt: bool,
     },
     /// Posts structured data back to the application.
-    #[serde(rename = "postback")]
+    #[serde(rename = "postback", alias = "post_back")]
     Postback { title: String, data: Value },
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageCardKind {
+    #[default]
+    Standard,
+    Oauth,
+}
+
+impl MessageCardKind {
+    pub fn is_standard(&self) -> bool {
+        matches!(self, MessageCardKind::Standard)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OauthProvider {
+    Microsoft,
+    Google,
+    Github,
+    Custom,
+}
+
+impl OauthProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OauthProvider::Microsoft => "microsoft",
+            OauthProvider::Google => "google",
+            OauthProvider::Github => "github",
+            OauthProvider::Custom => "custom",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            OauthProvider::Microsoft => "Microsoft",
+            OauthProvider::Google => "Google",
+            OauthProvider::Github => "GitHub",
+            OauthProvider::Custom => "External Provider",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OauthPrompt {
+    None,
+    Consent,
+    Login,
+}
+
+impl OauthPrompt {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OauthPrompt::None => "none",
+            OauthPrompt::Consent => "consent",
+            OauthPrompt::Login => "login",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OauthCard {
+    pub provider: OauthProvider,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<OauthPrompt>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
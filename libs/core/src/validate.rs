@@ -223,9 +223,7 @@ pub fn validate_envelope(env: &MessageEnvelope) -> Result<()> {
 ///     thread_id: None,
 ///     kind: OutKind::Text,
 ///     text: Some("Hello".into()),
-///     message_card: None,
-///     #[cfg(feature = "adaptive-cards")]
-///     adaptive_card: None,
+///     card: None,
 ///     meta: Default::default(),
 /// };
 ///
@@ -246,7 +244,7 @@ pub fn validate_out(out: &OutMessage) -> Result<()> {
         }
         OutKind::Card => {
             let card = out
-                .message_card
+                .card
                 .as_ref()
                 .ok_or_else(|| anyhow::anyhow!("card missing"))?;
             validate_card(card)?;
@@ -275,11 +273,15 @@ pub fn validate_out(out: &OutMessage) -> Result<()> {
 ///         },
 ///         CardAction::Postback { title: "Ack".into(), data: json!({"ok": true}) },
 ///     ],
+///     ..Default::default()
 /// };
 ///
 /// validate_card(&card).unwrap();
 /// ```
 pub fn validate_card(card: &MessageCard) -> Result<()> {
+    if card.is_rich() {
+        return Ok(());
+    }
     if card.body.is_empty() && card.title.as_deref().unwrap_or("").is_empty() {
         bail!("card must have title or body");
     }
@@ -291,7 +293,7 @@ pub fn validate_card(card: &MessageCard) -> Result<()> {
             {
                 bail!("empty fact")
             }
-            CardBlock::Image { url } if url.trim().is_empty() => bail!("empty image url"),
+            CardBlock::Image { url, .. } if url.trim().is_empty() => bail!("empty image url"),
             _ => {}
         }
     }
@@ -328,9 +330,7 @@ mod tests {
             thread_id: None,
             kind,
             text: Some("Hello".into()),
-            message_card: None,
-            #[cfg(feature = "adaptive-cards")]
-            adaptive_card: None,
+            card: None,
             meta: Default::default(),
         }
     }
@@ -357,7 +357,7 @@ mod tests {
     }
 
     #[test]
-    fn out_card_requires_message_card() {
+    fn out_card_requires_card() {
         let out = sample_out(OutKind::Card);
         assert!(validate_out(&out).is_err());
     }
@@ -368,6 +368,7 @@ mod tests {
             title: None,
             body: vec![],
             actions: vec![],
+            ..Default::default()
         };
         assert!(validate_card(&card).is_err());
     }
@@ -381,6 +382,7 @@ mod tests {
                 value: "".into(),
             }],
             actions: vec![],
+            ..Default::default()
         };
         assert!(validate_card(&card).is_err());
     }
@@ -403,6 +405,7 @@ mod tests {
                 title: "Ack".into(),
                 data: json!({"ok": true}),
             }],
+            ..Default::default()
         };
         assert!(validate_card(&card).is_ok());
     }
@@ -0,0 +1,115 @@
+use gsm_core::messaging_card::tier::Tier;
+use gsm_core::messaging_card::{
+    CardUpdate, MessageCardEngine, MessageCardIr, MessageCardIrBuilder,
+};
+use gsm_core::{SendOutput, SendStatus};
+use serde_json::json;
+
+#[test]
+fn update_requires_provider_message_id() {
+    let mut output = SendOutput {
+        message_id: "m-1".into(),
+        provider_message_id: None,
+        thread_id: None,
+        status: SendStatus::Sent,
+        ts: None,
+    };
+    assert!(CardUpdate::from_send_output(&output, sample_ir()).is_none());
+
+    output.provider_message_id = Some("1700000000.000100".into());
+    let update = CardUpdate::from_send_output(&output, sample_ir()).expect("update");
+    assert_eq!(update.provider_message_id, "1700000000.000100");
+}
+
+#[test]
+fn slack_update_uses_chat_update() {
+    let engine = MessageCardEngine::bootstrap();
+    let update = CardUpdate::new("1700000000.000100", sample_ir());
+    let snapshot = engine.render_update("slack", &update).expect("slack");
+    let payload = &snapshot.output.payload;
+    assert_eq!(payload["method"], "chat.update");
+    assert_eq!(payload["ts"], "1700000000.000100");
+    assert!(payload["blocks"].is_array());
+    assert_eq!(
+        snapshot.output.edit_message_id.as_deref(),
+        Some("1700000000.000100")
+    );
+}
+
+#[test]
+fn teams_and_webex_updates_wrap_adaptive_card() {
+    let engine = MessageCardEngine::bootstrap();
+    let update = CardUpdate::new("msg-42", sample_ir());
+
+    let teams = engine.render_update("teams", &update).expect("teams");
+    let payload = &teams.output.payload;
+    assert_eq!(payload["method"], "updateActivity");
+    assert_eq!(payload["activity_id"], "msg-42");
+    assert_eq!(
+        payload["activity"]["attachments"][0]["content"]["type"],
+        "AdaptiveCard"
+    );
+
+    let webex = engine.render_update("webex", &update).expect("webex");
+    let payload = &webex.output.payload;
+    assert_eq!(payload["method"], "edit");
+    assert_eq!(payload["messageId"], "msg-42");
+    assert_eq!(payload["attachments"][0]["content"]["type"], "AdaptiveCard");
+}
+
+#[test]
+fn telegram_update_edits_text_and_markup() {
+    let engine = MessageCardEngine::bootstrap();
+    let update = CardUpdate::new("381", sample_ir());
+    let snapshot = engine.render_update("telegram", &update).expect("telegram");
+    let payload = &snapshot.output.payload;
+    assert_eq!(payload["method"], "editMessageText");
+    assert_eq!(payload["message_id"], 381);
+    assert!(
+        payload["text"]
+            .as_str()
+            .unwrap()
+            .contains("Deploy finished")
+    );
+    assert!(payload["reply_markup"]["inline_keyboard"].is_array());
+
+    let buttons_only = MessageCardIrBuilder::default()
+        .tier(Tier::Basic)
+        .postback("Retry", json!({"retry": true}))
+        .build();
+    let update = CardUpdate::new("381", buttons_only);
+    let snapshot = engine.render_update("telegram", &update).expect("telegram");
+    assert_eq!(snapshot.output.payload["method"], "editMessageReplyMarkup");
+}
+
+#[test]
+fn unsupported_platform_falls_back_to_new_message() {
+    let engine = MessageCardEngine::bootstrap();
+    let update = CardUpdate::new("wamid.1", sample_ir());
+    let snapshot = engine.render_update("whatsapp", &update).expect("whatsapp");
+    assert!(snapshot.output.edit_message_id.is_none());
+    assert!(
+        snapshot
+            .output
+            .warnings
+            .iter()
+            .any(|w| w == "whatsapp.edit_unsupported")
+    );
+    assert_eq!(
+        snapshot.output.payload,
+        engine
+            .render_card_snapshot("whatsapp", &update.card)
+            .expect("whatsapp")
+            .output
+            .payload
+    );
+}
+
+fn sample_ir() -> MessageCardIr {
+    MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .title("Release")
+        .primary_text("Deploy finished", false)
+        .postback("Ack", json!({"ok": true}))
+        .build()
+}
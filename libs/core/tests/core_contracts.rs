@@ -63,22 +63,21 @@ fn out_text_and_card_validate() {
         thread_id: None,
         kind: OutKind::Text,
         text: Some("hello".into()),
-        message_card: None,
-        #[cfg(feature = "adaptive-cards")]
-        adaptive_card: None,
+        card: None,
         meta: Default::default(),
     };
     assert!(validate_out(&out).is_ok());
 
     out.kind = OutKind::Card;
     out.text = None;
-    out.message_card = Some(MessageCard {
+    out.card = Some(MessageCard {
         title: Some("Title".into()),
         body: vec![CardBlock::Text {
             text: "Body".into(),
             markdown: true,
         }],
         actions: vec![],
+        ..Default::default()
     });
     assert!(validate_out(&out).is_ok());
 }
@@ -52,6 +52,7 @@ fn out_message_roundtrips_with_card() {
             title: "Ack".into(),
             data: json!({"ok": true}),
         }],
+        ..Default::default()
     };
     let out = OutMessage {
         ctx,
@@ -61,15 +62,68 @@ fn out_message_roundtrips_with_card() {
         thread_id: Some("thread-2".into()),
         kind: OutKind::Card,
         text: None,
-        message_card: Some(card),
-        #[cfg(feature = "adaptive-cards")]
-        adaptive_card: None,
+        card: Some(card),
         meta: Default::default(),
     };
 
     assert_roundtrip(&out);
 }
 
+#[test]
+fn out_message_accepts_legacy_card_fields() {
+    let ctx = gsm_core::make_tenant_ctx("acme".into(), None, None);
+    let mut value = json!({
+        "ctx": ctx,
+        "tenant": "acme",
+        "platform": "teams",
+        "chat_id": "chat-1",
+        "thread_id": null,
+        "kind": "card",
+        "text": null,
+        "message_card": {"title": "Plain", "body": [], "actions": []},
+        "meta": {}
+    });
+    let out: OutMessage = serde_json::from_value(value.clone()).expect("legacy message_card");
+    assert_eq!(
+        out.card.as_ref().and_then(|c| c.title.as_deref()),
+        Some("Plain")
+    );
+
+    value["adaptive_card"] = json!({
+        "title": "Rich",
+        "text": "Hello",
+        "images": [{"url": "https://example.com/a.png", "alt": "A"}],
+        "actions": [{"type": "open_url", "title": "Docs", "url": "https://example.com"}]
+    });
+    let out: OutMessage = serde_json::from_value(value).expect("legacy adaptive_card");
+    let card = out.card.expect("card");
+    assert_eq!(card.title.as_deref(), Some("Rich"));
+    assert_eq!(
+        card.body,
+        vec![
+            CardBlock::Text {
+                text: "Hello".into(),
+                markdown: true,
+            },
+            CardBlock::Image {
+                url: "https://example.com/a.png".into(),
+                alt: Some("A".into()),
+            },
+        ]
+    );
+    assert!(
+        matches!(&card.actions[0], CardAction::OpenUrl { url, .. } if url == "https://example.com")
+    );
+
+    let reserialized = serde_json::to_value(OutMessage {
+        card: Some(card),
+        ..out_message_stub()
+    })
+    .expect("serialize");
+    assert!(reserialized.get("card").is_some());
+    assert!(reserialized.get("adaptive_card").is_none());
+}
+
 #[test]
 fn outbound_envelope_preserves_session_and_channel() {
     let channel = ChannelMessage {
@@ -161,6 +215,20 @@ fn webchat_error_classification_is_stable() {
     assert_eq!(retry_after.as_deref(), Some("5"));
 }
 
+fn out_message_stub() -> OutMessage {
+    OutMessage {
+        ctx: gsm_core::make_tenant_ctx("acme".into(), None, None),
+        tenant: "acme".into(),
+        platform: Platform::Teams,
+        chat_id: "chat-1".into(),
+        thread_id: None,
+        kind: OutKind::Card,
+        text: None,
+        card: None,
+        meta: Default::default(),
+    }
+}
+
 fn assert_roundtrip<T>(value: &T)
 where
     T: serde::Serialize + for<'de> serde::Deserialize<'de>,
@@ -0,0 +1,53 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use gsm_core::messaging_card::markdown::{MarkdownDialect, convert, convert_with_links};
+
+const DIALECTS: [MarkdownDialect; 6] = [
+    MarkdownDialect::SlackMrkdwn,
+    MarkdownDialect::TelegramMarkdownV2,
+    MarkdownDialect::TelegramHtml,
+    MarkdownDialect::WhatsApp,
+    MarkdownDialect::Webex,
+    MarkdownDialect::AdaptiveCard,
+];
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/markdown")
+}
+
+#[test]
+fn dialects_match_golden_files() {
+    let dir = fixtures_dir();
+    let input = fs::read_to_string(dir.join("input.md")).expect("read input");
+    for dialect in DIALECTS {
+        let path = dir.join(format!("{}.txt", dialect.as_str()));
+        let rendered = convert(&input, dialect);
+        let expected = fs::read_to_string(&path).expect("read golden");
+        assert_eq!(
+            rendered,
+            expected.trim_end_matches('\n'),
+            "dialect {}",
+            dialect.as_str()
+        );
+    }
+}
+
+#[test]
+fn blocked_links_render_as_plain_text() {
+    let dir = fixtures_dir().join("links");
+    let input = fs::read_to_string(dir.join("input.md")).expect("read input");
+    for dialect in DIALECTS {
+        let path = dir.join(format!("{}.txt", dialect.as_str()));
+        let rendered = convert_with_links(&input, dialect, |url| {
+            !url.starts_with("https://blocked.example")
+        });
+        let expected = fs::read_to_string(&path).expect("read golden");
+        assert_eq!(
+            rendered,
+            expected.trim_end_matches('\n'),
+            "dialect {}",
+            dialect.as_str()
+        );
+    }
+}
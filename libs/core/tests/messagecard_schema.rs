@@ -69,3 +69,41 @@ fn round_trip_with_oauth_payload() {
     assert!(matches!(recovered.kind, MessageCardKind::Oauth));
     assert!(recovered.oauth.is_some());
 }
+
+#[test]
+fn canonical_card_specs_follow_card_kind() {
+    use gsm_core::messaging_card::{MessageCardEngine, RenderSpec};
+
+    let engine = MessageCardEngine::bootstrap();
+    let plain = gsm_core::MessageCard {
+        title: Some("Plain".into()),
+        footer: Some("Footer".into()),
+        ..Default::default()
+    };
+    let RenderSpec::Card(ir) = engine.render_card_spec(&plain).expect("plain spec") else {
+        panic!("plain cards render through the IR");
+    };
+    assert_eq!(ir.meta.source.as_deref(), Some("plain"));
+    assert_eq!(ir.head.title.as_deref(), Some("Plain"));
+
+    let oauth: gsm_core::MessageCard = MessageCard {
+        kind: MessageCardKind::Oauth,
+        title: Some("Sign in".into()),
+        oauth: Some(OauthCard {
+            provider: OauthProvider::Google,
+            scopes: Vec::new(),
+            resource: None,
+            prompt: None,
+            start_url: Some("https://oauth/start".into()),
+            connection_name: None,
+            metadata: None,
+        }),
+        ..Default::default()
+    }
+    .into();
+    assert!(oauth.is_rich());
+    assert!(matches!(
+        engine.render_card_spec(&oauth).expect("oauth spec"),
+        RenderSpec::Auth(_)
+    ));
+}
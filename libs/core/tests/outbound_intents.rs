@@ -0,0 +1,117 @@
+use gsm_core::{
+    DIRECT_MESSAGE_META_KEY, INTENT_META_KEY, INTENT_WARNINGS_META_KEY, IntentDelivery, OutKind,
+    OutMessage, OutboundIntent, Platform, ProviderCapabilitiesV2, apply_intent, make_tenant_ctx,
+    negotiate_intent,
+};
+use serde_json::json;
+
+fn out_with_intent(intent: serde_json::Value) -> OutMessage {
+    OutMessage {
+        ctx: make_tenant_ctx("acme".into(), None, None),
+        tenant: "acme".into(),
+        platform: Platform::Slack,
+        chat_id: "C1".into(),
+        thread_id: Some("T0".into()),
+        kind: OutKind::Text,
+        text: None,
+        card: None,
+        meta: [(INTENT_META_KEY.to_string(), intent)].into(),
+    }
+}
+
+#[test]
+fn supported_intents_are_delivered_natively() {
+    let caps = ProviderCapabilitiesV2 {
+        supports_typing: true,
+        supports_reactions: true,
+        supports_ephemeral: true,
+        supports_threads: true,
+        ..Default::default()
+    };
+    let intents = [
+        OutboundIntent::Typing,
+        OutboundIntent::Reaction {
+            message_id: "m1".into(),
+            emoji: ":tada:".into(),
+        },
+        OutboundIntent::EphemeralReply {
+            user_id: "U1".into(),
+            text: "only you".into(),
+        },
+    ];
+    for intent in intents {
+        let plan = negotiate_intent(&intent, &caps);
+        assert!(plan.is_native(), "{} should be native", intent.kind());
+        assert!(plan.warnings.is_empty());
+    }
+
+    let mut out =
+        out_with_intent(json!({"type": "threaded_reply", "thread_id": "T9", "text": "hi"}));
+    let plan = apply_intent(&mut out, &caps).expect("intent present");
+    assert!(plan.is_native());
+    assert_eq!(out.thread_id.as_deref(), Some("T9"));
+    assert_eq!(out.text.as_deref(), Some("hi"));
+    assert!(out.meta.contains_key(INTENT_META_KEY));
+    assert!(!out.meta.contains_key(INTENT_WARNINGS_META_KEY));
+}
+
+#[test]
+fn unsupported_intents_degrade_with_warnings() {
+    let caps = ProviderCapabilitiesV2::default();
+
+    let typing = negotiate_intent(&OutboundIntent::Typing, &caps);
+    assert_eq!(typing.delivery, IntentDelivery::Skip);
+    assert_eq!(typing.warnings[0].code, "typing_not_supported");
+
+    let mut out = out_with_intent(json!({"type": "reaction", "message_id": "m1", "emoji": "👍"}));
+    let plan = apply_intent(&mut out, &caps).unwrap();
+    assert_eq!(plan.warnings[0].code, "reactions_not_supported");
+    assert_eq!(out.text.as_deref(), Some("👍"));
+    assert_eq!(out.thread_id, None);
+    assert!(!out.meta.contains_key(INTENT_META_KEY));
+    assert_eq!(
+        out.meta[INTENT_WARNINGS_META_KEY][0]["code"],
+        "reactions_not_supported"
+    );
+
+    let mut out =
+        out_with_intent(json!({"type": "threaded_reply", "thread_id": "T9", "text": "hi"}));
+    let plan = apply_intent(&mut out, &caps).unwrap();
+    assert_eq!(plan.warnings[0].code, "threads_not_supported");
+    assert_eq!(out.text.as_deref(), Some("hi"));
+    assert_eq!(out.thread_id, None);
+}
+
+#[test]
+fn ephemeral_reply_falls_back_to_direct_message_then_reply() {
+    let ephemeral = json!({"type": "ephemeral_reply", "user_id": "U1", "text": "psst"});
+
+    let dm_caps = ProviderCapabilitiesV2 {
+        supports_direct_messages: true,
+        ..Default::default()
+    };
+    let mut out = out_with_intent(ephemeral.clone());
+    let plan = apply_intent(&mut out, &dm_caps).unwrap();
+    assert_eq!(plan.warnings[0].code, "ephemeral_as_direct_message");
+    assert_eq!(out.meta[DIRECT_MESSAGE_META_KEY], "U1");
+    assert_eq!(
+        out.chat_id, "U1",
+        "the reply must not reach the conversation"
+    );
+    assert_eq!(out.text.as_deref(), Some("psst"));
+
+    let mut out = out_with_intent(ephemeral);
+    let plan = apply_intent(&mut out, &ProviderCapabilitiesV2::default()).unwrap();
+    assert_eq!(plan.warnings[0].code, "ephemeral_as_reply");
+    assert!(!out.meta.contains_key(DIRECT_MESSAGE_META_KEY));
+    assert_ne!(out.chat_id, "U1");
+}
+
+#[test]
+fn messages_without_a_valid_intent_are_untouched() {
+    let caps = ProviderCapabilitiesV2::default();
+    let mut out = out_with_intent(json!({"type": "wave"}));
+    assert!(apply_intent(&mut out, &caps).is_none());
+    out.meta.clear();
+    assert!(apply_intent(&mut out, &caps).is_none());
+}
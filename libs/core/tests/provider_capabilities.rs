@@ -1,6 +1,8 @@
 use gsm_core::provider_capabilities::{
-    CapabilitiesError, ProviderCapabilitiesV1, ProviderLimitsV1,
+    CapabilitiesError, FileUploadsV2, InputKindV2, ProviderCapabilitiesV1, ProviderCapabilitiesV2,
+    ProviderLimitsV1,
 };
+use serde_json::json;
 
 #[test]
 fn defaults_are_conservative() {
@@ -89,3 +91,119 @@ fn validation_accepts_ok_capabilities() {
     };
     assert_eq!(caps.validate(), Ok(()));
 }
+
+#[test]
+fn v1_upgrades_to_v2_with_single_limits_block() {
+    let v1 = ProviderCapabilitiesV1 {
+        supports_adaptive_cards: true,
+        supports_buttons: true,
+        max_text_len: Some(4096),
+        max_actions: Some(5),
+        limits: ProviderLimitsV1 {
+            max_actions: Some(4),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let v2 = ProviderCapabilitiesV2::from(v1);
+    assert_eq!(v2.version, "v2");
+    assert!(v2.supports_adaptive_cards && v2.supports_buttons);
+    assert!(!v2.supports_reactions && !v2.supports_typing);
+    assert_eq!(v2.limits.max_text_len, Some(4096));
+    assert_eq!(v2.limits.max_actions, Some(4));
+    assert!(v2.supports_input(InputKindV2::Choice));
+    assert!(v2.file_uploads.is_none());
+    assert_eq!(v2.validate(), Ok(()));
+}
+
+#[test]
+fn from_json_accepts_both_versions() {
+    let upgraded = ProviderCapabilitiesV2::from_json(
+        &serde_json::to_value(ProviderCapabilitiesV1 {
+            supports_markdown: true,
+            ..Default::default()
+        })
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(upgraded.supports_markdown);
+
+    let v2 = ProviderCapabilitiesV2::from_json(&json!({
+        "version": "v2",
+        "supports_reactions": true,
+        "supports_ephemeral": true,
+        "inputs": ["text", "date"],
+        "file_uploads": {"max_bytes": 1048576, "mime_types": ["image/*", "application/pdf"]},
+        "rate_limits": {"messages_per_second": 1}
+    }))
+    .unwrap();
+    assert!(v2.supports_reactions && v2.supports_ephemeral);
+    assert!(v2.supports_input(InputKindV2::Date));
+    assert_eq!(v2.rate_limits.messages_per_second, Some(1));
+    let uploads = v2.file_uploads.as_ref().unwrap();
+    assert!(uploads.accepts("image/png", 1024));
+    assert!(uploads.accepts("application/pdf", 1024));
+    assert!(!uploads.accepts("video/mp4", 1024));
+    assert!(!uploads.accepts("image/png", 2 * 1024 * 1024));
+
+    assert_eq!(
+        ProviderCapabilitiesV2::from_json(&json!({"version": "v3"})),
+        Err(CapabilitiesError::UnsupportedVersion("v3".into()))
+    );
+    assert!(matches!(
+        ProviderCapabilitiesV2::from_json(&json!({"version": "v2", "inputs": ["slider"]})),
+        Err(CapabilitiesError::Invalid(_))
+    ));
+}
+
+#[test]
+fn v2_validation_checks_limits_and_mime_types() {
+    let caps = ProviderCapabilitiesV2 {
+        file_uploads: Some(FileUploadsV2 {
+            max_bytes: None,
+            mime_types: vec!["pdf".into()],
+        }),
+        ..Default::default()
+    };
+    assert_eq!(
+        caps.validate(),
+        Err(CapabilitiesError::InvalidMimeType("pdf".into()))
+    );
+
+    let caps = ProviderCapabilitiesV2 {
+        limits: ProviderLimitsV1 {
+            max_buttons_per_row: Some(4),
+            max_total_buttons: Some(3),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert_eq!(
+        caps.validate(),
+        Err(CapabilitiesError::ButtonsRowExceedsTotal)
+    );
+}
+
+#[test]
+fn checked_in_v2_schema_is_current() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../schemas/messaging/common/provider_capabilities.v2.schema.json");
+    let raw = std::fs::read_to_string(&path).expect("schema file");
+    let checked_in: serde_json::Value = serde_json::from_str(&raw).unwrap();
+    assert_eq!(
+        checked_in,
+        ProviderCapabilitiesV2::json_schema(),
+        "regenerate with `greentic-messaging caps --schema`"
+    );
+
+    let validator = jsonschema::Validator::new(&checked_in).expect("schema compiles");
+    let full = serde_json::to_value(ProviderCapabilitiesV2 {
+        supports_mentions: true,
+        inputs: [InputKindV2::Toggle].into(),
+        file_uploads: Some(FileUploadsV2::default()),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(validator.is_valid(&full));
+    assert!(!validator.is_valid(&json!({"supports_typing": "yes"})));
+}
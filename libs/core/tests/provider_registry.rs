@@ -3,8 +3,9 @@ use greentic_types::pack_manifest::{
     ExtensionInline, ExtensionRef, PackKind, PackManifest, PackSignatures,
 };
 use gsm_core::{
+    ProviderCapsOverrides, load_provider_caps_from_pack_files,
     load_provider_extensions_from_pack_files,
-    pack_extensions::INGRESS_EXTENSION_ID,
+    pack_extensions::{CAPABILITIES_EXTENSION_ID, INGRESS_EXTENSION_ID},
     provider_capabilities::ProviderCapabilitiesV1,
     provider_registry::{CapsSource, ProviderCapsRegistry},
 };
@@ -51,33 +52,155 @@ fn records_encoder_and_source() {
     assert_eq!(record.encoder_ref, None);
 }
 
+#[test]
+fn overrides_win_over_pack_declared_caps() {
+    let mut registry = ProviderCapsRegistry::new();
+    let declared = ProviderCapabilitiesV1 {
+        supports_buttons: true,
+        ..Default::default()
+    };
+    let overridden = ProviderCapabilitiesV1 {
+        supports_markdown: true,
+        ..Default::default()
+    };
+    assert!(registry.register_provider(
+        "p",
+        "1.0.0",
+        CapsSource::Override,
+        overridden.clone(),
+        None
+    ));
+    assert!(!registry.register_provider(
+        "p",
+        "1.0.0",
+        CapsSource::FromPackManifest,
+        declared.clone(),
+        None
+    ));
+    assert_eq!(registry.get_caps("p"), Some(&overridden));
+
+    assert!(registry.register_provider("q", "1.0.0", CapsSource::FromPackManifest, declared, None));
+    assert!(registry.register_provider(
+        "q",
+        "1.0.1",
+        CapsSource::FromProviderCall,
+        overridden.clone(),
+        None
+    ));
+    assert_eq!(
+        registry.get("q").unwrap().caps_source,
+        CapsSource::FromProviderCall
+    );
+}
+
+#[test]
+fn caps_are_discovered_from_default_packs() {
+    let packs_root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../packs");
+    let paths = [
+        "messaging/slack.yaml",
+        "messaging/teams.yaml",
+        "messaging/telegram.yaml",
+    ]
+    .map(std::path::PathBuf::from);
+    let overrides: ProviderCapsOverrides = serde_json::from_value(serde_json::json!({
+        "providers": {
+            "teams-main": {
+                "version": "v1",
+                "supports_adaptive_cards": true,
+                "supports_markdown": true,
+                "supports_html": false,
+                "supports_images": true,
+                "supports_buttons": true,
+                "supports_threads": true,
+                "max_text_len": 28000,
+                "max_payload_bytes": null,
+                "max_actions": 6,
+                "max_buttons_per_row": null,
+                "max_total_buttons": null
+            }
+        }
+    }))
+    .unwrap();
+    let registry = load_provider_caps_from_pack_files(&packs_root, &paths, &overrides).unwrap();
+
+    let slack = registry.get("slack-main").expect("slack discovered");
+    assert_eq!(slack.caps_source, CapsSource::FromPackManifest);
+    assert!(slack.capabilities.supports_threads);
+    assert!(slack.capabilities.supports_images);
+    assert!(!slack.capabilities.supports_adaptive_cards);
+    let slack_v2 = registry.get_caps_v2("slack-main").unwrap();
+    assert!(slack_v2.supports_reactions);
+    assert!(slack_v2.file_uploads.is_some());
+
+    let teams = registry.get("teams-main").expect("teams discovered");
+    assert_eq!(teams.caps_source, CapsSource::Override);
+    assert_eq!(teams.version, registry.get("slack-main").unwrap().version);
+    assert_eq!(teams.capabilities.max_actions, Some(6));
+
+    // Ingress-only adapters do not render and are not registered.
+    assert!(registry.get("telegram-ingress").is_none());
+    assert!(
+        registry
+            .get_caps("telegram-egress")
+            .unwrap()
+            .supports_adaptive_cards
+    );
+    let ids: Vec<_> = registry.records().iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, vec!["slack-main", "teams-main", "telegram-egress"]);
+}
+
 #[test]
 fn loads_provider_extensions_from_pack() {
     let temp = TempDir::new().expect("temp dir");
     let gtpack_path = temp.path().join("provider.gtpack");
 
-    let extensions = std::collections::BTreeMap::from([(
-        INGRESS_EXTENSION_ID.to_string(),
-        ExtensionRef {
-            kind: INGRESS_EXTENSION_ID.to_string(),
-            version: "1.0.0".into(),
-            digest: None,
-            location: None,
-            inline: Some(ExtensionInline::Other(serde_json::json!({
-                "messaging.slack.bot": {
-                    "runtime": {
-                        "component_ref": "slack-adapter@1.0.0",
-                        "export": "run",
-                        "world": "greentic:provider/schema-core@1.0.0"
-                    },
-                    "capabilities": {
-                        "supports_webhook_validation": true,
-                        "content_types": ["application/json"]
+    let extensions = std::collections::BTreeMap::from([
+        (
+            INGRESS_EXTENSION_ID.to_string(),
+            ExtensionRef {
+                kind: INGRESS_EXTENSION_ID.to_string(),
+                version: "1.0.0".into(),
+                digest: None,
+                location: None,
+                inline: Some(ExtensionInline::Other(serde_json::json!({
+                    "messaging.slack.bot": {
+                        "runtime": {
+                            "component_ref": "slack-adapter@1.0.0",
+                            "export": "run",
+                            "world": "greentic:provider/schema-core@1.0.0"
+                        },
+                        "capabilities": {
+                            "supports_webhook_validation": true,
+                            "content_types": ["application/json"]
+                        }
+                    }
+                }))),
+            },
+        ),
+        (
+            CAPABILITIES_EXTENSION_ID.to_string(),
+            ExtensionRef {
+                kind: CAPABILITIES_EXTENSION_ID.to_string(),
+                version: "1.0.0".into(),
+                digest: None,
+                location: None,
+                inline: Some(ExtensionInline::Other(serde_json::json!({
+                    "messaging.slack.bot": serde_json::to_value(ProviderCapabilitiesV1 {
+                        supports_markdown: true,
+                        max_text_len: Some(3000),
+                        ..Default::default()
+                    })
+                    .unwrap(),
+                    "messaging.broken.bot": {"version": "v1", "supports_markdown": "yes"},
+                    "messaging.teams.bot": {
+                        "version": "v2",
+                        "supports_adaptive_cards": true,
+                        "supports_ephemeral": true
                     }
-                }
-            }))),
-        },
-    )]);
+                }))),
+            },
+        ),
+    ]);
 
     let manifest = PackManifest {
         schema_version: "pack-v1".into(),
@@ -114,4 +237,22 @@ fn loads_provider_extensions_from_pack() {
         .expect("ingress provider registered");
     assert_eq!(provider.runtime.component_ref, "slack-adapter@1.0.0");
     assert!(provider.capabilities.supports_webhook_validation);
+
+    let caps = ProviderCapsRegistry::from_packs(
+        &Default::default(),
+        &registry,
+        &ProviderCapsOverrides::default(),
+    );
+    let record = caps.get("messaging.slack.bot").expect("declared caps");
+    assert_eq!(record.caps_source, CapsSource::FromPackManifest);
+    assert_eq!(record.capabilities.max_text_len, Some(3000));
+
+    // A malformed entry is skipped without dropping its neighbours, and v2 documents keep
+    // their v2-only flags.
+    assert!(!registry.capabilities.contains_key("messaging.broken.bot"));
+    let teams = caps
+        .get_caps_v2("messaging.teams.bot")
+        .expect("v2 caps declared");
+    assert!(teams.supports_ephemeral);
+    assert!(teams.supports_adaptive_cards);
 }
@@ -1,9 +1,9 @@
 use gsm_core::{
-    compute_render_outcome,
+    compute_render_outcome, planner_policy,
     provider_capabilities::ProviderCapabilitiesV1,
     provider_registry::{CapsSource, ProviderCapsRegistry},
     render_mode::RenderMode,
-    render_plan::RenderTier,
+    render_plan::Tier,
     render_planner::PlannerCard,
 };
 
@@ -17,11 +17,18 @@ fn legacy_mode_skips_planner() {
         images: vec![],
     };
 
-    let outcome = compute_render_outcome(RenderMode::Legacy, "provider-x", &card, &registry, None);
+    let outcome = compute_render_outcome(
+        RenderMode::Legacy,
+        "provider-x",
+        &card.to_ir(),
+        &registry,
+        None,
+        &planner_policy(),
+    );
 
     assert!(outcome.plan.is_none());
     assert_eq!(outcome.warnings.len(), 0);
-    assert_eq!(outcome.tier(), RenderTier::TierD);
+    assert_eq!(outcome.tier(), Tier::Basic);
 }
 
 #[test]
@@ -39,10 +46,17 @@ fn planned_mode_uses_registry_caps() {
         images: vec![],
     };
 
-    let outcome = compute_render_outcome(RenderMode::Planned, "provider-y", &card, &registry, None);
+    let outcome = compute_render_outcome(
+        RenderMode::Planned,
+        "provider-y",
+        &card.to_ir(),
+        &registry,
+        None,
+        &planner_policy(),
+    );
 
     let plan = outcome.plan.expect("plan exists");
-    assert_eq!(plan.tier, RenderTier::TierA);
+    assert_eq!(plan.tier, Tier::Premium);
     assert!(outcome.warnings.is_empty());
 }
 
@@ -59,12 +73,36 @@ fn planned_mode_falls_back_to_default_caps() {
     let outcome = compute_render_outcome(
         RenderMode::Planned,
         "unknown",
-        &card,
+        &card.to_ir(),
         &registry,
         Some(&fallback_caps),
+        &planner_policy(),
     );
 
-    // default caps do not support images, so expect TierD warning path
-    assert_eq!(outcome.tier(), RenderTier::TierD);
+    // default caps do not support images, so expect the basic-tier warning path
+    assert_eq!(outcome.tier(), Tier::Basic);
     assert!(!outcome.warnings.is_empty());
 }
+
+#[test]
+fn planned_mode_skips_providers_without_known_caps() {
+    let registry = ProviderCapsRegistry::new();
+    let card = PlannerCard {
+        title: Some("Hello".into()),
+        text: Some("World".into()),
+        actions: vec![],
+        images: vec![],
+    };
+
+    let outcome = compute_render_outcome(
+        RenderMode::Planned,
+        "unknown",
+        &card.to_ir(),
+        &registry,
+        None,
+        &planner_policy(),
+    );
+
+    assert!(outcome.plan.is_none());
+    assert!(outcome.warnings.is_empty());
+}
@@ -1,13 +1,14 @@
-use gsm_core::render_plan::{RenderPlan, RenderTier, RenderWarning};
+use gsm_core::render_plan::{RenderPlan, RenderWarning, Tier};
 use serde_json::json;
 
 #[test]
 fn render_plan_serializes_and_roundtrips() {
     let plan = RenderPlan {
-        tier: RenderTier::TierB,
+        tier: Tier::Advanced,
         summary_text: Some("Summarized body".to_string()),
         actions: vec!["approve".into(), "reject".into()],
         attachments: vec!["https://example.com/attachment".into()],
+        elements: Vec::new(),
         warnings: vec![RenderWarning {
             code: "text_truncated".into(),
             message: Some("Body trimmed to 256 chars".into()),
@@ -21,7 +22,7 @@ fn render_plan_serializes_and_roundtrips() {
 
     let json_value = serde_json::to_value(&plan).expect("serialize");
     let expected = json!({
-        "tier": "tier_b",
+        "tier": "advanced",
         "summary_text": "Summarized body",
         "actions": ["approve", "reject"],
         "attachments": ["https://example.com/attachment"],
@@ -41,3 +42,23 @@ fn render_plan_serializes_and_roundtrips() {
     let roundtrip: RenderPlan = serde_json::from_str(&json_text).expect("deserialize");
     assert_eq!(roundtrip, plan);
 }
+
+#[test]
+fn plans_with_legacy_tier_names_still_parse() {
+    let tiers: Vec<Tier> =
+        serde_json::from_value(json!(["tier_a", "tier_b", "tier_c", "tier_d", "premium"])).unwrap();
+    assert_eq!(
+        tiers,
+        vec![
+            Tier::Premium,
+            Tier::Advanced,
+            Tier::Advanced,
+            Tier::Basic,
+            Tier::Premium
+        ]
+    );
+    assert_eq!(
+        serde_json::to_value(Tier::Premium).unwrap(),
+        json!("premium")
+    );
+}
@@ -1,6 +1,6 @@
 use gsm_core::{
     plan_render, planner_policy, provider_capabilities::ProviderCapabilitiesV1,
-    render_plan::RenderTier, render_planner::PlannerCard,
+    render_planner::PlannerCard,
 };
 use serde::Deserialize;
 use std::fs;
@@ -154,9 +154,9 @@ fn tier_caps_to_provider(c: &TierCaps) -> ProviderCapabilitiesV1 {
 fn tier_selection_fixtures() {
     let fixtures_dir = fixtures_dir();
     let paths = [
-        "tier_a_supported.json",
-        "tier_a_downgrades_to_b.json",
-        "tier_a_downgrades_to_d.json",
+        "tier_premium_supported.json",
+        "tier_premium_downgrades_to_advanced.json",
+        "tier_premium_downgrades_to_basic.json",
     ];
     for name in paths {
         let path = fixtures_dir.join(name);
@@ -165,13 +165,7 @@ fn tier_selection_fixtures() {
 
         let caps = tier_caps_to_provider(&fixture.capabilities);
         let plan = plan_render(&fixture.input, &caps, &planner_policy());
-        let tier_name = match plan.tier {
-            RenderTier::TierA => "tier_a",
-            RenderTier::TierB => "tier_b",
-            RenderTier::TierC => "tier_c",
-            RenderTier::TierD => "tier_d",
-        };
-        assert_eq!(tier_name, fixture.expected_tier);
+        assert_eq!(plan.tier.as_str(), fixture.expected_tier);
         let warnings = serde_json::to_value(&plan.warnings).expect("warnings to value");
         assert_eq!(
             warnings,
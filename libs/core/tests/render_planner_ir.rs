@@ -0,0 +1,446 @@
+#![cfg(feature = "adaptive-cards")]
+
+use gsm_core::messaging_card::ir::{Element, InputKind, IrAction, MessageCardIrBuilder};
+use gsm_core::messaging_card::{MessageCardEngine, RenderSpec};
+use gsm_core::{
+    ElementDisposition, MessageCardIr, PlannerPolicies, PlannerPolicy, ProviderCapabilitiesV1,
+    RenderMode, RenderModeConfig, RenderPreference, Tier, TruncationStrategy, apply_plan,
+    plan_card, planner_policy,
+};
+use serde_json::json;
+
+fn card() -> MessageCardIr {
+    MessageCardIrBuilder::default()
+        .title("Deploy")
+        .primary_text("Build **41** is ready", true)
+        .fact("Env", "staging")
+        .image("https://example.com/chart.png".into(), None)
+        .input(
+            Some("Reason".into()),
+            InputKind::Text,
+            Some("reason".into()),
+            Vec::new(),
+        )
+        .open_url("Logs", "https://example.com/logs")
+        .postback("Approve", json!({"ok": true}))
+        .build()
+}
+
+fn chat_caps() -> ProviderCapabilitiesV1 {
+    ProviderCapabilitiesV1 {
+        supports_markdown: true,
+        supports_buttons: true,
+        max_actions: Some(1),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn plan_records_a_decision_per_element_and_action() {
+    let plan = plan_card(&card(), &chat_caps(), &planner_policy());
+
+    let decisions: Vec<_> = plan
+        .elements
+        .iter()
+        .map(|d| (d.path.as_str(), d.kind.as_str(), d.disposition))
+        .collect();
+    assert_eq!(
+        decisions,
+        vec![
+            ("/elements/0", "text", ElementDisposition::Keep),
+            ("/elements/1", "fact_set", ElementDisposition::Keep),
+            ("/elements/2", "image", ElementDisposition::Drop),
+            ("/elements/3", "input", ElementDisposition::Drop),
+            ("/actions/0", "open_url", ElementDisposition::Keep),
+            ("/actions/1", "postback", ElementDisposition::Drop),
+        ]
+    );
+    assert_eq!(
+        plan.decision("/actions/1")
+            .and_then(|d| d.reason.as_deref()),
+        Some("actions_truncated")
+    );
+    assert_eq!(plan.decision("/elements/3").unwrap().tier, Tier::Premium);
+    assert_eq!(plan.dropped().count(), 3);
+    assert_eq!(plan.tier, Tier::Basic);
+    assert_eq!(plan.actions, vec!["Logs".to_string()]);
+    assert_eq!(
+        plan.summary_text.as_deref(),
+        Some(
+            "Deploy\nBuild **41** is ready\nEnv: staging\nActions: Logs (https://example.com/logs)"
+        )
+    );
+}
+
+#[test]
+fn applied_plan_is_what_renderers_receive() {
+    let ir = card();
+    let caps = ProviderCapabilitiesV1 {
+        supports_images: true,
+        ..Default::default()
+    };
+    let plan = plan_card(&ir, &caps, &planner_policy());
+    let planned = apply_plan(&ir, &plan);
+
+    assert!(planned.actions.is_empty());
+    assert_eq!(
+        planned.elements,
+        vec![
+            Element::Text {
+                text: "Build **41** is ready".into(),
+                markdown: false,
+            },
+            Element::Text {
+                text: "Env: staging".into(),
+                markdown: false,
+            },
+            Element::Image {
+                url: "https://example.com/chart.png".into(),
+                alt: None,
+            },
+            Element::Text {
+                text: "Logs: https://example.com/logs".into(),
+                markdown: false,
+            },
+        ]
+    );
+    assert!(
+        planned
+            .meta
+            .warnings
+            .contains(&"plan.inputs_not_supported".to_string())
+    );
+
+    let engine = MessageCardEngine::bootstrap();
+    let spec = RenderSpec::Card(Box::new(ir));
+    let snapshot = engine
+        .render_planned("slack", &spec, &plan)
+        .expect("slack renderer");
+    let rendered = snapshot.ir.expect("card snapshot");
+    assert!(rendered.actions.is_empty());
+    assert!(
+        !rendered
+            .elements
+            .iter()
+            .any(|element| matches!(element, Element::Input { .. }))
+    );
+}
+
+#[test]
+fn render_mode_is_selected_per_tenant() {
+    let config: RenderModeConfig = serde_json::from_value(json!({
+        "default": "legacy",
+        "tenants": {"acme": "planned"}
+    }))
+    .unwrap();
+    assert_eq!(config.mode_for("acme"), RenderMode::Planned);
+    assert_eq!(config.mode_for("globex"), RenderMode::Legacy);
+    assert_eq!(
+        RenderModeConfig::default().mode_for("globex"),
+        RenderMode::Legacy
+    );
+}
+
+fn slack_caps_registry() -> gsm_core::ProviderCapsRegistry {
+    let mut registry = gsm_core::ProviderCapsRegistry::new();
+    registry.register_provider(
+        "slack",
+        "0.1.0",
+        gsm_core::CapsSource::Override,
+        gsm_core::ProviderCapabilitiesV1 {
+            supports_markdown: true,
+            supports_buttons: true,
+            ..Default::default()
+        },
+        None,
+    );
+    registry
+}
+
+#[test]
+fn attach_render_plan_stores_plan_in_meta() {
+    let engine = MessageCardEngine::bootstrap();
+    let registry = slack_caps_registry();
+    let mut out = gsm_core::OutMessage {
+        ctx: gsm_core::make_tenant_ctx("acme".into(), None, None),
+        tenant: "acme".into(),
+        platform: gsm_core::Platform::Slack,
+        chat_id: "C1".into(),
+        thread_id: None,
+        kind: gsm_core::OutKind::Card,
+        text: None,
+        card: Some(gsm_core::MessageCard {
+            title: Some("Hello".into()),
+            ..Default::default()
+        }),
+        meta: Default::default(),
+    };
+
+    let legacy = gsm_core::attach_render_plan(
+        &mut out,
+        RenderMode::Legacy,
+        "slack",
+        &engine,
+        &registry,
+        None,
+        &planner_policy(),
+    )
+    .unwrap();
+    assert!(legacy.is_none());
+    assert!(!out.meta.contains_key(gsm_core::RENDER_PLAN_META_KEY));
+
+    let unknown = gsm_core::attach_render_plan(
+        &mut out,
+        RenderMode::Planned,
+        "unknown-provider",
+        &engine,
+        &registry,
+        None,
+        &planner_policy(),
+    )
+    .unwrap();
+    assert!(unknown.is_none());
+    assert!(!out.meta.contains_key(gsm_core::RENDER_PLAN_META_KEY));
+
+    let outcome = gsm_core::attach_render_plan(
+        &mut out,
+        RenderMode::Planned,
+        "slack",
+        &engine,
+        &registry,
+        None,
+        &planner_policy(),
+    )
+    .unwrap()
+    .expect("planned");
+    let stored: gsm_core::RenderPlan =
+        serde_json::from_value(out.meta[gsm_core::RENDER_PLAN_META_KEY].clone()).unwrap();
+    assert_eq!(Some(stored), outcome.plan);
+}
+
+#[test]
+fn attach_render_plan_strips_blocked_text_links() {
+    let engine = MessageCardEngine::bootstrap();
+    let registry = slack_caps_registry();
+    let mut out = gsm_core::OutMessage {
+        ctx: gsm_core::make_tenant_ctx("acme".into(), None, None),
+        tenant: "acme".into(),
+        platform: gsm_core::Platform::Slack,
+        chat_id: "C1".into(),
+        thread_id: None,
+        kind: gsm_core::OutKind::Card,
+        text: None,
+        card: Some(gsm_core::MessageCard {
+            body: vec![gsm_core::CardBlock::Text {
+                text: "See [logs](https://example.com/logs/1) or [docs](https://example.com/docs)"
+                    .into(),
+                markdown: true,
+            }],
+            ..Default::default()
+        }),
+        meta: Default::default(),
+    };
+    let policy = PlannerPolicy {
+        url_deny: vec!["https://example.com/logs".into()],
+        ..Default::default()
+    };
+
+    let outcome = gsm_core::attach_render_plan(
+        &mut out,
+        RenderMode::Planned,
+        "slack",
+        &engine,
+        &registry,
+        None,
+        &policy,
+    )
+    .unwrap()
+    .expect("planned");
+    let Some(gsm_core::CardBlock::Text { text, .. }) =
+        out.card.as_ref().and_then(|card| card.body.first())
+    else {
+        panic!("text block kept");
+    };
+    assert_eq!(text, "See logs or [docs](https://example.com/docs)");
+    assert!(outcome.warnings.iter().any(|w| w.code == "url_blocked"));
+}
+
+#[test]
+fn policy_limits_buttons_and_blocks_urls() {
+    let policy = PlannerPolicy {
+        max_buttons: Some(1),
+        url_deny: vec!["https://example.com/logs".into()],
+        images_as_links: true,
+        ..Default::default()
+    };
+    let caps = ProviderCapabilitiesV1 {
+        supports_buttons: true,
+        supports_adaptive_cards: true,
+        ..Default::default()
+    };
+    let mut ir = card();
+    ir.actions.push(IrAction::Postback {
+        title: "Reject".into(),
+        data: json!({"ok": false}),
+    });
+    let plan = plan_card(&ir, &caps, &policy);
+
+    let reason = |path: &str| plan.decision(path).and_then(|d| d.reason.clone());
+    assert_eq!(reason("/elements/2").as_deref(), Some("images_as_links"));
+    assert_eq!(reason("/actions/0").as_deref(), Some("url_blocked"));
+    assert_eq!(reason("/actions/1"), None);
+    assert_eq!(reason("/actions/2").as_deref(), Some("actions_truncated"));
+    assert_eq!(plan.actions, vec!["Approve".to_string()]);
+    assert!(plan.warnings.iter().any(|w| w.code == "url_blocked"));
+    assert_eq!(plan.tier, Tier::Advanced);
+    assert_eq!(
+        plan.debug.as_ref().unwrap()["policy"]["max_buttons"],
+        json!(1)
+    );
+}
+
+#[test]
+fn text_preference_plans_plain_text() {
+    let policy = PlannerPolicy {
+        prefer: RenderPreference::Text,
+        truncation: TruncationStrategy::Ellipsis,
+        ..Default::default()
+    };
+    let caps = ProviderCapabilitiesV1 {
+        supports_adaptive_cards: true,
+        supports_buttons: true,
+        supports_images: true,
+        supports_markdown: true,
+        max_text_len: Some(12),
+        ..Default::default()
+    };
+    let plan = plan_card(&card(), &caps, &policy);
+    assert_eq!(plan.tier, Tier::Basic);
+    assert_eq!(plan.summary_text.as_deref(), Some("Deploy\nBuil…"));
+    assert_eq!(
+        plan.decision("/actions/0").unwrap().disposition,
+        ElementDisposition::Downgrade
+    );
+
+    let words = PlannerPolicy {
+        truncation: TruncationStrategy::WordBoundary,
+        ..Default::default()
+    };
+    let plan = plan_card(&card(), &caps, &words);
+    assert_eq!(plan.summary_text.as_deref(), Some("Deploy\nBuild"));
+}
+
+#[test]
+fn policies_resolve_most_specific_match() {
+    let policies: PlannerPolicies = serde_json::from_value(json!({
+        "default": {"max_buttons": 5},
+        "tenants": {
+            "acme": {
+                "default": {"images_as_links": true},
+                "providers": {"sms": {"prefer": "text"}}
+            }
+        }
+    }))
+    .unwrap();
+    assert_eq!(policies.policy_for("globex", "sms").max_buttons, Some(5));
+    assert!(policies.policy_for("acme", "slack").images_as_links);
+    let sms = policies.policy_for("acme", "sms");
+    assert_eq!(sms.prefer, RenderPreference::Text);
+    assert!(!sms.images_as_links);
+
+    let policy = PlannerPolicy {
+        url_allow: vec!["https://example.com/".into()],
+        url_deny: vec!["https://example.com/private".into()],
+        ..Default::default()
+    };
+    assert!(policy.url_allowed("https://example.com/docs"));
+    assert!(!policy.url_allowed("https://example.com/private/x"));
+    assert!(!policy.url_allowed("https://other.example/"));
+    assert!(!policy.url_allowed("https://example.com.evil.net/docs"));
+    assert!(!policy.url_allowed("https://example.com@evil.net/docs"));
+    assert!(!policy.url_allowed("http://example.com/docs"));
+}
+
+#[test]
+fn legacy_mode_still_removes_blocked_urls() {
+    let engine = MessageCardEngine::bootstrap();
+    let registry = slack_caps_registry();
+    let mut out = gsm_core::OutMessage {
+        ctx: gsm_core::make_tenant_ctx("acme".into(), None, None),
+        tenant: "acme".into(),
+        platform: gsm_core::Platform::Slack,
+        chat_id: "C1".into(),
+        thread_id: None,
+        kind: gsm_core::OutKind::Card,
+        text: None,
+        card: Some(gsm_core::MessageCard {
+            body: vec![
+                gsm_core::CardBlock::Text {
+                    text: "[phish](https://example.com.evil.net/login)".into(),
+                    markdown: true,
+                },
+                gsm_core::CardBlock::Image {
+                    url: "https://evil.net/pixel.png".into(),
+                    alt: None,
+                },
+            ],
+            actions: vec![
+                gsm_core::CardAction::OpenUrl {
+                    title: "Docs".into(),
+                    url: "https://example.com/docs".into(),
+                    jwt: false,
+                },
+                gsm_core::CardAction::OpenUrl {
+                    title: "Login".into(),
+                    url: "https://example.com.evil.net/login".into(),
+                    jwt: false,
+                },
+            ],
+            adaptive: Some(json!({
+                "type": "AdaptiveCard",
+                "version": "1.5",
+                "body": [
+                    {"type": "Image", "url": "https://evil.net/pixel.png"},
+                    {"type": "TextBlock", "text": "Hi", "selectAction": {
+                        "type": "Action.OpenUrl", "url": "https://evil.net/"
+                    }}
+                ],
+                "actions": [
+                    {"type": "Action.OpenUrl", "title": "Docs", "url": "https://example.com/docs"},
+                    {"type": "Action.OpenUrl", "title": "Login", "url": "https://evil.net/login"}
+                ]
+            })),
+            ..Default::default()
+        }),
+        meta: Default::default(),
+    };
+    let policy = PlannerPolicy {
+        url_allow: vec!["https://example.com".into()],
+        ..Default::default()
+    };
+
+    let outcome = gsm_core::attach_render_plan(
+        &mut out,
+        RenderMode::Legacy,
+        "slack",
+        &engine,
+        &registry,
+        None,
+        &policy,
+    )
+    .unwrap();
+    assert!(outcome.is_none());
+    let card = out.card.expect("card kept");
+    assert_eq!(
+        card.body,
+        vec![gsm_core::CardBlock::Text {
+            text: "phish".into(),
+            markdown: true,
+        }]
+    );
+    assert_eq!(card.actions.len(), 1);
+    let adaptive = card.adaptive.expect("adaptive kept");
+    assert_eq!(adaptive["body"].as_array().unwrap().len(), 1);
+    assert!(adaptive["body"][0].get("selectAction").is_none());
+    assert_eq!(adaptive["actions"].as_array().unwrap().len(), 1);
+}
@@ -77,7 +77,7 @@ fn webex_oauth_downgrades_to_card() {
 }
 
 #[test]
-fn whatsapp_oauth_downgrades_to_template_link() {
+fn whatsapp_oauth_downgrades_to_cta_url() {
     let payload = render_oauth_for("whatsapp");
     assert_eq!(payload, load_fixture("whatsapp/oauth_downgrade.json"));
 }
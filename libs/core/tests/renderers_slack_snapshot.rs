@@ -2,7 +2,9 @@
 
 use gsm_core::messaging_card::ir::{InputChoice, InputKind};
 use gsm_core::messaging_card::tier::Tier;
-use gsm_core::messaging_card::{MessageCardIr, MessageCardIrBuilder, SlackRenderer};
+use gsm_core::messaging_card::{
+    MessageCardIr, MessageCardIrBuilder, SlackRenderer, SlackViewTarget,
+};
 use gsm_core::{AppLink, PlatformRenderer};
 use serde_json::{Value, json};
 
@@ -24,6 +26,125 @@ fn slack_modal_snapshot() {
     assert_eq!(rendered.payload, load_fixture("slack/modal.json"));
 }
 
+#[test]
+fn slack_modal_view_carries_callback_and_state() {
+    let renderer = SlackRenderer;
+    let ir = sample_ir(true);
+    let target = SlackViewTarget::Modal {
+        trigger_id: "trigger-1".into(),
+    };
+    let state = json!({"flow": "expenses", "step": 2});
+    let rendered = renderer.render_view(&ir, &target, "expense_form", Some(&state));
+    assert!(rendered.used_modal);
+    assert_eq!(rendered.payload["method"], "views.open");
+    assert_eq!(rendered.payload["trigger_id"], "trigger-1");
+
+    let mut expected_view = load_fixture("slack/modal.json");
+    expected_view["callback_id"] = json!("expense_form");
+    expected_view["private_metadata"] = json!("{\"flow\":\"expenses\",\"step\":2}");
+    assert_eq!(rendered.payload["view"], expected_view);
+}
+
+#[test]
+fn slack_home_view_publishes_blocks() {
+    let renderer = SlackRenderer;
+    let ir = sample_ir(false);
+    let target = SlackViewTarget::Home {
+        user_id: "U123".into(),
+    };
+    let rendered = renderer.render_view(&ir, &target, "home", None);
+    assert!(!rendered.used_modal);
+    assert_eq!(rendered.payload["method"], "views.publish");
+    assert_eq!(rendered.payload["user_id"], "U123");
+    assert_eq!(rendered.payload["view"]["type"], "home");
+    assert!(rendered.payload["view"].get("private_metadata").is_none());
+    assert_eq!(
+        rendered.payload["view"]["blocks"],
+        load_fixture("slack/basic.json")["blocks"]
+    );
+}
+
+#[test]
+fn slack_view_drops_oversized_state() {
+    let renderer = SlackRenderer;
+    let ir = sample_ir(true);
+    let target = SlackViewTarget::Modal {
+        trigger_id: "trigger-1".into(),
+    };
+    let state = json!({"blob": "x".repeat(4000)});
+    let rendered = renderer.render_view(&ir, &target, "expense_form", Some(&state));
+    assert!(rendered.limit_exceeded);
+    assert!(
+        rendered
+            .warnings
+            .iter()
+            .any(|w| w == "slack.private_metadata_dropped")
+    );
+    assert!(rendered.payload["view"].get("private_metadata").is_none());
+}
+
+#[test]
+fn slack_dialog_buttons_use_dialog_action_id() {
+    let renderer = SlackRenderer;
+    let ir = MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .title("Expenses")
+        .dialog("Open form", json!({"form": "expense"}))
+        .build();
+    let rendered = renderer.render(&ir);
+    let blocks = rendered.payload["blocks"].as_array().expect("blocks");
+    let actions = blocks.last().expect("actions block");
+    assert_eq!(actions["elements"][0]["action_id"], "dialog_0");
+}
+
+#[test]
+fn slack_markdown_text_converts_to_mrkdwn() {
+    let renderer = SlackRenderer;
+    let ir = MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .primary_text(
+            "Deploy **now** & see [notes](https://example.com/notes)",
+            true,
+        )
+        .build();
+    let rendered = renderer.render(&ir);
+    let section = rendered.payload["blocks"]
+        .as_array()
+        .expect("blocks")
+        .iter()
+        .find(|block| block["text"]["type"] == "mrkdwn")
+        .expect("mrkdwn section");
+    assert_eq!(
+        section["text"]["text"],
+        "Deploy *now* &amp; see <https://example.com/notes|notes>"
+    );
+}
+
+#[test]
+fn slack_long_text_splits_into_sections() {
+    let renderer = SlackRenderer;
+    let text = "Line of release notes. ".repeat(200);
+    let ir = MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .primary_text(&text, true)
+        .build();
+    let rendered = renderer.render(&ir);
+    let sections: Vec<_> = rendered.payload["blocks"]
+        .as_array()
+        .expect("blocks")
+        .iter()
+        .filter(|block| block["type"] == "section")
+        .collect();
+    assert_eq!(sections.len(), 2);
+    assert!(
+        sections
+            .iter()
+            .all(|s| s["text"]["text"].as_str().unwrap().chars().count() <= 3000)
+    );
+    assert!(rendered.warnings.iter().any(|w| w == "slack.text_split"));
+    assert!(rendered.continuations.is_empty());
+}
+
 fn sample_ir(include_input: bool) -> MessageCardIr {
     let mut builder = MessageCardIrBuilder::default()
         .tier(Tier::Premium)
@@ -40,7 +40,34 @@ mod adaptive_snapshots {
         });
         ir.meta.adaptive_payload = Some(adaptive.clone());
         let rendered = renderer.render(&ir);
-        assert_eq!(rendered.payload, adaptive);
+        let mut expected = adaptive;
+        expected["version"] = json!("1.5");
+        assert_eq!(rendered.payload, expected, "lowered to the Teams version");
+        assert!(rendered.warnings.is_empty());
+    }
+
+    #[test]
+    fn teams_replaces_unsupported_adaptive_features() {
+        let renderer = TeamsRenderer;
+        let mut ir = sample_ir();
+        ir.meta.adaptive_payload = Some(json!({
+            "type": "AdaptiveCard",
+            "version": "1.6",
+            "body": [{
+                "type": "Carousel",
+                "pages": [],
+                "fallback": { "type": "TextBlock", "text": "Open in Teams desktop" }
+            }]
+        }));
+        let rendered = renderer.render(&ir);
+        assert_eq!(
+            rendered.payload["body"][0],
+            json!({ "type": "TextBlock", "text": "Open in Teams desktop" })
+        );
+        assert_eq!(
+            rendered.warnings,
+            vec!["adaptive.host_downgraded".to_string()]
+        );
     }
 
     #[test]
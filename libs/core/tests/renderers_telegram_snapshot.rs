@@ -1,14 +1,19 @@
 #![cfg(feature = "adaptive-cards")]
 
-use gsm_core::messaging_card::ir::{InputChoice, InputKind};
+use std::sync::Arc;
+
+use gsm_core::messaging_card::ir::{InputChoice, InputKind, KeyboardKind, KeyboardLayout};
 use gsm_core::messaging_card::tier::Tier;
-use gsm_core::messaging_card::{MessageCardIr, MessageCardIrBuilder, TelegramRenderer};
+use gsm_core::messaging_card::{
+    InMemoryPostbackTokenStore, InteractionOutcome, MessageCardEngine, MessageCardIr,
+    MessageCardIrBuilder, PostbackTokenStore, TelegramRenderer, resolve_callback_data,
+};
 use gsm_core::{AppLink, PlatformRenderer};
 use serde_json::{Value, json};
 
 #[test]
 fn telegram_basic_snapshot() {
-    let renderer = TelegramRenderer;
+    let renderer = TelegramRenderer::default();
     let ir = sample_ir(false);
     let rendered = renderer.render(&ir);
     assert_eq!(rendered.payload, load_fixture("telegram/basic.json"));
@@ -17,7 +22,7 @@ fn telegram_basic_snapshot() {
 
 #[test]
 fn telegram_inputs_emit_warnings_and_prompts() {
-    let renderer = TelegramRenderer;
+    let renderer = TelegramRenderer::default();
     let ir = sample_ir(true);
     let rendered = renderer.render(&ir);
     assert_eq!(rendered.payload, load_fixture("telegram/interactive.json"));
@@ -29,6 +34,207 @@ fn telegram_inputs_emit_warnings_and_prompts() {
     );
 }
 
+#[test]
+fn telegram_large_postback_uses_token_store() {
+    let store = Arc::new(InMemoryPostbackTokenStore::new());
+    let renderer = TelegramRenderer::default().with_token_store(store.clone());
+    let data = json!({"action": "approve", "request_id": "r".repeat(80)});
+    let ir = MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .title("Approval")
+        .postback("Approve", data.clone())
+        .build();
+
+    let rendered = renderer.render(&ir);
+    let callback = rendered.payload["reply_markup"]["inline_keyboard"][0][0]["callback_data"]
+        .as_str()
+        .expect("callback data");
+    assert!(callback.len() <= 64);
+    assert!(!rendered.limit_exceeded);
+    assert_eq!(store.resolve(callback), Some(data.clone()));
+    assert_eq!(resolve_callback_data(store.as_ref(), callback), Some(data));
+}
+
+#[test]
+fn telegram_large_postback_without_store_is_dropped() {
+    let renderer = TelegramRenderer::default();
+    let ir = MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .title("Approval")
+        .postback("Approve", json!({"request_id": "r".repeat(80)}))
+        .build();
+
+    let rendered = renderer.render(&ir);
+    assert!(rendered.payload.get("reply_markup").is_none());
+    assert!(rendered.limit_exceeded);
+    assert!(
+        rendered
+            .warnings
+            .iter()
+            .any(|w| w == "telegram.callback_data_dropped")
+    );
+}
+
+#[test]
+fn engine_tokenizes_large_postbacks_by_default() {
+    let engine = MessageCardEngine::bootstrap();
+    let data = json!({"action": "approve", "request_id": "r".repeat(80)});
+    let ir = MessageCardIrBuilder::default()
+        .tier(Tier::Basic)
+        .title("Approval")
+        .postback("Approve", data.clone())
+        .build();
+
+    let payload = engine.render("telegram", &ir).expect("rendered");
+    let callback = payload["reply_markup"]["inline_keyboard"][0][0]["callback_data"]
+        .as_str()
+        .expect("callback data");
+    assert!(callback.starts_with("pb:"));
+    let mut resolved = engine
+        .resolve_callback_data(callback)
+        .expect("token resolves");
+    engine.record_interaction("telegram", &mut resolved);
+    assert_eq!(resolved, data);
+}
+
+#[test]
+fn correlated_postbacks_stay_inline_and_round_trip() {
+    let engine = MessageCardEngine::bootstrap();
+    let data = json!({"decision": "approve"});
+    let mut ir = MessageCardIrBuilder::default()
+        .tier(Tier::Basic)
+        .title("Expense")
+        .postback("Approve", data.clone())
+        .build();
+    ir.meta.card_id = Some("expense-2026-10-18".into());
+
+    let payload = engine.render("telegram", &ir).expect("rendered");
+    let callback = payload["reply_markup"]["inline_keyboard"][0][0]["callback_data"]
+        .as_str()
+        .expect("callback data");
+    assert!(callback.len() <= 64, "{callback} fits callback_data");
+    assert!(
+        !callback.starts_with("pb:"),
+        "small stamped payloads stay inline"
+    );
+
+    let mut inbound = engine
+        .resolve_callback_data(callback)
+        .expect("inline data parses");
+    let InteractionOutcome::Recorded(correlation) =
+        engine.record_interaction("telegram", &mut inbound)
+    else {
+        panic!("stamp recorded");
+    };
+    assert_eq!(correlation.card_id, "expense-2026-10-18");
+    assert_eq!(inbound, data);
+}
+
+#[test]
+fn telegram_keyboard_layout_from_card() {
+    let renderer = TelegramRenderer::default();
+    let mut builder = MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .title("Pick one");
+    for option in ["A", "B", "C", "D"] {
+        builder = builder.postback(option, json!(option));
+    }
+
+    let inline = builder
+        .keyboard(KeyboardLayout {
+            buttons_per_row: Some(2),
+            ..Default::default()
+        })
+        .build();
+    let rendered = renderer.render(&inline);
+    let rows = rendered.payload["reply_markup"]["inline_keyboard"]
+        .as_array()
+        .expect("rows");
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].as_array().unwrap().len(), 2);
+
+    let mut reply = inline.clone();
+    reply.meta.keyboard = Some(KeyboardLayout {
+        kind: KeyboardKind::Reply,
+        buttons_per_row: Some(4),
+        one_time: true,
+        resize: true,
+    });
+    let rendered = renderer.render(&reply);
+    assert_eq!(
+        rendered.payload["reply_markup"],
+        json!({
+            "keyboard": [[{"text": "A"}, {"text": "B"}, {"text": "C"}, {"text": "D"}]],
+            "one_time_keyboard": true,
+            "resize_keyboard": true,
+        })
+    );
+}
+
+#[test]
+fn telegram_uploaded_images_render_as_photos() {
+    let renderer = TelegramRenderer::default();
+    let mut ir = MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .image("https://example.com/chart.png".into(), Some("Chart".into()))
+        .postback("Refresh", json!("refresh"))
+        .build();
+    ir.meta
+        .media
+        .insert("https://example.com/chart.png".into(), "AgAD-file".into());
+
+    let rendered = renderer.render(&ir);
+    assert!(rendered.continuations.is_empty());
+    assert_eq!(rendered.payload["method"], "sendPhoto");
+    assert_eq!(rendered.payload["photo"], "AgAD-file");
+    assert_eq!(rendered.payload["caption"], "Chart");
+    assert!(rendered.payload["reply_markup"]["inline_keyboard"].is_array());
+}
+
+#[test]
+fn telegram_markdown_text_converts_to_html() {
+    let renderer = TelegramRenderer::default();
+    let ir = MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .title("Release")
+        .primary_text(
+            "Deploy **now** & see [notes](https://example.com/a?b=1&c=2)",
+            true,
+        )
+        .build();
+    let rendered = renderer.render(&ir);
+    let text = rendered.payload["text"].as_str().expect("text");
+    assert!(text.contains(
+        "Deploy <b>now</b> &amp; see <a href=\"https://example.com/a?b=1&amp;c=2\">notes</a>"
+    ));
+}
+
+#[test]
+fn telegram_long_body_splits_into_messages() {
+    let renderer = TelegramRenderer::default();
+    let paragraph = "Status update for the rollout. ".repeat(40);
+    let body = [paragraph.trim_end(); 6].join("\n\n");
+    let ir = MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .title("Report")
+        .primary_text(&body, false)
+        .postback("Ack", json!({"ok": true}))
+        .build();
+    let rendered = renderer.render(&ir);
+
+    let payloads: Vec<_> = rendered.payloads().collect();
+    assert!(payloads.len() > 1);
+    assert!(rendered.limit_exceeded);
+    assert!(rendered.warnings.iter().any(|w| w == "telegram.body_split"));
+    let total = payloads.len();
+    for (index, payload) in payloads.iter().enumerate() {
+        let text = payload["text"].as_str().expect("text");
+        assert!(text.chars().count() <= 4000);
+        assert!(text.ends_with(&format!("({}/{total})", index + 1)));
+        assert_eq!(payload.get("reply_markup").is_some(), index + 1 == total);
+    }
+}
+
 fn sample_ir(include_input: bool) -> MessageCardIr {
     let mut builder = MessageCardIrBuilder::default()
         .tier(Tier::Premium)
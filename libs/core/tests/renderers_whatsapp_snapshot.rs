@@ -1,6 +1,6 @@
 #![cfg(feature = "adaptive-cards")]
 
-use gsm_core::messaging_card::ir::{InputChoice, InputKind, IrAction};
+use gsm_core::messaging_card::ir::{InputChoice, InputKind, IrAction, TemplateMessage};
 use gsm_core::messaging_card::tier::Tier;
 use gsm_core::messaging_card::{MessageCardIr, MessageCardIrBuilder, WhatsAppRenderer};
 use gsm_core::{AppLink, PlatformRenderer};
@@ -49,6 +49,174 @@ fn whatsapp_inputs_downgraded() {
     );
 }
 
+#[test]
+fn whatsapp_postbacks_render_reply_buttons() {
+    let renderer = WhatsAppRenderer;
+    let ir = MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .title("Approve request?")
+        .primary_text("Expense #42 for 120 EUR", false)
+        .footer("Finance bot")
+        .postback("Approve", json!({"approve": true}))
+        .postback("Reject", json!({"approve": false}))
+        .build();
+    let rendered = renderer.render(&ir);
+    assert_eq!(
+        rendered.payload,
+        json!({
+            "type": "interactive",
+            "interactive": {
+                "type": "button",
+                "header": { "type": "text", "text": "Approve request?" },
+                "body": { "text": "Expense #42 for 120 EUR" },
+                "footer": { "text": "Finance bot" },
+                "action": {
+                    "buttons": [
+                        { "type": "reply", "reply": { "id": "{\"approve\":true}", "title": "Approve" } },
+                        { "type": "reply", "reply": { "id": "{\"approve\":false}", "title": "Reject" } }
+                    ]
+                }
+            }
+        })
+    );
+    assert!(rendered.warnings.is_empty());
+}
+
+#[test]
+fn whatsapp_long_reply_ids_fall_back_with_reason() {
+    let renderer = WhatsAppRenderer;
+    let ir = MessageCardIrBuilder::default()
+        .tier(Tier::Advanced)
+        .title("Approve request?")
+        .postback("Approve", json!({"request": "r".repeat(300)}))
+        .build();
+    let rendered = renderer.render(&ir);
+    assert_eq!(rendered.payload["type"], "WhatsAppTemplate");
+    assert!(
+        rendered
+            .warnings
+            .iter()
+            .any(|w| w == "whatsapp.reply_id_too_long")
+    );
+    assert!(
+        rendered
+            .warnings
+            .iter()
+            .any(|w| w == "whatsapp.interactive_fallback")
+    );
+}
+
+#[test]
+fn whatsapp_choice_inputs_render_list_sections() {
+    let renderer = WhatsAppRenderer;
+    let choices = (1..=12)
+        .map(|n| InputChoice {
+            title: format!("Slot {n}"),
+            value: format!("slot-{n}"),
+        })
+        .collect();
+    let ir = MessageCardIrBuilder::default()
+        .tier(Tier::Premium)
+        .title("Book a slot")
+        .input(
+            Some("Morning".into()),
+            InputKind::Choice,
+            Some("slot".into()),
+            choices,
+        )
+        .build();
+    let rendered = renderer.render(&ir);
+    let interactive = &rendered.payload["interactive"];
+    assert_eq!(interactive["type"], "list");
+    assert_eq!(interactive["body"]["text"], "Book a slot");
+    let sections = interactive["action"]["sections"]
+        .as_array()
+        .expect("sections");
+    assert_eq!(sections.len(), 1);
+    let rows = sections[0]["rows"].as_array().expect("rows");
+    assert_eq!(rows.len(), 10);
+    assert_eq!(
+        rows[0],
+        json!({"id": "{\"slot\":\"slot-1\"}", "title": "Slot 1"})
+    );
+    assert!(
+        rendered
+            .warnings
+            .iter()
+            .any(|w| w == "whatsapp.list_rows_truncated")
+    );
+}
+
+#[test]
+fn whatsapp_closed_window_uses_bound_template() {
+    let renderer = WhatsAppRenderer;
+    let mut ir = sample_ir(false, 2);
+    ir.meta.service_window_open = Some(false);
+    ir.meta.template = Some(TemplateMessage {
+        name: "order_update".into(),
+        language: "en_US".into(),
+        parameters: vec!["{{title}}".into(), "{{fact:Status}}".into(), "today".into()],
+    });
+    let rendered = renderer.render(&ir);
+    assert_eq!(
+        rendered.payload,
+        json!({
+            "type": "template",
+            "template": {
+                "name": "order_update",
+                "language": { "code": "en_US" },
+                "components": [{
+                    "type": "body",
+                    "parameters": [
+                        { "type": "text", "text": "WhatsApp Snapshot" },
+                        { "type": "text", "text": "Green" },
+                        { "type": "text", "text": "today" }
+                    ]
+                }]
+            }
+        })
+    );
+}
+
+#[test]
+fn whatsapp_closed_window_without_template_warns() {
+    let renderer = WhatsAppRenderer;
+    let mut ir = sample_ir(false, 2);
+    ir.meta.service_window_open = Some(false);
+    let rendered = renderer.render(&ir);
+    assert_eq!(rendered.payload, load_fixture("whatsapp/basic.json"));
+    assert!(
+        rendered
+            .warnings
+            .iter()
+            .any(|w| w == "whatsapp.service_window_closed")
+    );
+}
+
+#[test]
+fn whatsapp_uploaded_images_render_as_media_messages() {
+    let renderer = WhatsAppRenderer;
+    let mut ir = MessageCardIrBuilder::default()
+        .title("Receipt")
+        .primary_text("Thanks for your order", false)
+        .image(
+            "https://example.com/receipt.png".into(),
+            Some("Receipt".into()),
+        )
+        .build();
+    ir.meta
+        .media
+        .insert("https://example.com/receipt.png".into(), "wamid-1".into());
+
+    let rendered = renderer.render(&ir);
+    assert_eq!(
+        rendered.payload,
+        json!({"type": "image", "image": {"id": "wamid-1", "caption": "Receipt"}})
+    );
+    let body = rendered.continuations[0]["body"].as_str().expect("body");
+    assert!(!body.contains("receipt.png"), "{body}");
+}
+
 fn sample_ir(include_input: bool, action_count: usize) -> MessageCardIr {
     let mut builder = MessageCardIrBuilder::default()
         .tier(Tier::Premium)
@@ -3,10 +3,14 @@
 use std::fs;
 use std::sync::{Arc, Mutex, MutexGuard};
 
+use gsm_core::messaging_card::ir::IrAction;
 use gsm_core::messaging_card::renderers::override_url_allow_list;
-use gsm_core::messaging_card::{MessageCard, MessageCardEngine, TelemetryEvent, TelemetryHook};
+use gsm_core::messaging_card::{
+    InteractionOutcome, MessageCard, MessageCardEngine, MessageCardIrBuilder, TelemetryEvent,
+    TelemetryHook,
+};
 use once_cell::sync::Lazy;
-use serde_json::Value;
+use serde_json::{Value, json};
 
 #[test]
 fn url_allow_list_blocks_disallowed_links() {
@@ -51,6 +55,33 @@ fn url_allow_list_blocks_disallowed_links() {
     }
 }
 
+#[test]
+fn url_allow_list_applies_to_markdown_links() {
+    let _guard = AllowListGuard::new(Some(vec!["https://allowed.example/".into()]));
+    let engine = MessageCardEngine::bootstrap();
+    let ir = MessageCardIrBuilder::default()
+        .primary_text(
+            "Read [docs](https://allowed.example/docs), [this](https://blocked.example/phish) \
+             or [that](javascript:alert(1))",
+            true,
+        )
+        .build();
+
+    let output = engine
+        .render_card_snapshot("slack", &ir)
+        .expect("slack renders")
+        .output;
+    let text = output.payload["blocks"][0]["text"]["text"]
+        .as_str()
+        .expect("section text");
+    assert_eq!(
+        text,
+        "Read <https://allowed.example/docs|docs>, this or that"
+    );
+    assert_eq!(output.url_blocked_count, 1);
+    assert!(output.warnings.iter().any(|w| w == "url_blocked"));
+}
+
 #[test]
 fn sanitizer_strips_tags_and_records_metrics() {
     let _guard = AllowListGuard::new(None);
@@ -151,6 +182,72 @@ fn load_card(name: &str) -> MessageCard {
     serde_json::from_str(&data).expect("invalid MessageCard fixture")
 }
 
+#[test]
+fn postback_interactions_are_correlated_to_rendered_cards() {
+    let (hook, events) = recording_hook();
+    let engine = MessageCardEngine::bootstrap().with_telemetry(hook);
+    let mut ir = MessageCardIrBuilder::default()
+        .title("Expense")
+        .postback("Approve", json!({"decision": "approve"}))
+        .build();
+    ir.meta.card_id = Some("expense-7".into());
+
+    let snapshot = engine
+        .render_card_snapshot("slack", &ir)
+        .expect("slack renders");
+    let rendered_ir = snapshot.ir.expect("card snapshot keeps ir");
+    let IrAction::Postback { data, .. } = &rendered_ir.actions[0] else {
+        panic!("expected postback action");
+    };
+    let mut inbound = data.clone();
+
+    let outcome = engine.record_interaction("slack", &mut inbound);
+    assert!(matches!(outcome, InteractionOutcome::Recorded(_)));
+    assert_eq!(inbound, json!({"decision": "approve"}));
+    match last_event(&events) {
+        TelemetryEvent::ActionInvoked {
+            card_id,
+            action_id,
+            platform,
+            ..
+        } => {
+            assert_eq!(card_id, "expense-7");
+            assert_eq!(action_id, "0");
+            assert_eq!(platform, "slack");
+        }
+        other => panic!("expected action telemetry, got {other:?}"),
+    }
+
+    let mut stale = json!({"_gsm": {"c": "expense-7", "a": "0", "t": 0}});
+    let engine = engine.with_card_ttl(std::time::Duration::from_secs(60));
+    assert!(matches!(
+        engine.record_interaction("slack", &mut stale),
+        InteractionOutcome::Expired(_)
+    ));
+    assert!(matches!(
+        last_event(&events),
+        TelemetryEvent::CardExpired { .. }
+    ));
+}
+
+#[test]
+fn unknown_platform_emits_render_failed() {
+    let (hook, events) = recording_hook();
+    let engine = MessageCardEngine::bootstrap().with_telemetry(hook);
+    let mut ir = MessageCardIrBuilder::default().title("Hi").build();
+    ir.meta.card_id = Some("hello".into());
+    assert!(engine.render("pager", &ir).is_none());
+    match last_event(&events) {
+        TelemetryEvent::RenderFailed {
+            card_id, platform, ..
+        } => {
+            assert_eq!(card_id.as_deref(), Some("hello"));
+            assert_eq!(platform, "pager");
+        }
+        other => panic!("expected render failure telemetry, got {other:?}"),
+    }
+}
+
 fn last_event(events: &Arc<Mutex<Vec<TelemetryEvent>>>) -> TelemetryEvent {
     events
         .lock()
@@ -43,6 +43,12 @@ pub trait IdemStore: Send + Sync {
     /// key did not previously exist (meaning the caller should continue processing),
     /// `Ok(false)` for a duplicate, or an error when the store was unavailable.
     async fn put_if_absent(&self, key: &str, ttl_s: u64) -> Result<bool>;
+
+    /// Forgets `key` so a later `put_if_absent` succeeds again, e.g. when processing failed
+    /// after the key was registered. Stores that cannot delete keep it until the TTL expires.
+    async fn remove(&self, _key: &str) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Shared trait object wrapper.
@@ -79,6 +85,11 @@ impl IdemStore for InMemoryIdemStore {
             }
         }
     }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        self.inner.write().await.remove(key);
+        Ok(())
+    }
 }
 
 /// JetStream-backed idempotency store.
@@ -123,6 +134,13 @@ impl IdemStore for NatsKvIdemStore {
                 .context(format!("put idempotency key {key} with ttl {ttl_s}s"))),
         }
     }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        self.bucket
+            .delete(key)
+            .await
+            .with_context(|| format!("remove idempotency key {key}"))
+    }
 }
 
 /// Configuration derived at runtime.
@@ -210,6 +228,12 @@ impl IdempotencyGuard {
         }
         Ok(inserted)
     }
+
+    /// Releases a key registered by [`should_process`](Self::should_process) when the message
+    /// could not be processed, so a retry is not mistaken for a duplicate.
+    pub async fn release(&self, key: &IdKey) -> Result<()> {
+        self.store.remove(&key.to_string()).await
+    }
 }
 
 #[cfg(test)]
@@ -240,5 +264,7 @@ mod tests {
         };
         assert!(guard.should_process(&key).await.unwrap());
         assert!(!guard.should_process(&key).await.unwrap());
+        guard.release(&key).await.unwrap();
+        assert!(guard.should_process(&key).await.unwrap());
     }
 }
@@ -0,0 +1,376 @@
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration as StdDuration, Instant},
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+/// OpenID metadata document listing the keys that sign Bot Framework tokens.
+pub const BOT_FRAMEWORK_OPENID_METADATA_URL: &str =
+    "https://login.botframework.com/v1/.well-known/openidconfiguration";
+/// Issuer of tokens the Bot Framework connector sends to bots.
+pub const BOT_FRAMEWORK_ISSUER: &str = "https://api.botframework.com";
+
+const MIN_REFETCH_INTERVAL: StdDuration = StdDuration::from_secs(60);
+
+/// Where signing keys come from; swap in [`FileJwksSource`] for tests and air-gapped setups.
+#[async_trait]
+pub trait JwksSource: Send + Sync {
+    async fn fetch(&self) -> Result<JwkSet>;
+}
+
+/// Resolves `jwks_uri` from an OpenID metadata document and downloads the key set.
+#[derive(Clone)]
+pub struct OpenIdJwksSource {
+    client: reqwest::Client,
+    metadata_url: String,
+}
+
+impl OpenIdJwksSource {
+    pub fn new(metadata_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            metadata_url: metadata_url.into(),
+        }
+    }
+
+    pub fn bot_framework() -> Self {
+        Self::new(BOT_FRAMEWORK_OPENID_METADATA_URL)
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenIdMetadata {
+    jwks_uri: String,
+}
+
+#[async_trait]
+impl JwksSource for OpenIdJwksSource {
+    async fn fetch(&self) -> Result<JwkSet> {
+        let metadata: OpenIdMetadata = self
+            .client
+            .get(&self.metadata_url)
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .with_context(|| format!("fetch OpenID metadata from {}", self.metadata_url))?
+            .json()
+            .await
+            .context("parse OpenID metadata")?;
+        self.client
+            .get(&metadata.jwks_uri)
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .with_context(|| format!("fetch JWKS from {}", metadata.jwks_uri))?
+            .json()
+            .await
+            .context("parse JWKS")
+    }
+}
+
+/// Reads a JWKS document from disk on every fetch.
+#[derive(Debug, Clone)]
+pub struct FileJwksSource {
+    path: PathBuf,
+}
+
+impl FileJwksSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl JwksSource for FileJwksSource {
+    async fn fetch(&self) -> Result<JwkSet> {
+        let raw = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("read JWKS from {}", self.path.display()))?;
+        serde_json::from_str(&raw).with_context(|| format!("parse JWKS {}", self.path.display()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotFrameworkClaims {
+    pub iss: String,
+    pub aud: String,
+    pub exp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<i64>,
+    #[serde(
+        default,
+        rename = "serviceurl",
+        alias = "serviceUrl",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub service_url: Option<String>,
+}
+
+struct CachedKeys {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+/// Validates the `Authorization: Bearer <jwt>` header on activities sent by the Bot Framework
+/// connector: RS256 signature against the cached JWKS, issuer, audience (the bot's app id),
+/// expiry and the `serviceurl` claim.
+///
+/// Keys are cached for `refresh_after` (24h by default) and refetched early, at most once a
+/// minute, when a token names a key id the cache does not know, so key rotation does not need a
+/// restart.
+pub struct BotFrameworkValidator {
+    source: Arc<dyn JwksSource>,
+    issuer: String,
+    leeway_secs: u64,
+    refresh_after: StdDuration,
+    cache: RwLock<Option<CachedKeys>>,
+}
+
+impl BotFrameworkValidator {
+    pub fn new(source: Arc<dyn JwksSource>) -> Self {
+        Self {
+            source,
+            issuer: BOT_FRAMEWORK_ISSUER.to_string(),
+            leeway_secs: 300,
+            refresh_after: StdDuration::from_secs(24 * 60 * 60),
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// Validator backed by Microsoft's published Bot Framework metadata.
+    pub fn microsoft() -> Self {
+        Self::new(Arc::new(OpenIdJwksSource::bot_framework()))
+    }
+
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = issuer.into();
+        self
+    }
+
+    /// Clock skew tolerated on `exp` and `nbf`.
+    pub fn with_leeway_secs(mut self, leeway_secs: u64) -> Self {
+        self.leeway_secs = leeway_secs;
+        self
+    }
+
+    pub fn with_refresh_after(mut self, refresh_after: StdDuration) -> Self {
+        self.refresh_after = refresh_after;
+        self
+    }
+
+    /// Validates `authorization` (the raw header value) for the bot `app_id`. The token must
+    /// carry a `serviceurl` claim naming the activity's `service_url`.
+    pub async fn validate(
+        &self,
+        authorization: &str,
+        app_id: &str,
+        service_url: &str,
+    ) -> Result<BotFrameworkClaims> {
+        let token = authorization
+            .strip_prefix("Bearer ")
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| anyhow!("missing bearer token"))?;
+        let header = decode_header(token).context("invalid token header")?;
+        if header.alg != Algorithm::RS256 {
+            bail!("unexpected token algorithm {:?}", header.alg);
+        }
+        let kid = header.kid.ok_or_else(|| anyhow!("token has no key id"))?;
+        let key = self.decoding_key(&kid).await?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[self.issuer.as_str()]);
+        validation.set_audience(&[app_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+        validation.leeway = self.leeway_secs;
+        let claims = decode::<BotFrameworkClaims>(token, &key, &validation)
+            .context("token rejected")?
+            .claims;
+
+        let matches = claims
+            .service_url
+            .as_deref()
+            .is_some_and(|claimed| same_service_url(claimed, service_url));
+        if !matches {
+            bail!("token was not issued for service url {service_url}");
+        }
+        Ok(claims)
+    }
+
+    async fn decoding_key(&self, kid: &str) -> Result<DecodingKey> {
+        {
+            let cache = self.cache.read().await;
+            if let Some(cached) = cache.as_ref()
+                && cached.fetched_at.elapsed() < self.refresh_after
+                && let Some(jwk) = cached.keys.find(kid)
+            {
+                return DecodingKey::from_jwk(jwk).context("unusable signing key");
+            }
+            // Tokens with made-up key ids must not turn into a fetch per request.
+            if let Some(cached) = cache.as_ref()
+                && cached.keys.find(kid).is_none()
+                && cached.fetched_at.elapsed() < MIN_REFETCH_INTERVAL
+            {
+                bail!("unknown signing key {kid}");
+            }
+        }
+        let keys = self.source.fetch().await?;
+        let key = keys
+            .find(kid)
+            .map(DecodingKey::from_jwk)
+            .transpose()
+            .context("unusable signing key")?;
+        *self.cache.write().await = Some(CachedKeys {
+            keys,
+            fetched_at: Instant::now(),
+        });
+        key.ok_or_else(|| anyhow!("unknown signing key {kid}"))
+    }
+}
+
+fn same_service_url(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/')
+        .eq_ignore_ascii_case(b.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD as B64URL};
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use rand::thread_rng;
+    use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+    use rsa::traits::PublicKeyParts;
+    use rsa::{RsaPrivateKey, RsaPublicKey};
+    use time::OffsetDateTime;
+
+    const APP_ID: &str = "app-123";
+    const SERVICE_URL: &str = "https://smba.trafficmanager.net/emea/";
+
+    struct Fixture {
+        encoding: EncodingKey,
+        jwks_path: PathBuf,
+    }
+
+    fn fixture(name: &str) -> Fixture {
+        let private_key = RsaPrivateKey::new(&mut thread_rng(), 2048).expect("generate rsa key");
+        let public_key = RsaPublicKey::from(&private_key);
+        let jwks = serde_json::json!({
+            "keys": [{
+                "kty": "RSA",
+                "use": "sig",
+                "kid": "key-1",
+                "alg": "RS256",
+                "n": B64URL.encode(public_key.n().to_bytes_be()),
+                "e": B64URL.encode(public_key.e().to_bytes_be()),
+            }]
+        });
+        let jwks_path =
+            std::env::temp_dir().join(format!("gsm-security-{name}-{}.json", std::process::id()));
+        std::fs::write(&jwks_path, jwks.to_string()).expect("write jwks");
+        let pem = private_key
+            .to_pkcs8_pem(LineEnding::LF)
+            .expect("encode private");
+        Fixture {
+            encoding: EncodingKey::from_rsa_pem(pem.as_bytes()).expect("encoding key"),
+            jwks_path,
+        }
+    }
+
+    fn token(fixture: &Fixture, kid: &str, claims: serde_json::Value) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(kid.to_string());
+        format!(
+            "Bearer {}",
+            encode(&header, &claims, &fixture.encoding).expect("sign")
+        )
+    }
+
+    fn claims(aud: &str, exp_offset: i64) -> serde_json::Value {
+        serde_json::json!({
+            "iss": BOT_FRAMEWORK_ISSUER,
+            "aud": aud,
+            "exp": OffsetDateTime::now_utc().unix_timestamp() + exp_offset,
+            "serviceurl": SERVICE_URL,
+        })
+    }
+
+    #[tokio::test]
+    async fn accepts_tokens_signed_by_a_jwks_key() {
+        let fixture = fixture("accepts");
+        let validator =
+            BotFrameworkValidator::new(Arc::new(FileJwksSource::new(&fixture.jwks_path)));
+        let auth = token(&fixture, "key-1", claims(APP_ID, 600));
+
+        let claims = validator
+            .validate(&auth, APP_ID, "https://smba.trafficmanager.net/emea")
+            .await
+            .expect("valid token");
+        assert_eq!(claims.service_url.as_deref(), Some(SERVICE_URL));
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_audience_expiry_service_url_and_key() {
+        let fixture = fixture("rejects");
+        let validator =
+            BotFrameworkValidator::new(Arc::new(FileJwksSource::new(&fixture.jwks_path)))
+                .with_leeway_secs(0);
+
+        let other_app = token(&fixture, "key-1", claims("other-app", 600));
+        assert!(
+            validator
+                .validate(&other_app, APP_ID, SERVICE_URL)
+                .await
+                .is_err()
+        );
+
+        let expired = token(&fixture, "key-1", claims(APP_ID, -60));
+        assert!(
+            validator
+                .validate(&expired, APP_ID, SERVICE_URL)
+                .await
+                .is_err()
+        );
+
+        let valid = token(&fixture, "key-1", claims(APP_ID, 600));
+        assert!(
+            validator
+                .validate(&valid, APP_ID, "https://evil.example.com/")
+                .await
+                .is_err()
+        );
+
+        let mut unbound = claims(APP_ID, 600);
+        unbound.as_object_mut().unwrap().remove("serviceurl");
+        let unbound = token(&fixture, "key-1", unbound);
+        assert!(
+            validator
+                .validate(&unbound, APP_ID, SERVICE_URL)
+                .await
+                .is_err()
+        );
+
+        let unknown_key = token(&fixture, "key-2", claims(APP_ID, 600));
+        assert!(
+            validator
+                .validate(&unknown_key, APP_ID, SERVICE_URL)
+                .await
+                .is_err()
+        );
+
+        let wrong_issuer =
+            BotFrameworkValidator::new(Arc::new(FileJwksSource::new(&fixture.jwks_path)))
+                .with_issuer("https://login.example.com");
+        assert!(
+            wrong_issuer
+                .validate(&valid, APP_ID, SERVICE_URL)
+                .await
+                .is_err()
+        );
+    }
+}
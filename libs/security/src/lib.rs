@@ -1,9 +1,11 @@
+pub mod bot_framework;
 pub mod hash;
 pub mod jwt;
 pub mod links;
 pub mod middleware;
 pub mod nonce;
 
+pub use bot_framework::*;
 pub use hash::*;
 pub use jwt::*;
 pub use links::*;
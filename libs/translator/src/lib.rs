@@ -6,7 +6,9 @@
 
 use anyhow::{Result, anyhow};
 use gsm_core::messaging_card::MessageCardEngine;
-use gsm_core::{CardAction, CardBlock, MessageCard, OutKind, OutMessage};
+use gsm_core::{
+    CardAction, CardBlock, MessageCard, OutKind, OutMessage, RENDER_PLAN_META_KEY, RenderPlan,
+};
 use security::{
     hash::state_hash_out,
     jwt::{ActionClaims, JwtSigner},
@@ -50,10 +52,31 @@ pub fn secure_action_url(out: &OutMessage, title: &str, url: &str) -> String {
 static CARD_ENGINE: Lazy<MessageCardEngine> = Lazy::new(MessageCardEngine::bootstrap);
 static ACTION_LINK_CONFIG: Lazy<RwLock<Option<ActionLinkConfig>>> = Lazy::new(|| RwLock::new(None));
 
-pub(crate) fn render_via_engine(out: &OutMessage, platform: &str) -> Option<Value> {
-    let card = out.adaptive_card.as_ref()?;
-    let spec = CARD_ENGINE.render_spec(card).ok()?;
-    CARD_ENGINE.render_spec_payload(platform, &spec)
+/// Renders outbound cards through the card engine: every card egress attached a render plan
+/// to, plus rich cards (raw Adaptive Cards, OAuth prompts). Returns every payload to send, in
+/// order, since long content may be split across several messages. Open-URL actions are
+/// signed with [`secure_action_url`] first, as the per-platform builders do.
+pub(crate) fn render_via_engine(out: &OutMessage, platform: &str) -> Option<Vec<Value>> {
+    let plan = out
+        .meta
+        .get(RENDER_PLAN_META_KEY)
+        .and_then(|value| serde_json::from_value::<RenderPlan>(value.clone()).ok());
+    let mut card = out
+        .card
+        .clone()
+        .filter(|card| plan.is_some() || card.is_rich())?;
+    for action in card.actions.iter_mut() {
+        if let CardAction::OpenUrl { title, url, .. } = action {
+            *url = secure_action_url(out, title, url);
+        }
+    }
+    let spec = CARD_ENGINE.render_card_spec(&card).ok()?;
+    match plan {
+        Some(plan) => CARD_ENGINE
+            .render_planned(platform, &spec, &plan)
+            .map(|snapshot| snapshot.output.into_payloads()),
+        None => CARD_ENGINE.render_spec_payloads(platform, &spec),
+    }
 }
 
 #[derive(Clone)]
@@ -133,8 +156,7 @@ pub mod webex;
 ///     thread_id: None,
 ///     kind: OutKind::Text,
 ///     text: Some("Hello".into()),
-///     message_card: None,
-///     adaptive_card: None,
+///     card: None,
 ///     meta: Default::default(),
 /// };
 /// let translator = TelegramTranslator::new();
@@ -174,7 +196,7 @@ impl TelegramTranslator {
                     html_escape(label),
                     html_escape(value)
                 )),
-                CardBlock::Image { url } => parts.push(url.clone()),
+                CardBlock::Image { url, .. } => parts.push(url.clone()),
             }
         }
 
@@ -219,8 +241,8 @@ impl Default for TelegramTranslator {
 impl Translator for TelegramTranslator {
     fn to_platform(&self, out: &OutMessage) -> Result<Vec<Value>> {
         translate_with_span(out, "telegram", || {
-            if let Some(payload) = crate::render_via_engine(out, "telegram") {
-                return Ok(vec![payload]);
+            if let Some(payloads) = crate::render_via_engine(out, "telegram") {
+                return Ok(payloads);
             }
 
             match out.kind {
@@ -229,10 +251,7 @@ impl Translator for TelegramTranslator {
                     Ok(vec![Self::render_text(text)])
                 }
                 OutKind::Card => {
-                    let card = out
-                        .message_card
-                        .as_ref()
-                        .ok_or_else(|| anyhow!("missing card"))?;
+                    let card = out.card.as_ref().ok_or_else(|| anyhow!("missing card"))?;
                     Ok(Self::render_card(out, card))
                 }
             }
@@ -283,8 +302,7 @@ impl Default for WebChatTranslator {
 ///     thread_id: None,
 ///     kind: OutKind::Text,
 ///     text: Some("Hello WebChat".into()),
-///     message_card: None,
-///     adaptive_card: None,
+///     card: None,
 ///     meta: Default::default(),
 /// };
 ///
@@ -298,8 +316,8 @@ impl Default for WebChatTranslator {
 impl Translator for WebChatTranslator {
     fn to_platform(&self, out: &OutMessage) -> Result<Vec<Value>> {
         translate_with_span(out, "webchat", || {
-            if let Some(payload) = crate::render_via_engine(out, "webchat") {
-                return Ok(vec![payload]);
+            if let Some(payloads) = crate::render_via_engine(out, "webchat") {
+                return Ok(payloads);
             }
 
             let payload = match out.kind {
@@ -308,10 +326,7 @@ impl Translator for WebChatTranslator {
                   "text": out.text.clone().unwrap_or_default(),
                 }),
                 OutKind::Card => {
-                    let mut card = out
-                        .message_card
-                        .clone()
-                        .ok_or_else(|| anyhow!("missing card"))?;
+                    let mut card = out.card.clone().ok_or_else(|| anyhow!("missing card"))?;
                     for action in card.actions.iter_mut() {
                         if let CardAction::OpenUrl { title, url, .. } = action {
                             let signed = secure_action_url(out, title, url);
@@ -373,9 +388,7 @@ mod tests {
             thread_id: None,
             kind,
             text: None,
-            message_card: None,
-
-            adaptive_card: None,
+            card: None,
             meta: Default::default(),
         }
     }
@@ -403,7 +416,7 @@ mod tests {
         let mut out = sample_out_message(OutKind::Card);
         let _guard = ACTION_LINK_TEST_LOCK.lock().expect("action link lock");
         clear_action_link_config();
-        out.message_card = Some(MessageCard {
+        out.card = Some(MessageCard {
             title: Some("Weather".into()),
             body: vec![
                 CardBlock::Text {
@@ -426,6 +439,7 @@ mod tests {
                     data: json!({"ok": true}),
                 },
             ],
+            ..Default::default()
         });
 
         let translator = TelegramTranslator::new();
@@ -465,7 +479,7 @@ mod tests {
             signer.clone(),
         ));
         let mut out = sample_out_message(OutKind::Card);
-        out.message_card = Some(MessageCard {
+        out.card = Some(MessageCard {
             title: None,
             body: vec![],
             actions: vec![CardAction::OpenUrl {
@@ -473,6 +487,7 @@ mod tests {
                 url: "https://example.com/path".into(),
                 jwt: true,
             }],
+            ..Default::default()
         });
 
         let translator = TelegramTranslator::new();
@@ -491,6 +506,55 @@ mod tests {
         clear_action_link_config();
     }
 
+    #[test]
+    fn planned_card_actions_are_signed_when_configured() {
+        let _guard = ACTION_LINK_TEST_LOCK.lock().expect("action link lock");
+        let signer = JwtSigner::from_config(JwtConfig::hs256("signing-secret")).expect("signer");
+        set_action_link_config(ActionLinkConfig::with_default_ttl(
+            "https://actions.test/a",
+            signer.clone(),
+        ));
+        let mut out = sample_out_message(OutKind::Card);
+        out.card = Some(MessageCard {
+            title: Some("Report".into()),
+            actions: vec![CardAction::OpenUrl {
+                title: "Open".into(),
+                url: "https://example.com/path".into(),
+                jwt: true,
+            }],
+            ..Default::default()
+        });
+        let caps = gsm_core::ProviderCapabilitiesV1 {
+            supports_buttons: true,
+            ..Default::default()
+        };
+        gsm_core::attach_render_plan(
+            &mut out,
+            gsm_core::RenderMode::Planned,
+            "telegram",
+            &MessageCardEngine::bootstrap(),
+            &gsm_core::ProviderCapsRegistry::new(),
+            Some(&caps),
+            &gsm_core::PlannerPolicy::default(),
+        )
+        .unwrap()
+        .expect("planned");
+
+        let payloads = TelegramTranslator::new().to_platform(&out).unwrap();
+        let rendered = serde_json::to_string(&payloads).unwrap();
+        assert!(!rendered.contains("\"https://example.com/path\""));
+        let token = rendered
+            .split("https://actions.test/a?action=")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .expect("signed link");
+        let claims = signer
+            .verify(&urlencoding::decode(token).expect("decode token"))
+            .expect("claims");
+        assert_eq!(claims.redirect.as_deref(), Some("https://example.com/path"));
+        clear_action_link_config();
+    }
+
     #[test]
     fn webchat_text_payload() {
         let mut out = sample_out_message(OutKind::Text);
@@ -512,17 +576,18 @@ mod tests {
     #[test]
     fn webchat_card_payload() {
         let mut out = sample_out_message(OutKind::Card);
-        out.message_card = Some(MessageCard {
+        out.card = Some(MessageCard {
             title: Some("Title".into()),
             body: vec![CardBlock::Text {
                 text: "Hello".into(),
                 markdown: true,
             }],
             actions: vec![],
+            ..Default::default()
         });
 
         out.platform = Platform::WebChat;
-        let expected_card = out.message_card.clone();
+        let expected_card = out.card.clone();
 
         let translator = WebChatTranslator::new();
         let payloads = translator.to_platform(&out).unwrap();
@@ -560,6 +625,7 @@ mod tests {
                 url: "https://example.com".into(),
                 jwt: false,
             }],
+            ..Default::default()
         };
 
         let mut out = sample_out_message(OutKind::Card);
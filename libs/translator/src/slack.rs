@@ -10,8 +10,8 @@ const MAX_ACTIONS_PER_BLOCK: usize = 5;
 
 pub fn to_slack_payloads(out: &OutMessage) -> Result<Vec<Value>> {
     translate_with_span(out, "slack", || {
-        if let Some(payload) = crate::render_via_engine(out, "slack") {
-            return Ok(vec![payload]);
+        if let Some(payloads) = crate::render_via_engine(out, "slack") {
+            return Ok(payloads);
         }
 
         let thread_ts = out.thread_id.as_deref();
@@ -23,7 +23,7 @@ pub fn to_slack_payloads(out: &OutMessage) -> Result<Vec<Value>> {
             }
             OutKind::Card => {
                 let card = out
-                    .message_card
+                    .card
                     .as_ref()
                     .context("missing card for OutKind::Card")?;
                 let blocks = card_to_blocks(card, out)?;
@@ -79,12 +79,12 @@ fn card_to_blocks(card: &MessageCard, out: &OutMessage) -> Result<Vec<Value>> {
             CardBlock::Fact { label, value } => {
                 fact_lines.push(format!("• *{label}*: {value}"));
             }
-            CardBlock::Image { url } => {
+            CardBlock::Image { url, alt } => {
                 flush_facts(&mut fact_lines, &mut blocks);
                 blocks.push(json!({
                   "type": "image",
                   "image_url": url,
-                  "alt_text": "image"
+                  "alt_text": alt.as_deref().unwrap_or("image")
                 }));
             }
         }
@@ -155,9 +155,7 @@ mod tests {
             thread_id: Some("1710000000.000100".into()),
             kind,
             text: None,
-            message_card: None,
-
-            adaptive_card: None,
+            card: None,
             meta: Default::default(),
         }
     }
@@ -178,7 +176,7 @@ mod tests {
     #[test]
     fn card_payload_builds_blocks_and_actions() {
         let mut out = base_message(OutKind::Card);
-        out.message_card = Some(MessageCard {
+        out.card = Some(MessageCard {
             title: Some("Status Update".into()),
             body: vec![
                 CardBlock::Text {
@@ -191,6 +189,7 @@ mod tests {
                 },
                 CardBlock::Image {
                     url: "https://example.com/image.png".into(),
+                    alt: None,
                 },
             ],
             actions: vec![
@@ -204,6 +203,7 @@ mod tests {
                     data: serde_json::json!({"ok": true}),
                 },
             ],
+            ..Default::default()
         });
 
         let payloads = to_slack_payloads(&out).unwrap();
@@ -220,7 +220,7 @@ mod tests {
     #[test]
     fn card_payload_paginates_blocks() {
         let mut out = base_message(OutKind::Card);
-        out.message_card = Some(MessageCard {
+        out.card = Some(MessageCard {
             title: Some("Large".into()),
             body: (0..95)
                 .map(|i| CardBlock::Text {
@@ -229,6 +229,7 @@ mod tests {
                 })
                 .collect(),
             actions: vec![],
+            ..Default::default()
         });
 
         let payloads = to_slack_payloads(&out).unwrap();
@@ -248,7 +249,7 @@ mod tests {
     #[test]
     fn actions_chunk_to_multiple_blocks() {
         let mut out = base_message(OutKind::Card);
-        out.message_card = Some(MessageCard {
+        out.card = Some(MessageCard {
             title: None,
             body: vec![CardBlock::Text {
                 text: "Actions".into(),
@@ -261,6 +262,7 @@ mod tests {
                     jwt: false,
                 })
                 .collect(),
+            ..Default::default()
         });
 
         let payloads = to_slack_payloads(&out).unwrap();
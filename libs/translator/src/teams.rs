@@ -24,6 +24,7 @@ use serde_json::{Value, json};
 ///             jwt: false,
 ///         }
 ///     ],
+///     ..Default::default()
 /// };
 ///
 /// let out = gsm_core::OutMessage {
@@ -34,8 +35,7 @@ use serde_json::{Value, json};
 ///     thread_id: None,
 ///     kind: gsm_core::OutKind::Card,
 ///     text: None,
-///     message_card: None,
-///     adaptive_card: None,
+///     card: None,
 ///     meta: Default::default(),
 /// };
 /// let card_payload = to_teams_adaptive(&card, &out).unwrap();
@@ -62,8 +62,12 @@ pub fn to_teams_adaptive(card: &MessageCard, out: &OutMessage) -> Result<Value>
                 CardBlock::Fact { label, value } => {
                     facts.push(json!({"title": label, "value": value}));
                 }
-                CardBlock::Image { url } => {
-                    body.push(json!({"type":"Image","url":url}));
+                CardBlock::Image { url, alt } => {
+                    let mut image = json!({"type":"Image","url":url});
+                    if let Some(alt) = alt {
+                        image["altText"] = json!(alt);
+                    }
+                    body.push(image);
                 }
             }
         }
@@ -94,6 +94,10 @@ fn adaptive_to_card(value: &Value) -> Result<MessageCard> {
                 if let Some(url) = element.get("url").and_then(|v| v.as_str()) {
                     blocks.push(CardBlock::Image {
                         url: url.to_string(),
+                        alt: element
+                            .get("altText")
+                            .and_then(|v| v.as_str())
+                            .map(str::to_string),
                     });
                 }
             }
@@ -166,6 +170,7 @@ fn adaptive_to_card(value: &Value) -> Result<MessageCard> {
         title,
         body: blocks,
         actions,
+        ..Default::default()
     })
 }
 
@@ -14,7 +14,8 @@ fn build_payload(out: &OutMessage) -> Result<Value> {
     let mut map = serde_json::Map::new();
     map.insert("roomId".into(), Value::String(out.chat_id.clone()));
 
-    if let Some(content) = render_via_engine(out, "webex") {
+    // The Webex renderer emits a single card, so only the first payload is used.
+    if let Some(content) = render_via_engine(out, "webex").and_then(|p| p.into_iter().next()) {
         if let Some(text) = out.text.clone()
             && !text.is_empty()
         {
@@ -38,7 +39,7 @@ fn build_payload(out: &OutMessage) -> Result<Value> {
         }
         OutKind::Card => {
             let card = out
-                .message_card
+                .card
                 .clone()
                 .ok_or_else(|| anyhow!("missing message card for card payload"))?;
             let markdown = out.text.clone().unwrap_or_default();
@@ -86,11 +87,15 @@ fn card_to_adaptive(out: &OutMessage, card: MessageCard) -> Result<Value> {
                     "facts": [{"title": label, "value": value}],
                 }));
             }
-            CardBlock::Image { url } => {
-                body.push(json!({
+            CardBlock::Image { url, alt } => {
+                let mut image = json!({
                     "type": "Image",
                     "url": url,
-                }));
+                });
+                if let Some(alt) = alt {
+                    image["altText"] = json!(alt);
+                }
+                body.push(image);
             }
         }
     }
@@ -139,9 +144,7 @@ mod tests {
             thread_id: None,
             kind,
             text: Some("Hello".into()),
-            message_card: card,
-
-            adaptive_card: None,
+            card,
             meta: Default::default(),
         }
     }
@@ -168,6 +171,7 @@ mod tests {
                 url: "https://example.com".into(),
                 jwt: false,
             }],
+            ..Default::default()
         };
         let out = sample_out(OutKind::Card, Some(card));
         let payload = to_webex_payload(&out).expect("payload");
@@ -11,9 +11,7 @@ fn base_out_message() -> OutMessage {
         thread_id: None,
         kind: OutKind::Card,
         text: None,
-        message_card: None,
-
-        adaptive_card: None,
+        card: None,
         meta: Default::default(),
     }
 }
@@ -31,8 +29,8 @@ fn adaptive_card_contract() {
 
     for case in cases {
         let mut out = base_out_message();
-        out.message_card = Some(load_card_fixture(case));
-        let payload = to_teams_adaptive(out.message_card.as_ref().unwrap(), &out)
+        out.card = Some(load_card_fixture(case));
+        let payload = to_teams_adaptive(out.card.as_ref().unwrap(), &out)
             .expect("translate to adaptive card");
 
         assert_matches_schema(SCHEMA, &payload).expect("adaptive payload to match schema");
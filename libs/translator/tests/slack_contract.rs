@@ -11,9 +11,7 @@ fn base_out_message() -> OutMessage {
         thread_id: Some("1700000000.900100".into()),
         kind: OutKind::Card,
         text: None,
-        message_card: None,
-
-        adaptive_card: None,
+        card: None,
         meta: Default::default(),
     }
 }
@@ -31,7 +29,7 @@ fn slack_card_contract() {
 
     for case in cases {
         let mut out = base_out_message();
-        out.message_card = Some(load_card_fixture(case));
+        out.card = Some(load_card_fixture(case));
         let payloads = to_slack_payloads(&out).expect("translate to slack payload");
 
         for payload in &payloads {
@@ -14,9 +14,7 @@ fn telegram_text_snapshot() {
         thread_id: None,
         kind: OutKind::Text,
         text: Some("Hello <Greentic>".into()),
-        message_card: None,
-
-        adaptive_card: None,
+        card: None,
         meta: Default::default(),
     };
     let payloads = t.to_platform(&out).unwrap();
@@ -34,7 +32,7 @@ fn telegram_card_snapshot() {
         thread_id: None,
         kind: OutKind::Card,
         text: None,
-        message_card: Some(MessageCard {
+        card: Some(MessageCard {
             title: Some("Weather".into()),
             body: vec![
                 CardBlock::Text {
@@ -57,8 +55,8 @@ fn telegram_card_snapshot() {
                     data: serde_json::json!({"a":"b"}),
                 },
             ],
+            ..Default::default()
         }),
-        adaptive_card: None,
         meta: Default::default(),
     };
     let payloads = t.to_platform(&out).unwrap();
@@ -76,15 +74,15 @@ fn webchat_card_snapshot() {
         thread_id: None,
         kind: OutKind::Card,
         text: None,
-        message_card: Some(MessageCard {
+        card: Some(MessageCard {
             title: Some("Weather".into()),
             body: vec![CardBlock::Text {
                 text: "Hi".into(),
                 markdown: true,
             }],
             actions: vec![],
+            ..Default::default()
         }),
-        adaptive_card: None,
         meta: Default::default(),
     };
     let payloads = t.to_platform(&out).unwrap();
@@ -16,9 +16,7 @@ fn outbound_text_payload() {
         thread_id: None,
         kind: OutKind::Text,
         text: Some("Hello".into()),
-        message_card: None,
-
-        adaptive_card: None,
+        card: None,
         meta: Default::default(),
     };
     let payload = to_webex_payload(&out).expect("payload");
@@ -39,6 +37,7 @@ fn outbound_card_payload() {
             url: "https://example.com".into(),
             jwt: false,
         }],
+        ..Default::default()
     };
     let out = OutMessage {
         ctx: make_tenant_ctx("acme".into(), None, None),
@@ -48,9 +47,7 @@ fn outbound_card_payload() {
         thread_id: None,
         kind: OutKind::Card,
         text: Some("Intro".into()),
-        message_card: Some(card),
-
-        adaptive_card: None,
+        card: Some(card),
         meta: Default::default(),
     };
     let payload = to_webex_payload(&out).expect("payload");
@@ -73,9 +70,7 @@ fn outbound_via_translator_struct() {
         thread_id: None,
         kind: OutKind::Text,
         text: Some("Hi".into()),
-        message_card: None,
-
-        adaptive_card: None,
+        card: None,
         meta: Default::default(),
     };
     let payloads = translator.to_platform(&out).expect("payloads");